@@ -74,6 +74,21 @@ pub async fn put(
     Ok(())
 }
 
+/// Put `value` only if `key` doesn't exist yet, returns whether it's created.
+pub async fn create(client: &mut EtcdClient, key: &str, value: impl Serialize) -> Result<bool> {
+    let value = serde_json::to_string(&value)
+        .map_err(|err| EtcdError::new("Failed to serialize".into(), Some(err.to_string())))?;
+
+    let txn = Txn::new()
+        .when([Compare::create_revision(key, CompareOp::Equal, 0)])
+        .and_then([TxnOp::put(key, value, None)]);
+    let res = client
+        .txn(txn)
+        .await
+        .map_err(|err| EtcdError::new("Failed to create".into(), Some(err.to_string())))?;
+    Ok(res.succeeded())
+}
+
 pub async fn forward_watch_to_ws<T: Object>(
     socket: WebSocket,
     watcher: Watcher,
@@ -1,20 +1,20 @@
 use std::sync::Arc;
 
-use axum::{Extension, Json};
+use axum::{extract::Query, Extension, Json};
 use axum_macros::debug_handler;
 use resources::{
-    models::{ErrResponse, Response},
+    models::ErrResponse,
     objects::{
-        node::NodeAddressType,
-        pod::{PodCondition, PodConditionType},
-        KubeObject, Object,
+        condition::Condition, node::NodeAddressType, pod::PodConditionType, KubeObject, Object,
     },
 };
 
 use crate::{
     handler::{
         response::HandlerResult,
-        utils::{etcd_get_object, etcd_put},
+        utils::{
+            etcd_get_object, etcd_put, fill_system_metadata, mutation_response, MutationParams,
+        },
     },
     AppState,
 };
@@ -23,8 +23,10 @@ use crate::{
 #[allow(dead_code)]
 pub async fn bind(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // check payload
     if let KubeObject::Binding(binding) = &payload {
         // get node
@@ -57,12 +59,9 @@ pub async fn bind(
         // update pod
         if let KubeObject::Pod(ref mut pod) = object {
             let mut status = &mut pod.status.as_mut().expect("Pod should have a status");
-            status.conditions.insert(
-                PodConditionType::PodScheduled,
-                PodCondition {
-                    status: true,
-                },
-            );
+            status
+                .conditions
+                .set(Condition::new(PodConditionType::PodScheduled, true));
             status.host_ip = node
                 .status
                 .addresses
@@ -80,10 +79,16 @@ pub async fn bind(
             ));
         }
         // put it back
-        etcd_put(&app_state, &object).await?;
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some("bind successfully".to_string()), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut object).await?;
+        if !dry_run {
+            etcd_put(&app_state, &object).await?;
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            "bind successfully".to_string(),
+            object,
+            dry_run,
+        ))
     } else {
         let res = ErrResponse::new(
             "bind error".to_string(),
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Multipart, Path, WebSocketUpgrade},
+    extract::{Multipart, Path, Query, WebSocketUpgrade},
     response::IntoResponse,
     Extension, Json,
 };
@@ -17,10 +17,12 @@ use crate::{etcd::forward_watch_to_ws, AppState};
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     mut multipart: Multipart,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     let mut payload: Option<KubeObject> = None;
-    let mut filename: Option<String> = None;
+    let mut code_file: Option<CodeFile> = None;
     while let Some(field) = multipart.next_field().await.unwrap() {
         let name = field.name().map_or("".to_string(), |n| n.to_owned());
         match name.as_str() {
@@ -28,7 +30,7 @@ pub async fn create(
                 payload = Some(decode_field_json(field).await?);
             },
             "code" => {
-                filename = Some(store_code_file(field).await?);
+                code_file = Some(read_code_file(field).await?);
             },
             _ => {},
         }
@@ -37,37 +39,48 @@ pub async fn create(
     let mut payload = payload.ok_or_else(|| {
         ErrResponse::bad_request("Function field is not presented".to_string(), None)
     })?;
-    let filename = filename
+    let code_file = code_file
         .ok_or_else(|| ErrResponse::bad_request("Code field is not presented".to_string(), None))?;
 
     if let KubeObject::Function(ref mut function) = payload {
         // get function name
+        resolve_name(&app_state, &function.prefix(), &mut function.metadata).await?;
         let name = function.metadata.name.to_owned();
 
         // create service object
         let svc_name = unique_name(&format!("func-{}", name));
-        let service = KubeObject::Service(Service::from_function(
+        let mut service = KubeObject::Service(Service::from_function(
             &svc_name,
             name.as_str(),
-            gen_service_ip(&app_state),
+            gen_service_ip(&app_state, dry_run),
         ));
-        etcd_put(&app_state, &service).await?;
 
         // create function object
-        function.init(service.uri(), filename);
-        etcd_put(&app_state, &payload).await?;
+        function.init(service.uri(), code_file.filename.to_owned());
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if dry_run {
+            return Ok(create_response(
+                format!("function/{} created", name),
+                payload,
+                dry_run,
+            ));
+        }
+        code_file.persist().await?;
+        etcd_create(&app_state, &payload).await?;
+        fill_system_metadata(&app_state, &mut service).await?;
+        etcd_put(&app_state, &service).await?;
 
         let mut i = 0;
-        loop {
-            let function = etcd_get_object(&app_state, payload.uri(), Some(payload.kind())).await?;
-            if let KubeObject::Function(function) = function {
-                if let Some(image) = function.status.unwrap().image {
+        let created = loop {
+            let object = etcd_get_object(&app_state, payload.uri(), Some(payload.kind())).await?;
+            if let KubeObject::Function(ref function) = object {
+                if let Some(ref image) = function.status.as_ref().unwrap().image {
                     tracing::info!(
                         "Image for function {} has been created, name: {}",
                         name,
                         image
                     );
-                    break;
+                    break object;
                 }
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
@@ -82,10 +95,13 @@ pub async fn create(
                 ));
             }
             i += 1;
-        }
+        };
 
-        let res = Response::new(Some(format!("function/{} created", name)), None);
-        Ok(Json(res))
+        Ok(create_response(
+            format!("function/{} created", name),
+            created,
+            dry_run,
+        ))
     } else {
         Err(ErrResponse::new(
             String::from("Error creating function"),
@@ -155,13 +171,21 @@ pub async fn list(
 #[debug_handler]
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::Function(_) = payload {
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("functions/{} updated", payload.name())), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("functions/{} updated", payload.name()),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -175,16 +199,25 @@ pub async fn update(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(name): Path<String>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     let function = etcd_get_object(
         &app_state,
         format!("/api/v1/functions/{}", name),
         Some("function"),
     )
     .await?;
-    if let KubeObject::Function(func) = function {
-        if let Some(status) = func.status {
-            etcd_delete(&app_state, status.service_ref)
+    if dry_run {
+        return Ok(mutation_response(
+            format!("functions/{} deleted", name),
+            function,
+            dry_run,
+        ));
+    }
+    if let KubeObject::Function(ref func) = function {
+        if let Some(ref status) = func.status {
+            etcd_delete(&app_state, status.service_ref.to_owned())
                 .await
                 .unwrap_or_else(|_| tracing::error!("Error deleting service"));
         }
@@ -199,6 +232,9 @@ pub async fn delete(
         .unwrap_or_else(|_| tracing::error!("Error deleting HPA"));
     }
     etcd_delete(&app_state, format!("/api/v1/functions/{}", name)).await?;
-    let res = Response::new(Some(format!("functions/{} deleted", name)), None);
-    Ok(Json(res))
+    Ok(mutation_response(
+        format!("functions/{} deleted", name),
+        function,
+        dry_run,
+    ))
 }
@@ -1,7 +1,7 @@
 use std::{str::FromStr, sync::Arc};
 
 use axum::{
-    extract::{Multipart, Path, WebSocketUpgrade},
+    extract::{Multipart, Path, Query, WebSocketUpgrade},
     http::Request,
     response::IntoResponse,
     Extension, Json,
@@ -22,10 +22,12 @@ use crate::{etcd::forward_watch_to_ws, AppState};
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     mut multipart: Multipart,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     let mut gpu_job: Option<KubeObject> = None;
-    let mut filename: Option<String> = None;
+    let mut code_file: Option<CodeFile> = None;
     while let Some(field) = multipart.next_field().await.unwrap() {
         let name = field.name().map_or("".to_string(), |n| n.to_owned());
         match name.as_str() {
@@ -33,7 +35,7 @@ pub async fn create(
                 gpu_job = Some(decode_field_json(field).await?);
             },
             "code" => {
-                filename = Some(store_code_file(field).await?);
+                code_file = Some(read_code_file(field).await?);
             },
             _ => {},
         }
@@ -41,28 +43,29 @@ pub async fn create(
 
     let mut gpu_job = gpu_job
         .ok_or_else(|| ErrResponse::bad_request("Job field is not presented".to_string(), None))?;
-    let filename = filename
+    let code_file = code_file
         .ok_or_else(|| ErrResponse::bad_request("Code field is not presented".to_string(), None))?;
 
     if let KubeObject::GpuJob(ref mut job) = gpu_job {
-        let job_name = &job.metadata.name.to_owned();
-        let result = etcd_get_object(&app_state, job.uri(), Some("gpujob")).await;
-        if result.is_ok() {
-            return Err(ErrResponse::new(
-                String::from("Error creating gpu job"),
-                Some(format!("Gpu job {} already exists", job_name)),
-            ));
-        }
+        resolve_name(&app_state, &job.prefix(), &mut job.metadata).await?;
+        let job_name = job.metadata.name.to_owned();
         job.metadata.uid = Some(Uuid::new_v4());
         let status = GpuJobStatus {
-            filename: Some(filename),
+            filename: Some(code_file.filename.to_owned()),
             ..Default::default()
         };
         job.status = Some(status);
 
-        etcd_put(&app_state, &gpu_job).await?;
-        let res = Response::new(Some(format!("gpujob/{} created", job_name)), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut gpu_job).await?;
+        if !dry_run {
+            code_file.persist().await?;
+            etcd_create(&app_state, &gpu_job).await?;
+        }
+        Ok(create_response(
+            format!("gpujob/{} created", job_name),
+            gpu_job,
+            dry_run,
+        ))
     } else {
         return Err(ErrResponse::new(
             String::from("Error creating gpujob"),
@@ -90,22 +93,39 @@ pub async fn get(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(job_name): Path<String>,
-) -> HandlerResult<()> {
-    etcd_delete(&app_state, format!("/api/v1/gpujobs/{}", job_name)).await?;
-    let res = Response::new(Some(format!("gpujob/{} deleted", job_name)), None);
-    Ok(Json(res))
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/gpujobs/{}", job_name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("gpujob")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("gpujob/{} deleted", job_name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(job_name): Path<String>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     if let KubeObject::GpuJob(_) = payload {
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("gpujob/{} updated", job_name)), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("gpujob/{} updated", job_name),
+            payload,
+            dry_run,
+        ))
     } else {
         Err(ErrResponse::new(
             String::from("Error updating gpujob"),
@@ -118,8 +138,10 @@ pub async fn update(
 pub async fn patch(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(job_name): Path<String>,
+    Query(params): Query<MutationParams>,
     Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     let mut object = etcd_get_object(
         &app_state,
         format!("/api/v1/gpujobs/{}", job_name),
@@ -129,9 +151,15 @@ pub async fn patch(
     match (&payload, &mut object) {
         (KubeObject::GpuJob(payload_job), KubeObject::GpuJob(ref mut job)) => {
             job.spec = payload_job.spec.to_owned();
-            etcd_put(&app_state, &object).await?;
-            let res = Response::new(Some(format!("gpujob/{} patched", job_name)), None);
-            Ok(Json(res))
+            fill_system_metadata(&app_state, &mut object).await?;
+            if !dry_run {
+                etcd_put(&app_state, &object).await?;
+            }
+            Ok(mutation_response(
+                format!("gpujob/{} patched", job_name),
+                object,
+                dry_run,
+            ))
         },
         _ => Err(ErrResponse::new(
             String::from("Error patching gpujob"),
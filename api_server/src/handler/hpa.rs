@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, WebSocketUpgrade},
+    extract::{Path, Query, WebSocketUpgrade},
     response::IntoResponse,
     Extension, Json,
 };
@@ -18,21 +18,14 @@ use crate::{etcd::forward_watch_to_ws, AppState};
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::HorizontalPodAutoscaler(mut hpa) = payload {
+        resolve_name(&app_state, &hpa.prefix(), &mut hpa.metadata).await?;
         let hpa_name = hpa.metadata.name.to_owned();
-        let result = etcd_get_object(&app_state, hpa.uri(), Some("horizontalpodautoscaler")).await;
-        if result.is_ok() {
-            return Err(ErrResponse::new(
-                String::from("Error creating horizontal pod autoscaler"),
-                Some(format!(
-                    "Horizontal pod autoscaler {} already exists",
-                    hpa_name
-                )),
-            ));
-        }
         hpa.metadata.uid = Some(Uuid::new_v4());
         let target_kind = hpa.spec.scale_target_ref.kind.to_lowercase();
         let target = etcd_get_object(
@@ -52,13 +45,19 @@ pub async fn create(
                         current_replicas: rs_status.replicas,
                         desired_replicas: rs_status.replicas,
                         last_scale_time: None,
+                        observed_generation: 0,
+                        conditions: Default::default(),
                     });
-                    etcd_put(&app_state, &KubeObject::HorizontalPodAutoscaler(hpa)).await?;
-                    let res = Response::new(
-                        Some(format!("horizontalpodautoscaler/{} created", hpa_name)),
-                        None,
-                    );
-                    Ok(Json(res))
+                    let mut object = KubeObject::HorizontalPodAutoscaler(hpa);
+                    fill_system_metadata(&app_state, &mut object).await?;
+                    if !dry_run {
+                        etcd_create(&app_state, &object).await?;
+                    }
+                    Ok(create_response(
+                        format!("horizontalpodautoscaler/{} created", hpa_name),
+                        object,
+                        dry_run,
+                    ))
                 },
                 _ => Err(ErrResponse::new(
                     String::from("Error creating horizontal pod autoscaler"),
@@ -107,25 +106,30 @@ pub async fn get(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(hpa_name): Path<String>,
-) -> HandlerResult<()> {
-    etcd_delete(
-        &app_state,
-        format!("/api/v1/horizontalpodautoscalers/{}", hpa_name),
-    )
-    .await?;
-    let res = Response::new(
-        Some(format!("horizontalpodautoscaler/{} deleted", hpa_name)),
-        None,
-    );
-    Ok(Json(res))
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/horizontalpodautoscalers/{}", hpa_name);
+    let object =
+        etcd_get_object(&app_state, uri.to_owned(), Some("horizontalpodautoscaler")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("horizontalpodautoscaler/{} deleted", hpa_name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(hpa_name): Path<String>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // Ensure object exists, otherwise deleted object will be created:
     // Controller started processing -> User deleted object -> Controller update status
     etcd_get_object(
@@ -135,12 +139,15 @@ pub async fn update(
     )
     .await?;
     if let KubeObject::HorizontalPodAutoscaler(_) = payload {
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(
-            Some(format!("horizontalpodautoscaler/{} updated", hpa_name)),
-            None,
-        );
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("horizontalpodautoscaler/{} updated", hpa_name),
+            payload,
+            dry_run,
+        ))
     } else {
         Err(ErrResponse::new(
             String::from("Error updating horizontal pod autoscaler"),
@@ -156,8 +163,10 @@ pub async fn update(
 pub async fn patch(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(hpa_name): Path<String>,
+    Query(params): Query<MutationParams>,
     Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     let mut object = etcd_get_object(
         &app_state,
         format!("/api/v1/horizontalpodautoscalers/{}", hpa_name),
@@ -170,12 +179,15 @@ pub async fn patch(
             KubeObject::HorizontalPodAutoscaler(ref mut hpa),
         ) => {
             hpa.spec = payload_hpa.spec.to_owned();
-            etcd_put(&app_state, &object).await?;
-            let res = Response::new(
-                Some(format!("horizontalpodautoscaler/{} patched", hpa_name)),
-                None,
-            );
-            Ok(Json(res))
+            fill_system_metadata(&app_state, &mut object).await?;
+            if !dry_run {
+                etcd_put(&app_state, &object).await?;
+            }
+            Ok(mutation_response(
+                format!("horizontalpodautoscaler/{} patched", hpa_name),
+                object,
+                dry_run,
+            ))
         },
         _ => Err(ErrResponse::new(
             String::from("Error patching horizontal pod autoscaler"),
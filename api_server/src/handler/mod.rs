@@ -10,6 +10,8 @@ pub mod ingress;
 pub mod metrics;
 pub mod node;
 pub mod pod;
+pub mod pod_group;
+pub mod priority_class;
 pub mod replica_set;
 mod response;
 pub mod service;
@@ -1,14 +1,19 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, WebSocketUpgrade},
+    extract::{Path, Query, WebSocketUpgrade},
     response::IntoResponse,
     Extension, Json,
 };
 use axum_macros::debug_handler;
+use chrono::Local;
 use resources::{
     models::{ErrResponse, Response},
-    objects::{node::NodeAddressType, KubeObject, Object},
+    objects::{
+        node::NodeAddressType,
+        taint::{Taint, TaintEffect},
+        KubeObject, Object,
+    },
 };
 use uuid::Uuid;
 
@@ -20,7 +25,10 @@ use crate::{
     etcd::forward_watch_to_ws,
     handler::{
         metrics::{add_scrape_target, remove_scrape_target},
-        utils::{etcd_delete, etcd_get_object, etcd_put},
+        utils::{
+            etcd_delete, etcd_get_object, etcd_put, fill_system_metadata, mutation_response,
+            MutationParams,
+        },
     },
     AppState,
 };
@@ -55,8 +63,10 @@ pub async fn get(
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(node_name): Path<String>,
+    Query(params): Query<MutationParams>,
     Json(mut payload): Json<KubeObject>,
 ) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     if let KubeObject::Node(ref mut node) = payload {
         let old_node = etcd_get_object(
             &app_state,
@@ -65,9 +75,24 @@ pub async fn update(
         )
         .await;
         match old_node {
-            // Node exists, replace status and return existing metadata
+            // Node exists, replace status and return existing metadata and spec
             Ok(KubeObject::Node(old_node)) => {
                 node.metadata = old_node.metadata;
+                node.spec = old_node.spec;
+                // rKubelet and the node lifecycle controller update different parts of status,
+                // keep the conditions and heartbeat not carried by either of them
+                for condition in old_node.status.conditions.iter() {
+                    if node.status.conditions.get(&condition.type_).is_none() {
+                        node.status.conditions.set(condition.to_owned());
+                    }
+                }
+                node.status.last_heartbeat = node
+                    .status
+                    .last_heartbeat
+                    .max(old_node.status.last_heartbeat);
+            },
+            _ if dry_run => {
+                node.metadata.uid = Some(Uuid::new_v4());
             },
             _ => {
                 node.metadata.uid = Some(Uuid::new_v4());
@@ -83,6 +108,14 @@ pub async fn update(
                 }
             },
         }
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if dry_run {
+            return Ok(mutation_response(
+                format!("node/{} updated", node_name),
+                payload,
+                dry_run,
+            ));
+        }
         etcd_put(&app_state, &payload).await?;
         let res = Response::new(Some(format!("node/{} updated", node_name)), Some(payload));
         Ok(Json(res))
@@ -98,15 +131,24 @@ pub async fn update(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(node_name): Path<String>,
-) -> HandlerResult<()> {
-    let node = etcd_get_object(
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let object = etcd_get_object(
         &app_state,
         format!("/api/v1/nodes/{}", node_name),
         Some("node"),
     )
     .await?;
+    if dry_run {
+        return Ok(mutation_response(
+            format!("node/{} deleted", node_name),
+            object,
+            dry_run,
+        ));
+    }
     etcd_delete(&app_state, format!("/api/v1/nodes/{}", node_name)).await?;
-    if let KubeObject::Node(node) = node {
+    if let KubeObject::Node(ref node) = object {
         if let Some(internal_ip) = node.status.addresses.get(&NodeAddressType::InternalIP) {
             remove_scrape_target(
                 "cadvisor",
@@ -116,16 +158,24 @@ pub async fn delete(
             .await?;
         }
     }
-    let res = Response::new(Some(format!("node/{} deleted", node_name)), None);
-    Ok(Json(res))
+    Ok(mutation_response(
+        format!("node/{} deleted", node_name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
 pub async fn patch(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(node_name): Path<String>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(fields): Json<serde_json::Value>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let payload: KubeObject = serde_json::from_value(fields.clone()).map_err(|err| {
+        ErrResponse::bad_request(String::from("Error patching node"), Some(err.to_string()))
+    })?;
     let mut object = etcd_get_object(
         &app_state,
         format!("/api/v1/nodes/{}", node_name),
@@ -134,10 +184,28 @@ pub async fn patch(
     .await?;
     match (&payload, &mut object) {
         (KubeObject::Node(payload_node), KubeObject::Node(ref mut node)) => {
-            node.metadata.labels = payload_node.metadata.labels.clone();
-            etcd_put(&app_state, &object).await?;
-            let res = Response::new(Some(format!("node/{} patched", node_name)), None);
-            Ok(Json(res))
+            // Only apply the fields carried by the patch,
+            // so that e.g. a label-only patch keeps the taints and cordon
+            if fields.pointer("/metadata/labels").is_some() {
+                node.metadata.labels = payload_node.metadata.labels.clone();
+            }
+            if fields.pointer("/spec/taints").is_some() {
+                let mut taints = payload_node.spec.taints.clone();
+                stamp_no_execute_taints(&node.spec.taints, &mut taints);
+                node.spec.taints = taints;
+            }
+            if fields.pointer("/spec/unschedulable").is_some() {
+                node.spec.unschedulable = payload_node.spec.unschedulable;
+            }
+            fill_system_metadata(&app_state, &mut object).await?;
+            if !dry_run {
+                etcd_put(&app_state, &object).await?;
+            }
+            Ok(mutation_response(
+                format!("node/{} patched", node_name),
+                object,
+                dry_run,
+            ))
         },
         _ => Err(ErrResponse::new(
             String::from("Error patching node"),
@@ -146,6 +214,21 @@ pub async fn patch(
     }
 }
 
+/// Keep the time existing NoExecute taints were added,
+/// and stamp new ones with the current time.
+fn stamp_no_execute_taints(old_taints: &[Taint], taints: &mut [Taint]) {
+    let now = Local::now().naive_utc();
+    for taint in taints
+        .iter_mut()
+        .filter(|taint| taint.effect == TaintEffect::NoExecute)
+    {
+        let existing = old_taints.iter().find(|old| {
+            old.key == taint.key && old.value == taint.value && old.effect == taint.effect
+        });
+        taint.time_added = existing.and_then(|old| old.time_added).or(Some(now));
+    }
+}
+
 #[debug_handler]
 pub async fn watch_all(
     Extension(app_state): Extension<Arc<AppState>>,
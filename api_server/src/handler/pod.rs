@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{OriginalUri, Path, WebSocketUpgrade},
+    extract::{OriginalUri, Path, Query, WebSocketUpgrade},
     http::Request,
     response::IntoResponse,
     Extension, Json,
@@ -14,27 +14,36 @@ use resources::{
 };
 use uuid::Uuid;
 
-use super::{response::HandlerResult, utils::*};
+use super::{priority_class::resolve_priority, response::HandlerResult, utils::*};
 use crate::{etcd::forward_watch_to_ws, AppState};
 
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     Json(mut payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::Pod(ref mut pod) = payload {
+        resolve_name(&app_state, &pod.prefix(), &mut pod.metadata).await?;
         pod.metadata.uid = Some(Uuid::new_v4());
-        pod.metadata.name = unique_name(&pod.metadata.name);
         let pod_name = pod.metadata.name.to_owned();
 
         let mut status = pod.status.clone().unwrap_or_default();
         status.phase = PodPhase::Pending;
         pod.status = Some(status);
+        resolve_priority(&app_state, pod).await?;
 
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("pod/{} created", pod_name)), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_create(&app_state, &payload).await?;
+        }
+        Ok(create_response(
+            format!("pod/{} created", pod_name),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -63,8 +72,10 @@ pub async fn get(
 pub async fn replace(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(pod_name): Path<String>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // Ensure object exists, otherwise deleted object will be created
     etcd_get_object(
         &app_state,
@@ -73,9 +84,15 @@ pub async fn replace(
     )
     .await?;
     if let KubeObject::Pod(_) = payload {
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("pod/{} replaced", pod_name)), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("pod/{} replaced", pod_name),
+            payload,
+            dry_run,
+        ))
     } else {
         Err(ErrResponse::new(
             String::from("Error replacing pod"),
@@ -88,10 +105,19 @@ pub async fn replace(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(pod_name): Path<String>,
-) -> HandlerResult<()> {
-    etcd_delete(&app_state, format!("/api/v1/pods/{}", pod_name)).await?;
-    let res = Response::new(Some(format!("pod/{} deleted", pod_name)), None);
-    Ok(Json(res))
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/pods/{}", pod_name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("pod")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("pod/{} deleted", pod_name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
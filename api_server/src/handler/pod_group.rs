@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
+use axum_macros::debug_handler;
+use resources::{
+    models::{ErrResponse, Response},
+    objects::{pod_group::PodGroupStatus, KubeObject, Object},
+};
+use uuid::Uuid;
+
+use super::{response::HandlerResult, utils::*};
+use crate::AppState;
+
+#[debug_handler]
+pub async fn create(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    if let KubeObject::PodGroup(ref mut group) = payload {
+        if group.spec.min_member == 0 {
+            return Err(ErrResponse::bad_request(
+                String::from("Invalid pod group"),
+                Some("minMember must be positive".to_string()),
+            ));
+        }
+        resolve_name(&app_state, &group.prefix(), &mut group.metadata).await?;
+        let group_name = group.metadata.name.to_owned();
+        group.metadata.uid = Some(Uuid::new_v4());
+        group.status = Some(PodGroupStatus::default());
+
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_create(&app_state, &payload).await?;
+        }
+        Ok(create_response(
+            format!("podgroup/{} created", group_name),
+            payload,
+            dry_run,
+        ))
+    } else {
+        Err(ErrResponse::new(
+            String::from("Error creating pod group"),
+            Some(format!("Expecting pod group kind, got {}", payload.kind())),
+        ))
+    }
+}
+
+#[debug_handler]
+pub async fn get(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> HandlerResult<KubeObject> {
+    let group = etcd_get_object(
+        &app_state,
+        format!("/api/v1/podgroups/{}", name),
+        Some("podgroup"),
+    )
+    .await?;
+    let res = Response::new(None, Some(group));
+    Ok(Json(res))
+}
+
+#[debug_handler]
+pub async fn delete(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/podgroups/{}", name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("podgroup")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("podgroup/{} deleted", name),
+        object,
+        dry_run,
+    ))
+}
+
+#[debug_handler]
+pub async fn update(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    // Ensure object exists, otherwise a deleted pod group is recreated by a status update
+    etcd_get_object(
+        &app_state,
+        format!("/api/v1/podgroups/{}", name),
+        Some("podgroup"),
+    )
+    .await?;
+    if let KubeObject::PodGroup(ref group) = payload {
+        if group.spec.min_member == 0 {
+            return Err(ErrResponse::bad_request(
+                String::from("Invalid pod group"),
+                Some("minMember must be positive".to_string()),
+            ));
+        }
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("podgroup/{} updated", name),
+            payload,
+            dry_run,
+        ))
+    } else {
+        Err(ErrResponse::new(
+            String::from("Error updating pod group"),
+            Some(format!("Expecting pod group kind, got {}", payload.kind())),
+        ))
+    }
+}
+
+#[debug_handler]
+pub async fn list(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> HandlerResult<Vec<KubeObject>> {
+    let groups = etcd_get_objects_by_prefix(
+        &app_state,
+        "/api/v1/podgroups".to_string(),
+        Some("podgroup"),
+    )
+    .await?;
+
+    let res = Response::new(None, Some(groups));
+    Ok(Json(res))
+}
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
+use axum_macros::debug_handler;
+use resources::{
+    models::{ErrResponse, Response},
+    objects::{
+        pod::Pod,
+        priority_class::{PreemptionPolicy, PriorityClass},
+        KubeObject, Object,
+    },
+};
+use uuid::Uuid;
+
+use super::{response::HandlerResult, utils::*};
+use crate::AppState;
+
+#[debug_handler]
+pub async fn create(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    if let KubeObject::PriorityClass(ref mut class) = payload {
+        resolve_name(&app_state, &class.prefix(), &mut class.metadata).await?;
+        class.metadata.uid = Some(Uuid::new_v4());
+        validate_global_default(&app_state, class).await?;
+
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_create(&app_state, &payload).await?;
+        }
+        Ok(create_response(
+            format!("priorityclass/{} created", payload.name()),
+            payload,
+            dry_run,
+        ))
+    } else {
+        Err(ErrResponse::new(
+            String::from("Error creating priority class"),
+            Some(format!("Expecting priority class, got {}", payload.kind())),
+        ))
+    }
+}
+
+#[debug_handler]
+pub async fn update(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    if let KubeObject::PriorityClass(ref class) = payload {
+        let old_class = etcd_get_object(
+            &app_state,
+            format!("/api/v1/priorityclasses/{}", name),
+            Some("priorityclass"),
+        )
+        .await?;
+        if let KubeObject::PriorityClass(old_class) = old_class {
+            // Pods have their priority resolved at creation, changing it would be misleading
+            if old_class.value != class.value {
+                return Err(ErrResponse::bad_request(
+                    String::from("Error updating priority class"),
+                    Some("Value of a priority class is immutable".to_string()),
+                ));
+            }
+        }
+        validate_global_default(&app_state, class).await?;
+
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("priorityclass/{} updated", name),
+            payload,
+            dry_run,
+        ))
+    } else {
+        Err(ErrResponse::new(
+            String::from("Error updating priority class"),
+            Some(format!("Expecting priority class, got {}", payload.kind())),
+        ))
+    }
+}
+
+#[debug_handler]
+pub async fn list(
+    Extension(app_state): Extension<Arc<AppState>>,
+) -> HandlerResult<Vec<KubeObject>> {
+    let classes = etcd_get_objects_by_prefix(
+        &app_state,
+        "/api/v1/priorityclasses".to_string(),
+        Some("priorityclass"),
+    )
+    .await?;
+
+    let res = Response::new(None, Some(classes));
+    Ok(Json(res))
+}
+
+#[debug_handler]
+pub async fn get(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> HandlerResult<KubeObject> {
+    let class = etcd_get_object(
+        &app_state,
+        format!("/api/v1/priorityclasses/{}", name),
+        Some("priorityclass"),
+    )
+    .await?;
+    let res = Response::new(None, Some(class));
+    Ok(Json(res))
+}
+
+#[debug_handler]
+pub async fn delete(
+    Extension(app_state): Extension<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/priorityclasses/{}", name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("priorityclass")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("priorityclass/{} deleted", name),
+        object,
+        dry_run,
+    ))
+}
+
+async fn list_priority_classes(
+    app_state: &Arc<AppState>,
+) -> Result<Vec<PriorityClass>, ErrResponse> {
+    let classes = etcd_get_objects_by_prefix(
+        app_state,
+        "/api/v1/priorityclasses".to_string(),
+        Some("priorityclass"),
+    )
+    .await?;
+    Ok(classes
+        .into_iter()
+        .filter_map(|object| match object {
+            KubeObject::PriorityClass(class) => Some(class),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Only one priority class can be marked as global default
+async fn validate_global_default(
+    app_state: &Arc<AppState>,
+    class: &PriorityClass,
+) -> Result<(), ErrResponse> {
+    if !class.global_default {
+        return Ok(());
+    }
+    let existing = list_priority_classes(app_state)
+        .await?
+        .into_iter()
+        .find(|other| other.global_default && other.metadata.name != class.metadata.name);
+    match existing {
+        Some(other) => Err(ErrResponse::bad_request(
+            String::from("Invalid priority class"),
+            Some(format!(
+                "priorityclass/{} is already marked as global default",
+                other.metadata.name
+            )),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Populate priority and preemption policy of the pod from its priority class,
+/// or from the global default class if it has none.
+pub async fn resolve_priority(app_state: &Arc<AppState>, pod: &mut Pod) -> Result<(), ErrResponse> {
+    let class = match &pod.spec.priority_class_name {
+        Some(name) => {
+            let object = etcd_get_object(
+                app_state,
+                format!("/api/v1/priorityclasses/{}", name),
+                Some("priorityclass"),
+            )
+            .await
+            .map_err(|_| {
+                ErrResponse::bad_request(
+                    String::from("Invalid pod"),
+                    Some(format!("No priority class with name {} is found", name)),
+                )
+            })?;
+            match object {
+                KubeObject::PriorityClass(class) => Some(class),
+                _ => None,
+            }
+        },
+        None => list_priority_classes(app_state)
+            .await?
+            .into_iter()
+            .find(|class| class.global_default),
+    };
+
+    match class {
+        Some(class) => {
+            pod.spec.priority_class_name = Some(class.metadata.name);
+            pod.spec.priority = Some(class.value);
+            pod.spec.preemption_policy = Some(class.preemption_policy);
+        },
+        None => {
+            pod.spec.priority = Some(0);
+            pod.spec.preemption_policy = Some(PreemptionPolicy::default());
+        },
+    }
+    Ok(())
+}
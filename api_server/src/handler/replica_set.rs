@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, WebSocketUpgrade},
+    extract::{Path, Query, WebSocketUpgrade},
     response::IntoResponse,
     Extension, Json,
 };
@@ -18,24 +18,26 @@ use crate::{etcd::forward_watch_to_ws, AppState};
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     Json(mut payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::ReplicaSet(ref mut rs) = payload {
-        let rs_name = &rs.metadata.name.to_owned();
-        let result = etcd_get_object(&app_state, rs.uri(), Some("replicaset")).await;
-        if result.is_ok() {
-            return Err(ErrResponse::new(
-                String::from("Error creating replica set"),
-                Some(format!("Replica set {} already exists", rs_name)),
-            ));
-        }
+        resolve_name(&app_state, &rs.prefix(), &mut rs.metadata).await?;
+        let rs_name = rs.metadata.name.to_owned();
         rs.metadata.uid = Some(Uuid::new_v4());
         rs.status = Some(ReplicaSetStatus::default());
 
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("replicaset/{} created", rs_name)), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_create(&app_state, &payload).await?;
+        }
+        Ok(create_response(
+            format!("replicaset/{} created", rs_name),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -64,22 +66,39 @@ pub async fn get(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(rs_name): Path<String>,
-) -> HandlerResult<()> {
-    etcd_delete(&app_state, format!("/api/v1/replicasets/{}", rs_name)).await?;
-    let res = Response::new(Some(format!("replicaset/{} deleted", rs_name)), None);
-    Ok(Json(res))
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/replicasets/{}", rs_name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("replicaset")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("replicaset/{} deleted", rs_name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(rs_name): Path<String>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     if let KubeObject::ReplicaSet(_) = payload {
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("replicaset/{} updated", rs_name)), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("replicaset/{} updated", rs_name),
+            payload,
+            dry_run,
+        ))
     } else {
         Err(ErrResponse::new(
             String::from("Error updating replicaset"),
@@ -92,8 +111,10 @@ pub async fn update(
 pub async fn patch(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(rs_name): Path<String>,
+    Query(params): Query<MutationParams>,
     Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     let mut object = etcd_get_object(
         &app_state,
         format!("/api/v1/replicasets/{}", rs_name),
@@ -103,9 +124,15 @@ pub async fn patch(
     match (&payload, &mut object) {
         (KubeObject::ReplicaSet(payload_rs), KubeObject::ReplicaSet(ref mut rs)) => {
             rs.spec = payload_rs.spec.to_owned();
-            etcd_put(&app_state, &object).await?;
-            let res = Response::new(Some(format!("replicaset/{} patched", rs_name)), None);
-            Ok(Json(res))
+            fill_system_metadata(&app_state, &mut object).await?;
+            if !dry_run {
+                etcd_put(&app_state, &object).await?;
+            }
+            Ok(mutation_response(
+                format!("replicaset/{} patched", rs_name),
+                object,
+                dry_run,
+            ))
         },
         _ => Err(ErrResponse::new(
             String::from("Error patching replicaset"),
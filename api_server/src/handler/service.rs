@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, WebSocketUpgrade},
+    extract::{Path, Query, WebSocketUpgrade},
     response::IntoResponse,
     Extension, Json,
 };
@@ -18,10 +18,13 @@ use crate::{etcd::forward_watch_to_ws, AppState};
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     Json(mut payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::Service(ref mut service) = payload {
+        resolve_name(&app_state, &service.prefix(), &mut service.metadata).await?;
         service.metadata.uid = Some(Uuid::new_v4());
 
         if let Some(ip) = service.spec.cluster_ip {
@@ -32,12 +35,18 @@ pub async fn create(
                 ));
             }
         } else {
-            service.spec.cluster_ip = Some(gen_service_ip(&app_state));
+            service.spec.cluster_ip = Some(gen_service_ip(&app_state, dry_run));
         }
 
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("service/{} created", payload.name())), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_create(&app_state, &payload).await?;
+        }
+        Ok(create_response(
+            format!("service/{} created", payload.name()),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -50,13 +59,21 @@ pub async fn create(
 #[debug_handler]
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::Service(_) = payload {
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("service/{} updated", payload.name())), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("service/{} updated", payload.name()),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -97,10 +114,19 @@ pub async fn get(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(name): Path<String>,
-) -> HandlerResult<()> {
-    etcd_delete(&app_state, format!("/api/v1/services/{}", name)).await?;
-    let res = Response::new(Some(format!("services/{} deleted", name)), None);
-    Ok(Json(res))
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/services/{}", name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("service")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("services/{} deleted", name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
@@ -1,4 +1,4 @@
-use std::{io, net::Ipv4Addr, path::PathBuf, sync::Arc};
+use std::{net::Ipv4Addr, path::PathBuf, sync::Arc};
 
 use axum::{
     body::Bytes,
@@ -9,26 +9,128 @@ use axum::{
     },
     http::{Request, Uri},
     response::IntoResponse,
-    BoxError,
+    Json,
 };
+use chrono::Local;
 use etcd_client::{GetOptions, GetResponse, WatchOptions, WatchStream, Watcher};
-use futures::{SinkExt, Stream, StreamExt, TryStreamExt};
+use futures::{SinkExt, StreamExt};
 use hyper::{client::HttpConnector, Body, Client};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use resources::{
     config::kubelet::KubeletConfig,
-    models::ErrResponse,
-    objects::{node::Node, pod::Pod, KubeObject, Object},
+    models::{ErrResponse, Response},
+    objects::{node::Node, pod::Pod, KubeObject, Metadata, Object},
 };
-use tokio::{fs::File, io::BufWriter};
+use serde::Deserialize;
 use tokio_tungstenite::{connect_async, tungstenite as ts};
-use tokio_util::io::StreamReader;
 
 use crate::{
     etcd::{self, kv_to_str},
     AppState, TMP_DIR,
 };
 
+/// Query parameters accepted by mutating handlers
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutationParams {
+    /// When set to `All`, the request goes through the whole pipeline
+    /// but nothing is persisted
+    pub dry_run: Option<String>,
+}
+
+impl MutationParams {
+    pub fn dry_run(&self) -> Result<bool, ErrResponse> {
+        match self.dry_run.as_deref() {
+            None => Ok(false),
+            Some("All") => Ok(true),
+            Some(value) => Err(ErrResponse::bad_request(
+                format!("Invalid dryRun value: {}", value),
+                Some("Only \"All\" is supported".to_string()),
+            )),
+        }
+    }
+}
+
+/// Build the response of a mutating request,
+/// the object that would have been persisted is returned on dry run.
+pub fn mutation_response(
+    msg: String,
+    object: KubeObject,
+    dry_run: bool,
+) -> Json<Response<KubeObject>> {
+    if dry_run {
+        Json(Response::new(Some(dry_run_msg(msg, dry_run)), Some(object)))
+    } else {
+        Json(Response::new(Some(msg), None))
+    }
+}
+
+/// Build the response of a create request, the created object is always returned
+/// so that clients can learn server-populated fields such as the generated name.
+pub fn create_response(
+    msg: String,
+    object: KubeObject,
+    dry_run: bool,
+) -> Json<Response<KubeObject>> {
+    Json(Response::new(Some(dry_run_msg(msg, dry_run)), Some(object)))
+}
+
+fn dry_run_msg(msg: String, dry_run: bool) -> String {
+    if dry_run {
+        format!("{} (server dry run)", msg)
+    } else {
+        msg
+    }
+}
+
+/// Everything but metadata and status, i.e. the desired state of an object
+fn desired_state(object: &KubeObject) -> Result<serde_json::Value, ErrResponse> {
+    let mut value = serde_json::to_value(object)
+        .map_err(|err| ErrResponse::new("failed to serialize".into(), Some(err.to_string())))?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("metadata");
+        fields.remove("status");
+    }
+    Ok(value)
+}
+
+/// Fill in metadata populated by the server before `object` is persisted.
+///
+/// A new object gets the current time as creation timestamp and generation 1.
+/// An existing object keeps them, and its generation is bumped
+/// whenever its desired state changes.
+pub async fn fill_system_metadata(
+    app_state: &Arc<AppState>,
+    object: &mut KubeObject,
+) -> Result<(), ErrResponse> {
+    let res = etcd_get(app_state, object.uri()).await?;
+    let old = if res.kvs().is_empty() {
+        None
+    } else {
+        let old_str = get_value_str(res)?;
+        let old: KubeObject = serde_json::from_str(old_str.as_str()).map_err(|err| {
+            ErrResponse::new("failed to deserialize".into(), Some(err.to_string()))
+        })?;
+        Some(old)
+    };
+    let (creation_timestamp, generation) = match old {
+        Some(old) => {
+            let metadata = old.metadata();
+            let generation = if desired_state(&old)? == desired_state(object)? {
+                metadata.generation
+            } else {
+                metadata.generation + 1
+            };
+            (metadata.creation_timestamp, generation)
+        },
+        None => (Some(Local::now().naive_utc()), 1),
+    };
+    let metadata = object.metadata_mut();
+    metadata.creation_timestamp = creation_timestamp;
+    metadata.generation = generation;
+    Ok(())
+}
+
 pub async fn etcd_put(app_state: &Arc<AppState>, val: &KubeObject) -> Result<(), ErrResponse> {
     let mut client = app_state.get_client().await?;
     etcd::put(&mut client, val.uri().as_str(), val, None)
@@ -37,6 +139,23 @@ pub async fn etcd_put(app_state: &Arc<AppState>, val: &KubeObject) -> Result<(),
     Ok(())
 }
 
+/// Put a new object, failing with 409 if one with the same URI exists,
+/// even if it's created concurrently after [`resolve_name`].
+pub async fn etcd_create(app_state: &Arc<AppState>, val: &KubeObject) -> Result<(), ErrResponse> {
+    let mut client = app_state.get_client().await?;
+    let created = etcd::create(&mut client, val.uri().as_str(), val)
+        .await
+        .map_err(ErrResponse::from)?;
+    if created {
+        Ok(())
+    } else {
+        Err(ErrResponse::conflict(
+            format!("{} already exists", val.name()),
+            None,
+        ))
+    }
+}
+
 pub async fn etcd_get(app_state: &Arc<AppState>, key: String) -> Result<GetResponse, ErrResponse> {
     let mut client = app_state.get_client().await?;
     let res = etcd::get(&mut client, &key, None)
@@ -140,23 +259,72 @@ pub fn get_value_str(response: GetResponse) -> Result<String, ErrResponse> {
     }
 }
 
-pub fn unique_name(name: &str) -> String {
+fn random_suffix() -> String {
     let mut rng = thread_rng();
-    let suffix = (&mut rng)
+    (&mut rng)
         .sample_iter(Alphanumeric)
         .take(5)
         .map(char::from)
         .collect::<String>()
-        .to_lowercase();
-    format!("{}-{}", name, suffix)
+        .to_lowercase()
+}
+
+pub fn unique_name(name: &str) -> String {
+    format!("{}-{}", name, random_suffix())
 }
 
-pub fn gen_service_ip(app_state: &Arc<AppState>) -> Ipv4Addr {
+/// Settle the name of an object to be created under `prefix`.
+///
+/// The given `metadata.name` is kept as is and must not exist yet,
+/// otherwise a name is generated from `metadata.generateName`.
+/// The object must then be written with [`etcd_create`],
+/// which rejects a name taken in the meantime.
+pub async fn resolve_name(
+    app_state: &Arc<AppState>,
+    prefix: &str,
+    metadata: &mut Metadata,
+) -> Result<(), ErrResponse> {
+    const MAX_GENERATE_ATTEMPTS: usize = 8;
+
+    if !metadata.name.is_empty() {
+        let uri = format!("{}/{}", prefix, metadata.name);
+        if !etcd_get(app_state, uri).await?.kvs().is_empty() {
+            return Err(ErrResponse::conflict(
+                format!("{} already exists", metadata.name),
+                None,
+            ));
+        }
+        return Ok(());
+    }
+    let generate_name = metadata.generate_name.as_ref().ok_or_else(|| {
+        ErrResponse::bad_request(
+            "metadata.name or metadata.generateName is required".to_string(),
+            None,
+        )
+    })?;
+    for _ in 0..MAX_GENERATE_ATTEMPTS {
+        let name = format!("{}{}", generate_name, random_suffix());
+        let uri = format!("{}/{}", prefix, name);
+        if etcd_get(app_state, uri).await?.kvs().is_empty() {
+            metadata.name = name;
+            return Ok(());
+        }
+    }
+    Err(ErrResponse::conflict(
+        format!("Failed to generate a unique name from {}", generate_name),
+        None,
+    ))
+}
+
+/// Generate an unused service IP, which is reserved in the pool unless on dry run
+pub fn gen_service_ip(app_state: &Arc<AppState>, dry_run: bool) -> Ipv4Addr {
     let mut rng = thread_rng();
     loop {
         let ip = Ipv4Addr::new(172, rng.gen_range(16..32), rng.gen(), rng.gen());
         if !app_state.service_ip_pool.contains(&ip) {
-            app_state.service_ip_pool.insert(ip);
+            if !dry_run {
+                app_state.service_ip_pool.insert(ip);
+            }
             return ip;
         }
     }
@@ -194,36 +362,6 @@ fn unique_filename(filename: &str) -> PathBuf {
     new_filename
 }
 
-pub async fn stream_to_tmp_file<S, E>(
-    original_filename: &str,
-    stream: S,
-) -> Result<String, ErrResponse>
-where
-    S: Stream<Item = Result<Bytes, E>>,
-    E: Into<BoxError>,
-{
-    async {
-        // Convert the stream into an `AsyncRead`.
-        let body_with_io_error = stream.map_err(|err| io::Error::new(io::ErrorKind::Other, err));
-        let body_reader = StreamReader::new(body_with_io_error);
-        futures::pin_mut!(body_reader);
-
-        // add unique hash to filename
-        let file = unique_filename(original_filename);
-
-        // write file
-        let path = std::path::Path::new(TMP_DIR).join(&file);
-        let mut file_writer = BufWriter::new(File::create(path).await?);
-        tokio::io::copy(&mut body_reader, &mut file_writer).await?;
-
-        // return the new filename
-        tracing::info!("add new tmp file: {}", file.display());
-        Ok::<String, io::Error>(file.to_str().unwrap_or_default().to_string())
-    }
-    .await
-    .map_err(|err| ErrResponse::new(err.to_string(), None))
-}
-
 pub async fn decode_field_json(field: Field<'_>) -> Result<KubeObject, ErrResponse> {
     serde_json::from_str(
         field
@@ -235,14 +373,44 @@ pub async fn decode_field_json(field: Field<'_>) -> Result<KubeObject, ErrRespon
     .map_err(|_| ErrResponse::bad_request("Failed to deserialize".to_string(), None))
 }
 
-pub async fn store_code_file(field: Field<'_>) -> Result<String, ErrResponse> {
+/// Code file uploaded along with an object,
+/// kept in memory until it's persisted so that dry runs leave nothing behind
+pub struct CodeFile {
+    /// Unique name of the file under the temporary directory
+    pub filename: String,
+    content: Bytes,
+}
+
+impl CodeFile {
+    /// Write the file to the temporary directory
+    pub async fn persist(&self) -> Result<(), ErrResponse> {
+        let path = std::path::Path::new(TMP_DIR).join(&self.filename);
+        tokio::fs::write(path, &self.content)
+            .await
+            .map_err(|err| ErrResponse::new(err.to_string(), None))?;
+        tracing::info!("add new tmp file: {}", self.filename);
+        Ok(())
+    }
+}
+
+pub async fn read_code_file(field: Field<'_>) -> Result<CodeFile, ErrResponse> {
     let original_filename = field.file_name().map_or("".to_string(), |n| n.to_owned());
     if !original_filename.ends_with(".zip") {
         let err = ErrResponse::bad_request("Please upload a zip file".to_string(), None);
         return Err(err);
     }
 
-    stream_to_tmp_file(original_filename.as_str(), field).await
+    let content = field.bytes().await.map_err(|err| {
+        ErrResponse::bad_request("Invalid code field".to_string(), Some(err.to_string()))
+    })?;
+    let filename = unique_filename(original_filename.as_str())
+        .to_str()
+        .unwrap_or_default()
+        .to_string();
+    Ok(CodeFile {
+        filename,
+        content,
+    })
 }
 
 pub async fn get_pod_node(app_state: &Arc<AppState>, pod: &Pod) -> Option<Node> {
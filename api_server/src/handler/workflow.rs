@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
-use axum::{extract::Path, Extension, Json};
+use axum::{
+    extract::{Path, Query},
+    Extension, Json,
+};
 use axum_macros::debug_handler;
 use resources::{
     models::{ErrResponse, Response},
@@ -18,10 +21,13 @@ use crate::AppState;
 #[debug_handler]
 pub async fn create(
     Extension(app_state): Extension<Arc<AppState>>,
+    Query(params): Query<MutationParams>,
     Json(mut payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::Workflow(ref mut workflow) = payload {
+        resolve_name(&app_state, &workflow.prefix(), &mut workflow.metadata).await?;
         workflow.metadata.uid = Some(Uuid::new_v4());
 
         validate_workflow(&app_state, workflow).await.map_err(|e| {
@@ -29,9 +35,15 @@ pub async fn create(
             ErrResponse::new(format!("Error validating workflow, caused by: {}", e), None)
         })?;
 
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("workflow/{} created", payload.name())), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_create(&app_state, &payload).await?;
+        }
+        Ok(create_response(
+            format!("workflow/{} created", payload.name()),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -71,17 +83,25 @@ async fn validate_workflow(app_state: &Arc<AppState>, workflow: &Workflow) -> Re
 #[debug_handler]
 pub async fn update(
     Extension(app_state): Extension<Arc<AppState>>,
-    Json(payload): Json<KubeObject>,
-) -> HandlerResult<()> {
+    Query(params): Query<MutationParams>,
+    Json(mut payload): Json<KubeObject>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
     // TODO: validate payload
     if let KubeObject::Workflow(ref workflow) = payload {
         validate_workflow(&app_state, workflow).await.map_err(|e| {
             tracing::info!("Error validating workflow, caused by: {}", e);
             ErrResponse::new(format!("Error validating workflow, caused by: {}", e), None)
         })?;
-        etcd_put(&app_state, &payload).await?;
-        let res = Response::new(Some(format!("workflow/{} updated", payload.name())), None);
-        Ok(Json(res))
+        fill_system_metadata(&app_state, &mut payload).await?;
+        if !dry_run {
+            etcd_put(&app_state, &payload).await?;
+        }
+        Ok(mutation_response(
+            format!("workflow/{} updated", payload.name()),
+            payload,
+            dry_run,
+        ))
     } else {
         // TODO: fill business logic and error handling
         return Err(ErrResponse::new(
@@ -110,10 +130,19 @@ pub async fn get(
 pub async fn delete(
     Extension(app_state): Extension<Arc<AppState>>,
     Path(name): Path<String>,
-) -> HandlerResult<()> {
-    etcd_delete(&app_state, format!("/api/v1/workflows/{}", name)).await?;
-    let res = Response::new(Some(format!("workflows/{} deleted", name)), None);
-    Ok(Json(res))
+    Query(params): Query<MutationParams>,
+) -> HandlerResult<KubeObject> {
+    let dry_run = params.dry_run()?;
+    let uri = format!("/api/v1/workflows/{}", name);
+    let object = etcd_get_object(&app_state, uri.to_owned(), Some("workflow")).await?;
+    if !dry_run {
+        etcd_delete(&app_state, uri).await?;
+    }
+    Ok(mutation_response(
+        format!("workflows/{} deleted", name),
+        object,
+        dry_run,
+    ))
 }
 
 #[debug_handler]
@@ -235,6 +235,37 @@ async fn main() -> Result<()> {
             ),
     );
 
+    #[rustfmt::skip]
+    let pod_group_routes = Router::new().nest(
+        "/podgroups",
+        Router::new()
+            .route("/",
+                   get(handler::pod_group::list)
+                       .post(handler::pod_group::create))
+            .route("/:name",
+                   get(handler::pod_group::get)
+                       .put(handler::pod_group::update)
+                       .delete(handler::pod_group::delete),
+            ),
+    );
+
+    #[rustfmt::skip]
+    let priority_class_routes = Router::new().nest(
+        "/priorityclasses",
+        Router::new()
+            .route(
+                "/",
+                get(handler::priority_class::list)
+                    .post(handler::priority_class::create),
+            )
+            .route(
+                "/:name",
+                get(handler::priority_class::get)
+                    .put(handler::priority_class::update)
+                    .delete(handler::priority_class::delete),
+            ),
+    );
+
     // tmp file server
     fs::create_dir_all(TMP_DIR).await?;
     let tmp_file_service = get_service(ServeDir::new(TMP_DIR)).handle_error(|error| async move {
@@ -259,6 +290,8 @@ async fn main() -> Result<()> {
                 .merge(gpujob_routes)
                 .merge(function_routes)
                 .merge(workflow_routes)
+                .merge(priority_class_routes)
+                .merge(pod_group_routes)
                 .nest("/tmp", tmp_file_service)
                 .route("/bindings", post(handler::binding::bind)),
         )
@@ -1,89 +1,62 @@
-use anyhow::{anyhow, Error, Result};
-use reqwest::Url;
+use std::time::Duration;
+
+use anyhow::Result;
 use resources::{
-    informer::{EventHandler, Informer, ListerWatcher, ResyncHandler, WsStream},
-    models,
-    objects::pod::Pod,
+    client::{Api, Client},
+    config::ClusterConfig,
+    controller::{async_trait, Action, Controller, Reconciler},
+    informer::Store,
+    objects::{pod::Pod, replica_set::ReplicaSet},
 };
-use tokio::sync::mpsc;
-use tokio_tungstenite::connect_async;
 
-// noinspection DuplicatedCode
+/// Print ReplicaSets and the pods they own whenever they change
+struct DummyReconciler {
+    rs_store: Store<ReplicaSet>,
+    pod_store: Store<Pod>,
+}
+
+#[async_trait]
+impl Reconciler for DummyReconciler {
+    async fn reconcile(&self, key: &str) -> Result<Action> {
+        // you can do controller related stuff here
+        let rs = self.rs_store.read().await.get(key).cloned();
+        match rs {
+            Some(rs) => {
+                let pods = self.pod_store.read().await;
+                let owned = pods
+                    .values()
+                    .filter(|pod| {
+                        pod.metadata.owner_references.iter().any(|owner| {
+                            owner.kind == "ReplicaSet" && owner.name == rs.metadata.name
+                        })
+                    })
+                    .count();
+                println!("reconcile {}: {} pods owned\n{:#?}", key, owned, rs);
+                println!("store size: {}", pods.len());
+                // Requeue periodically even if nothing changes
+                Ok(Action::Requeue(Duration::from_secs(30)))
+            },
+            None => {
+                println!("{} deleted", key);
+                Ok(Action::AwaitChange)
+            },
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    // create list watcher closures
-    // TODO: maybe some crate or macros can simplify the tedious boxed closure creation in heap
-    let lw = ListerWatcher {
-        lister: Box::new(|_| {
-            Box::pin(async {
-                let res = reqwest::get("http://localhost:8080/api/v1/pods")
-                    .await?
-                    .json::<models::Response<Vec<Pod>>>()
-                    .await?;
-                let res = res.data.ok_or_else(|| anyhow!("Lister failed"))?;
-                Ok::<Vec<Pod>, Error>(res)
-            })
-        }),
-        watcher: Box::new(|_| {
-            Box::pin(async {
-                let url = Url::parse("ws://localhost:8080/api/v1/watch/pods")?;
-                let (stream, _) = connect_async(url).await?;
-                Ok::<WsStream, Error>(stream)
-            })
-        }),
+    let client = Client::new(ClusterConfig::default())?;
+    let controller = Controller::new(Api::<ReplicaSet>::new(client.to_owned(), "replicasets"))
+        .owns(Api::<Pod>::new(client, "pods"));
+    let reconciler = DummyReconciler {
+        rs_store: controller.store(),
+        pod_store: controller
+            .store_of::<Pod>()
+            .expect("pods are owned by the controller"),
     };
 
-    // create event handler closures
-    let (tx_add, mut rx) = mpsc::channel::<String>(16);
-    let tx_update = tx_add.clone();
-    let tx_delete = tx_add.clone();
-    let eh = EventHandler::<Pod> {
-        add_cls: Box::new(move |new| {
-            // TODO: this is not good: tx is copied every time add_cls is called, but I can't find a better way
-            let tx_add = tx_add.clone();
-            Box::pin(async move {
-                let message = format!("add\n{:#?}", new);
-                tx_add.send(message).await?;
-                Ok(())
-            })
-        }),
-        update_cls: Box::new(move |(old, new)| {
-            let tx_update = tx_update.clone();
-            Box::pin(async move {
-                let message = format!("update\n{:#?}\n{:#?}", old, new);
-                tx_update.send(message).await?;
-                Ok(())
-            })
-        }),
-        delete_cls: Box::new(move |old| {
-            let tx_delete = tx_delete.clone();
-            Box::pin(async move {
-                let message = format!("delete\n{:#?}", old);
-                tx_delete.send(message).await?;
-                Ok(())
-            })
-        }),
-    };
-
-    // TODO: fill real logic
-    let rh = ResyncHandler(Box::new(move |_| {
-        Box::pin(async move {
-            println!("Resync\n");
-            Ok(())
-        })
-    }));
-    // start the informer
-    let informer = Informer::new(lw, eh, rh);
-    let store = informer.get_store();
-    let informer_handler = tokio::spawn(async move { informer.run().await });
-
-    while let Some(s) = rx.recv().await {
-        // you can do deserializing and controller related stuff here
-        println!("{}", s);
-        println!("store size: {}", store.read().await.len());
-    }
-
-    informer_handler.await?
+    controller.run(reconciler).await
 }
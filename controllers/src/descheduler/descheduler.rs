@@ -0,0 +1,114 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Result;
+use resources::{
+    client::{Api, Client},
+    config::descheduler::DeschedulerPolicy,
+    objects::{node::Node, pod::Pod, replica_set::ReplicaSet},
+};
+use tokio::time;
+
+use crate::{evictor::Evictor, strategies};
+
+/// Nodes and running pods a round of descheduling works on
+pub struct ClusterState {
+    pub nodes: Vec<Node>,
+    /// Active pods bound to each node, by node name
+    pub pods_by_node: HashMap<String, Vec<Pod>>,
+}
+
+impl ClusterState {
+    pub fn pods_on(&self, node: &Node) -> &[Pod] {
+        self.pods_by_node
+            .get(&node.metadata.name)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Nodes evicted pods may be scheduled onto,
+    /// i.e. ready, not cordoned and not under pressure
+    pub fn ready_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter(|node| {
+            node.is_ready() && !node.spec.unschedulable && node.pressures().is_empty()
+        })
+    }
+}
+
+/// Evict running pods periodically so that the scheduler can place them better,
+/// e.g. on nodes added after they're scheduled.
+pub struct Descheduler {
+    policy: DeschedulerPolicy,
+    node_api: Api<Node>,
+    pod_api: Api<Pod>,
+    rs_api: Api<ReplicaSet>,
+}
+
+impl Descheduler {
+    pub fn new(client: Client, policy: DeschedulerPolicy) -> Self {
+        Self {
+            policy,
+            node_api: Api::new(client.to_owned(), "nodes"),
+            pod_api: Api::new(client.to_owned(), "pods"),
+            rs_api: Api::new(client, "replicasets"),
+        }
+    }
+
+    pub async fn run(&self) -> Result<()> {
+        tracing::info!("Descheduler started");
+        let mut interval = time::interval(Duration::from_secs(self.policy.interval_seconds));
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.deschedule().await {
+                tracing::error!("Failed to deschedule: {:#}", e);
+            }
+        }
+    }
+
+    /// Run enabled strategies in order on a fresh view of the cluster
+    async fn deschedule(&self) -> Result<()> {
+        let nodes = self.node_api.list().await?;
+        let mut pods_by_node = HashMap::<String, Vec<Pod>>::new();
+        for pod in self.pod_api.list().await? {
+            if !pod.is_active() {
+                continue;
+            }
+            if let Some(node_name) = &pod.spec.node_name {
+                pods_by_node
+                    .entry(node_name.to_owned())
+                    .or_default()
+                    .push(pod);
+            }
+        }
+        let state = ClusterState {
+            nodes,
+            pods_by_node,
+        };
+        let mut evictor = Evictor::new(
+            self.pod_api.to_owned(),
+            self.rs_api.list().await?,
+            self.policy.max_pods_to_evict_per_node,
+        );
+
+        let policy = &self.policy;
+        if policy.remove_pods_violating_node_taints {
+            strategies::remove_pods_violating_node_taints(&state, &mut evictor).await;
+        }
+        if policy.remove_pods_violating_node_affinity {
+            strategies::remove_pods_violating_node_affinity(&state, &mut evictor).await;
+        }
+        if let Some(args) = &policy.remove_pods_having_too_many_restarts {
+            strategies::remove_pods_having_too_many_restarts(&state, &mut evictor, args).await;
+        }
+        if policy.remove_duplicates {
+            strategies::remove_duplicates(&state, &mut evictor).await;
+        }
+        if let Some(args) = &policy.low_node_utilization {
+            strategies::low_node_utilization(&state, &mut evictor, args).await;
+        }
+
+        let evicted = evictor.evicted_count();
+        if evicted > 0 {
+            tracing::info!("Evicted {} pod(s)", evicted);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::{HashMap, HashSet};
+
+use resources::{
+    client::Api,
+    objects::{pod::Pod, replica_set::ReplicaSet, Object},
+};
+
+/// Evict pods within the limits of a descheduling round.
+///
+/// Only pods owned by a ReplicaSet are evicted, so that they're recreated elsewhere.
+/// Each ReplicaSet acts as a disruption budget of one unavailable pod:
+/// a pod is only evicted when all replicas of its ReplicaSet are ready,
+/// and at most one pod of a ReplicaSet is evicted in a round.
+pub struct Evictor {
+    pod_api: Api<Pod>,
+    /// ReplicaSets by name
+    replica_sets: HashMap<String, ReplicaSet>,
+    max_pods_per_node: Option<u32>,
+    /// Names of evicted pods
+    evicted: HashSet<String>,
+    /// ReplicaSets with a pod evicted
+    disrupted: HashSet<String>,
+    evicted_per_node: HashMap<String, u32>,
+}
+
+impl Evictor {
+    pub fn new(
+        pod_api: Api<Pod>,
+        replica_sets: Vec<ReplicaSet>,
+        max_pods_per_node: Option<u32>,
+    ) -> Self {
+        Self {
+            pod_api,
+            replica_sets: replica_sets
+                .into_iter()
+                .map(|rs| (rs.metadata.name.to_owned(), rs))
+                .collect(),
+            max_pods_per_node,
+            evicted: HashSet::new(),
+            disrupted: HashSet::new(),
+            evicted_per_node: HashMap::new(),
+        }
+    }
+
+    pub fn evicted_count(&self) -> usize {
+        self.evicted.len()
+    }
+
+    /// Whether the pod can be evicted now
+    pub fn is_evictable(&self, pod: &Pod) -> bool {
+        if self.evicted.contains(pod.name()) {
+            return false;
+        }
+        let rs = match owner_replica_set(pod).and_then(|name| self.replica_sets.get(name)) {
+            Some(rs) => rs,
+            None => return false,
+        };
+        if self.disrupted.contains(rs.name()) {
+            return false;
+        }
+        let ready_replicas = rs.status.as_ref().map_or(0, |status| status.ready_replicas);
+        if ready_replicas < rs.spec.replicas {
+            return false;
+        }
+        match (self.max_pods_per_node, &pod.spec.node_name) {
+            (Some(max), Some(node_name)) => {
+                self.evicted_per_node
+                    .get(node_name)
+                    .copied()
+                    .unwrap_or_default()
+                    < max
+            },
+            _ => true,
+        }
+    }
+
+    /// Evict the pod if it's evictable, return whether it's evicted
+    pub async fn evict(&mut self, pod: &Pod, reason: &str) -> bool {
+        if !self.is_evictable(pod) {
+            return false;
+        }
+        let node_name = pod.spec.node_name.to_owned().unwrap_or_default();
+        tracing::info!(
+            "Evicting pod {} on node {}: {}",
+            pod.name(),
+            node_name,
+            reason
+        );
+        match self.pod_api.delete(pod.name()).await {
+            Err(e) if !e.is_not_found() => {
+                tracing::error!("Failed to evict pod {}: {}", pod.name(), e);
+                return false;
+            },
+            _ => {},
+        }
+        self.evicted.insert(pod.name().to_owned());
+        if let Some(rs_name) = owner_replica_set(pod) {
+            self.disrupted.insert(rs_name.to_owned());
+        }
+        *self.evicted_per_node.entry(node_name).or_default() += 1;
+        true
+    }
+}
+
+/// Name of the ReplicaSet owning the pod
+pub fn owner_replica_set(pod: &Pod) -> Option<&String> {
+    pod.metadata
+        .owner_references
+        .iter()
+        .find(|owner| owner.kind == "ReplicaSet")
+        .map(|owner| &owner.name)
+}
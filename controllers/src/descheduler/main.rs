@@ -0,0 +1,43 @@
+#[macro_use]
+extern crate lazy_static;
+
+use anyhow::{Context, Result};
+use config::{Config, File};
+use descheduler::Descheduler;
+use resources::{
+    client::Client,
+    config::{descheduler::DeschedulerPolicy, load_or_default, ClusterConfig},
+};
+
+mod descheduler;
+mod evictor;
+mod strategies;
+
+const POLICY_PATH: &str = "/etc/rminik8s/descheduler.yaml";
+
+lazy_static! {
+    pub static ref CONFIG: ClusterConfig = Config::builder()
+        .add_source(File::with_name("/etc/rminik8s/controller-manager.yaml").required(false))
+        .set_override_option("apiServerUrl", std::env::var("API_SERVER_URL").ok())
+        .unwrap()
+        .set_override_option(
+            "apiServerWatchUrl",
+            std::env::var("API_SERVER_WATCH_URL").ok(),
+        )
+        .unwrap()
+        .build()
+        .unwrap_or_default()
+        .try_deserialize::<ClusterConfig>()
+        .with_context(|| "Failed to parse config".to_string())
+        .unwrap_or_default();
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let policy = load_or_default::<DeschedulerPolicy>(POLICY_PATH)?;
+    tracing::info!("Descheduler policy: {:?}", policy);
+    let client = Client::new(CONFIG.to_owned())?;
+    Descheduler::new(client, policy).run().await
+}
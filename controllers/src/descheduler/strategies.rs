@@ -0,0 +1,383 @@
+use std::collections::HashMap;
+
+use resources::{
+    config::descheduler::{LowNodeUtilizationArgs, ResourceThresholds, TooManyRestartsArgs},
+    objects::{
+        affinity::NodeSelector,
+        metrics::Resource,
+        node::Node,
+        pod::Pod,
+        taint::{tolerated, TaintEffect},
+        Object,
+    },
+};
+
+use crate::{
+    descheduler::ClusterState,
+    evictor::{owner_replica_set, Evictor},
+};
+
+/// Evict pods not tolerating NoSchedule taints of their node,
+/// which are added after the pods are scheduled.
+pub async fn remove_pods_violating_node_taints(state: &ClusterState, evictor: &mut Evictor) {
+    for node in state.nodes.iter() {
+        for taint in node
+            .spec
+            .taints
+            .iter()
+            .filter(|taint| taint.effect == TaintEffect::NoSchedule)
+        {
+            for pod in state.pods_on(node) {
+                if !tolerated(&pod.spec.tolerations, taint) {
+                    evictor
+                        .evict(pod, &format!("not tolerating taint {}", taint))
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+/// Evict pods whose node no longer matches their node selector or required node affinity,
+/// if another ready node matches.
+pub async fn remove_pods_violating_node_affinity(state: &ClusterState, evictor: &mut Evictor) {
+    for node in state.nodes.iter() {
+        for pod in state.pods_on(node) {
+            if fits_node(pod, node) {
+                continue;
+            }
+            if state
+                .ready_nodes()
+                .any(|other| other.name() != node.name() && fits_node(pod, other))
+            {
+                evictor.evict(pod, "violating node affinity").await;
+            }
+        }
+    }
+}
+
+/// Evict pods whose containers restarted too many times in total
+pub async fn remove_pods_having_too_many_restarts(
+    state: &ClusterState,
+    evictor: &mut Evictor,
+    args: &TooManyRestartsArgs,
+) {
+    for pods in state.pods_by_node.values() {
+        for pod in pods {
+            let restarts = pod.status.as_ref().map_or(0, |status| {
+                status
+                    .container_statuses
+                    .iter()
+                    .map(|c| c.restart_count)
+                    .sum::<u32>()
+            });
+            if restarts >= args.pod_restart_threshold {
+                evictor
+                    .evict(pod, &format!("restarted {} times", restarts))
+                    .await;
+            }
+        }
+    }
+}
+
+/// Evict pods of the same ReplicaSet crowding on a node,
+/// so that they spread over other nodes.
+pub async fn remove_duplicates(state: &ClusterState, evictor: &mut Evictor) {
+    for (pod, owner) in find_duplicates(state) {
+        evictor
+            .evict(pod, &format!("duplicate of ReplicaSet {}", owner))
+            .await;
+    }
+}
+
+/// Pods of a ReplicaSet on a node beyond an even spread over the nodes they fit,
+/// i.e. above `ceil(replicas / feasible nodes)`, so that evicted pods don't come back.
+fn find_duplicates(state: &ClusterState) -> Vec<(&Pod, &String)> {
+    let mut replicas = HashMap::<&String, usize>::new();
+    for pod in state.pods_by_node.values().flatten() {
+        if let Some(owner) = owner_replica_set(pod) {
+            *replicas.entry(owner).or_default() += 1;
+        }
+    }
+
+    let mut duplicates = Vec::new();
+    for node in state.nodes.iter() {
+        let mut pods_by_owner = HashMap::<&String, Vec<&Pod>>::new();
+        for pod in state.pods_on(node) {
+            if let Some(owner) = owner_replica_set(pod) {
+                pods_by_owner.entry(owner).or_default().push(pod);
+            }
+        }
+        for (owner, mut pods) in pods_by_owner {
+            // Pods of a ReplicaSet share the template, any of them tells where they fit
+            let feasible_nodes = state
+                .ready_nodes()
+                .filter(|node| fits_node(pods[0], node) && tolerates_node(pods[0], node))
+                .count();
+            let max_pods = div_ceil(replicas[owner], feasible_nodes.max(1));
+            if pods.len() <= max_pods {
+                continue;
+            }
+            pods.sort_by(|a, b| a.name().cmp(b.name()));
+            duplicates.extend(pods.into_iter().skip(max_pods).map(|pod| (pod, owner)));
+        }
+    }
+    duplicates
+}
+
+fn div_ceil(dividend: usize, divisor: usize) -> usize {
+    (dividend + divisor - 1) / divisor
+}
+
+/// Evict pods from overutilized nodes while underutilized nodes have room for them,
+/// pods of lower priority first.
+pub async fn low_node_utilization(
+    state: &ClusterState,
+    evictor: &mut Evictor,
+    args: &LowNodeUtilizationArgs,
+) {
+    let usages = state
+        .ready_nodes()
+        .map(|node| NodeUsage::new(node, state.pods_on(node)))
+        .collect::<Vec<_>>();
+    let (mut room_cpu, mut room_memory) = (0, 0);
+    for usage in usages
+        .iter()
+        .filter(|usage| usage.is_below(&args.thresholds))
+    {
+        room_cpu += (usage.allocatable_cpu * args.target_thresholds.cpu / 100 - usage.cpu).max(0);
+        room_memory +=
+            (usage.allocatable_memory * args.target_thresholds.memory / 100 - usage.memory).max(0);
+    }
+    if room_cpu == 0 && room_memory == 0 {
+        return;
+    }
+
+    let mut overutilized = usages
+        .into_iter()
+        .filter(|usage| usage.exceeds(&args.target_thresholds))
+        .collect::<Vec<_>>();
+    overutilized.sort_by_key(|usage| -usage.cpu_percent());
+    for mut usage in overutilized {
+        let mut pods = state
+            .pods_on(usage.node)
+            .iter()
+            .map(|pod| {
+                (
+                    pod,
+                    pod.requests(&Resource::CPU),
+                    pod.requests(&Resource::Memory),
+                )
+            })
+            // Moving pods requesting nothing doesn't help
+            .filter(|&(_, cpu, memory)| cpu > 0 || memory > 0)
+            .collect::<Vec<_>>();
+        pods.sort_by_key(|(pod, ..)| pod.priority());
+        for (pod, cpu, memory) in pods {
+            if !usage.exceeds(&args.target_thresholds) {
+                break;
+            }
+            if cpu > room_cpu || memory > room_memory {
+                continue;
+            }
+            if evictor.evict(pod, "node is overutilized").await {
+                usage.cpu -= cpu;
+                usage.memory -= memory;
+                room_cpu -= cpu;
+                room_memory -= memory;
+            }
+        }
+    }
+}
+
+/// Resources requested by pods on a node
+struct NodeUsage<'a> {
+    node: &'a Node,
+    cpu: i64,
+    memory: i64,
+    allocatable_cpu: i64,
+    allocatable_memory: i64,
+}
+
+impl<'a> NodeUsage<'a> {
+    fn new(node: &'a Node, pods: &[Pod]) -> Self {
+        Self {
+            node,
+            cpu: pods.iter().map(|pod| pod.requests(&Resource::CPU)).sum(),
+            memory: pods.iter().map(|pod| pod.requests(&Resource::Memory)).sum(),
+            allocatable_cpu: node.status.allocatable.cpu.milli_value(),
+            allocatable_memory: node.status.allocatable.memory.value(),
+        }
+    }
+
+    fn cpu_percent(&self) -> i64 {
+        percentage(self.cpu, self.allocatable_cpu)
+    }
+
+    fn memory_percent(&self) -> i64 {
+        percentage(self.memory, self.allocatable_memory)
+    }
+
+    /// Whether all resources are requested below the thresholds
+    fn is_below(&self, thresholds: &ResourceThresholds) -> bool {
+        self.cpu_percent() < thresholds.cpu && self.memory_percent() < thresholds.memory
+    }
+
+    /// Whether any resource is requested above the thresholds
+    fn exceeds(&self, thresholds: &ResourceThresholds) -> bool {
+        self.cpu_percent() > thresholds.cpu || self.memory_percent() > thresholds.memory
+    }
+}
+
+fn percentage(requested: i64, allocatable: i64) -> i64 {
+    if allocatable <= 0 {
+        return 0;
+    }
+    requested * 100 / allocatable
+}
+
+/// Whether the node matches the node selector and required node affinity of the pod
+fn fits_node(pod: &Pod, node: &Node) -> bool {
+    node.metadata.labels.matches(&pod.spec.node_selector)
+        && required_node_affinity(pod)
+            .map_or(true, |selector| selector.matches(&node.metadata.labels))
+}
+
+/// Whether the pod tolerates all taints of the node repelling new pods
+fn tolerates_node(pod: &Pod, node: &Node) -> bool {
+    node.spec
+        .taints
+        .iter()
+        .filter(|taint| taint.effect != TaintEffect::PreferNoSchedule)
+        .all(|taint| tolerated(&pod.spec.tolerations, taint))
+}
+
+fn required_node_affinity(pod: &Pod) -> Option<&NodeSelector> {
+    pod.spec
+        .affinity
+        .as_ref()?
+        .node_affinity
+        .as_ref()?
+        .required_during_scheduling_ignored_during_execution
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::objects::{
+        condition::Condition,
+        node::{NodeConditionType, NodeSpec, NodeStatus},
+        object_reference::ObjectReference,
+        pod::{PodPhase, PodSpec, PodStatus},
+        Metadata,
+    };
+
+    use super::*;
+
+    fn node(name: &str) -> Node {
+        let mut status = NodeStatus::default();
+        status
+            .conditions
+            .set(Condition::new(NodeConditionType::Ready, true));
+        Node {
+            metadata: Metadata {
+                name: name.to_owned(),
+                ..Default::default()
+            },
+            spec: NodeSpec::default(),
+            status,
+        }
+    }
+
+    fn pod(name: &str, owner: &str, node_name: &str) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: name.to_owned(),
+                owner_references: vec![ObjectReference::new(
+                    "ReplicaSet".to_owned(),
+                    owner.to_owned(),
+                )],
+                ..Default::default()
+            },
+            spec: PodSpec {
+                node_name: Some(node_name.to_owned()),
+                ..Default::default()
+            },
+            status: Some(PodStatus {
+                phase: PodPhase::Running,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn state(nodes: &[&str], pods: Vec<Pod>) -> ClusterState {
+        let mut pods_by_node = HashMap::<String, Vec<Pod>>::new();
+        for pod in pods {
+            let node_name = pod.spec.node_name.to_owned().unwrap();
+            pods_by_node.entry(node_name).or_default().push(pod);
+        }
+        ClusterState {
+            nodes: nodes.iter().map(|name| node(name)).collect(),
+            pods_by_node,
+        }
+    }
+
+    fn duplicate_names(state: &ClusterState) -> Vec<&str> {
+        let mut names = find_duplicates(state)
+            .into_iter()
+            .map(|(pod, _)| pod.name().as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn uneven_replicas_are_kept() {
+        // 3 replicas can't spread evenly over 2 nodes, 2 on one node is the best
+        let state = state(
+            &["node-1", "node-2"],
+            vec![
+                pod("rs-a", "rs", "node-1"),
+                pod("rs-b", "rs", "node-1"),
+                pod("rs-c", "rs", "node-2"),
+            ],
+        );
+        assert!(duplicate_names(&state).is_empty());
+    }
+
+    #[test]
+    fn crowded_replicas_are_evicted() {
+        let state = state(
+            &["node-1", "node-2"],
+            vec![
+                pod("rs-a", "rs", "node-1"),
+                pod("rs-b", "rs", "node-1"),
+                pod("rs-c", "rs", "node-1"),
+            ],
+        );
+        assert_eq!(duplicate_names(&state), vec!["rs-c"]);
+    }
+
+    #[test]
+    fn replicas_stay_without_other_feasible_nodes() {
+        let mut state = state(
+            &["node-1", "node-2"],
+            vec![pod("rs-a", "rs", "node-1"), pod("rs-b", "rs", "node-1")],
+        );
+        state.nodes[1].spec.unschedulable = true;
+        assert!(duplicate_names(&state).is_empty());
+    }
+
+    #[test]
+    fn replica_sets_are_counted_separately() {
+        let state = state(
+            &["node-1", "node-2"],
+            vec![
+                pod("rs-a", "rs", "node-1"),
+                pod("other-a", "other", "node-1"),
+                pod("rs-b", "rs", "node-2"),
+            ],
+        );
+        assert!(duplicate_names(&state).is_empty());
+    }
+}
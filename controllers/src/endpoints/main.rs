@@ -8,7 +8,7 @@ use reqwest::Url;
 use resources::{
     informer::Store,
     models::NodeConfig,
-    objects::{pod::Pod, service::Service},
+    objects::{pod::Pod, service::Service, Object},
 };
 use tokio::{select, sync::mpsc};
 use utils::update_service;
@@ -100,21 +100,18 @@ async fn handle_resync(pod_store: Store<Pod>, svc_store: Store<Service>) -> Resu
 
     let mut svc_store = svc_store.write().await;
     let pod_store = pod_store.read().await;
-    for (_, svc) in svc_store.iter_mut() {
-        let svc_spec = &mut svc.spec;
-        let mut new_eps: HashSet<Ipv4Addr> = HashSet::new();
-
-        for (_, pod) in pod_store.iter() {
-            if let Some(pod_ip) = pod.get_ip() {
-                if svc_spec.selector.matches(&pod.metadata.labels) {
-                    new_eps.insert(pod_ip);
-                }
-            }
-        }
-
-        if svc_spec.endpoints != new_eps {
-            svc_spec.endpoints.clone_from(&new_eps);
-            update_service(svc).await?;
+    let services = svc_store.values().cloned().collect::<Vec<_>>();
+    for mut svc in services {
+        let new_eps = pod_store
+            .select(&svc.spec.selector)
+            .into_iter()
+            .filter_map(|pod| pod.get_ip())
+            .collect::<HashSet<Ipv4Addr>>();
+
+        if svc.spec.endpoints != new_eps {
+            svc.spec.endpoints = new_eps;
+            update_service(&svc).await?;
+            svc_store.insert(svc.uri(), svc);
         }
     }
     tracing::info!("Resync succeeded!");
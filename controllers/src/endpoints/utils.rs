@@ -1,6 +1,10 @@
+use std::sync::Arc;
+
 use anyhow::{anyhow, Ok, Result};
 use resources::{
-    informer::{EventHandler, Informer, ListerWatcher, ResyncHandler, Store},
+    informer::{
+        label_index, EventHandler, Informer, ListerWatcher, ResyncHandler, Store, LABEL_INDEX,
+    },
     models,
     models::ErrResponse,
     objects::{pod::Pod, service::Service, KubeObject, Object},
@@ -128,7 +132,7 @@ pub fn create_pods_informer(
     }));
 
     // start the informer
-    Informer::new(lw, eh, rh)
+    Informer::new(lw, eh, rh).with_indexer(LABEL_INDEX, Arc::new(label_index::<Pod>))
 }
 
 pub async fn add_svc_endpoint(svc_store: Store<Service>, pod: Pod) -> Result<()> {
@@ -139,13 +143,18 @@ pub async fn add_svc_endpoint(svc_store: Store<Service>, pod: Pod) -> Result<()>
     };
 
     let mut store = svc_store.write().await;
-    for (_, svc) in store.iter_mut() {
-        if svc.spec.selector.matches(&pod.metadata.labels) && !svc.spec.endpoints.contains(&pod_ip)
-        {
-            svc.spec.endpoints.insert(pod_ip);
-            update_service(svc).await?;
-            tracing::info!("Add endpoint {} for service {}", pod_ip, svc.name());
-        }
+    let services = store
+        .values()
+        .filter(|svc| {
+            pod.metadata.labels.matches(&svc.spec.selector) && !svc.spec.endpoints.contains(&pod_ip)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    for mut svc in services {
+        svc.spec.endpoints.insert(pod_ip);
+        update_service(&svc).await?;
+        tracing::info!("Add endpoint {} for service {}", pod_ip, svc.name());
+        store.insert(svc.uri(), svc);
     }
     Ok(())
 }
@@ -158,16 +167,22 @@ pub async fn del_svc_endpoint(svc_store: Store<Service>, pod: Pod) -> Result<()>
     };
 
     let mut store = svc_store.write().await;
-    for (_, svc) in store.iter_mut() {
-        if svc.spec.selector.matches(&pod.metadata.labels) && svc.spec.endpoints.contains(&pod_ip) {
-            svc.spec.endpoints.retain(|ip| ip != &pod_ip);
-            update_service(svc).await?;
-            tracing::info!(
-                "Remove endpoint {} for service {}",
-                pod_ip,
-                svc.metadata.name
-            );
-        }
+    let services = store
+        .values()
+        .filter(|svc| {
+            pod.metadata.labels.matches(&svc.spec.selector) && svc.spec.endpoints.contains(&pod_ip)
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    for mut svc in services {
+        svc.spec.endpoints.retain(|ip| ip != &pod_ip);
+        update_service(&svc).await?;
+        tracing::info!(
+            "Remove endpoint {} for service {}",
+            pod_ip,
+            svc.metadata.name
+        );
+        store.insert(svc.uri(), svc);
     }
     Ok(())
 }
@@ -188,15 +203,9 @@ pub async fn update_service(svc: &Service) -> Result<()> {
 
 pub async fn add_enpoints(pod_store: Store<Pod>, mut svc: Service) -> Result<()> {
     let mut svc_changed = false;
-    let mut store = pod_store.write().await;
-    for (_, pod) in store.iter_mut() {
-        let pod_ip = if let Some(ip) = pod.get_ip() {
-            ip
-        } else {
-            continue;
-        };
-
-        if svc.spec.selector.matches(&pod.metadata.labels) {
+    let store = pod_store.read().await;
+    for pod in store.select(&svc.spec.selector) {
+        if let Some(pod_ip) = pod.get_ip() {
             svc_changed = true;
             svc.spec.endpoints.insert(pod_ip);
             tracing::info!("Add endpoint {} for service {}", pod_ip, svc.metadata.name);
@@ -6,11 +6,14 @@ use std::{
 
 use anyhow::{anyhow, Context, Error, Result};
 use fs_extra::dir::{copy, CopyOptions};
-use reqwest::Client;
 use resources::{
-    models::Response,
+    client::{Api, Client},
+    informer::{Event, EventHandler, Informer, ResyncHandler, ResyncNotification},
     objects::{
-        function::Function, hpa::HorizontalPodAutoscaler, replica_set::ReplicaSet, KubeObject,
+        condition::Condition,
+        function::{Function, FunctionConditionType},
+        hpa::HorizontalPodAutoscaler,
+        replica_set::ReplicaSet,
         Object,
     },
 };
@@ -20,33 +23,44 @@ use tokio::{
     task::JoinHandle,
 };
 
-use crate::{
-    utils::{create_informer, log_command, Event, ResyncNotification},
-    CONFIG, DOCKER_REGISTRY, TEMPLATES_DIR, TMP_DIR,
-};
+use crate::{utils::log_command, CONFIG, DOCKER_REGISTRY, TEMPLATES_DIR, TMP_DIR};
 
 pub struct FunctionController {
-    client: Client,
+    func_api: Api<Function>,
+    rs_api: Api<ReplicaSet>,
+    hpa_api: Api<HorizontalPodAutoscaler>,
+
     func_rx: Receiver<Event<Function>>,
     func_resync_rx: Receiver<ResyncNotification>,
     func_informer: Option<JoinHandle<Result<(), Error>>>,
 }
 
 impl FunctionController {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self> {
+        let client = Client::new(CONFIG.to_owned())?;
+        let func_api = Api::<Function>::new(client.to_owned(), "functions");
+        let rs_api = Api::<ReplicaSet>::new(client.to_owned(), "replicasets");
+        let hpa_api = Api::<HorizontalPodAutoscaler>::new(client, "horizontalpodautoscalers");
+
         let (func_tx, func_rx) = mpsc::channel::<Event<Function>>(16);
         let (func_resync_tx, func_resync_rx) = mpsc::channel::<ResyncNotification>(16);
-        let func_informer =
-            create_informer::<Function>("functions".to_string(), func_tx, func_resync_tx);
+        let func_informer = Informer::new(
+            func_api.lister_watcher(),
+            EventHandler::forward(func_tx),
+            ResyncHandler::forward(func_resync_tx),
+        );
 
         let func_informer = tokio::spawn(async move { func_informer.run().await });
 
-        Self {
-            client: Client::new(),
+        Ok(Self {
+            func_api,
+            rs_api,
+            hpa_api,
+
             func_rx,
             func_resync_rx,
             func_informer: Some(func_informer),
-        }
+        })
     }
 
     pub async fn run(&mut self) -> Result<()> {
@@ -77,16 +91,37 @@ impl FunctionController {
 
     async fn handle_function_add(&mut self, mut func: Function) -> Result<()> {
         tracing::info!("New function: {}", func.metadata.name);
-        let image_name = self.build_function_image(&mut func).await?;
-        func.status.as_mut().unwrap().image = Some(image_name);
+        let built = self.build_function_image(&func).await;
+        let status = func.status.as_mut().unwrap();
+        status.observed_generation = func.metadata.generation;
+        let condition = match &built {
+            Ok(image_name) => {
+                status.image = Some(image_name.to_owned());
+                Condition::new(FunctionConditionType::ImageBuilt, true)
+                    .with_reason("BuildSucceeded", "image built and pushed")
+            },
+            Err(e) => Condition::new(FunctionConditionType::ImageBuilt, false)
+                .with_reason("BuildFailed", format!("{:#}", e)),
+        };
+        status.conditions.set(condition);
         self.post_status(&func).await?;
-
-        self.create_replica_set(&func).await?;
-        self.create_hpa(&func).await?;
-        Ok(())
+        built?;
+
+        let (reason, result) = match self.create_replica_set(&func).await {
+            Ok(()) => ("FailedCreateHPA", self.create_hpa(&func).await),
+            Err(e) => ("FailedCreateReplicaSet", Err(e)),
+        };
+        let condition = match &result {
+            Ok(()) => Condition::new(FunctionConditionType::Ready, true),
+            Err(e) => Condition::new(FunctionConditionType::Ready, false)
+                .with_reason(reason, format!("{:#}", e)),
+        };
+        func.status.as_mut().unwrap().conditions.set(condition);
+        self.post_status(&func).await?;
+        result
     }
 
-    async fn build_function_image(&self, func: &mut Function) -> Result<String> {
+    async fn build_function_image(&self, func: &Function) -> Result<String> {
         self.prepare_file(func).await?;
         let image_name = self.build_image(func).await?;
         Ok(image_name)
@@ -163,58 +198,38 @@ impl FunctionController {
     }
 
     async fn post_status(&self, func: &Function) -> Result<()> {
-        let name = func.metadata.name.to_owned();
-        let response = self
-            .client
-            .put(format!("{}{}", CONFIG.api_server_url, func.uri()))
-            .json(&KubeObject::Function(func.to_owned()))
-            .send()
-            .await?
-            .json::<Response<()>>()
+        self.func_api
+            .replace(func)
             .await
             .with_context(|| "Error posting status")?;
-        tracing::info!(
-            "Posted status for Function {}: {}",
-            name,
-            response.msg.unwrap_or_else(|| "".to_string())
-        );
+        tracing::info!("Posted status for Function {}", func.metadata.name);
         Ok(())
     }
 
     async fn create_replica_set(&self, func: &Function) -> Result<()> {
-        let rs = ReplicaSet::from_function(func);
-        let response = self
-            .client
-            .post(format!("{}{}", CONFIG.api_server_url, rs.prefix()))
-            .json(&KubeObject::ReplicaSet(rs))
-            .send()
-            .await?
-            .json::<Response<()>>()
+        let rs = self
+            .rs_api
+            .create(&ReplicaSet::from_function(func))
             .await
             .with_context(|| "Error creating replicaset")?;
         tracing::info!(
-            "Created replicaset for Function {}: {}",
-            func.metadata.name,
-            response.msg.unwrap_or_else(|| "".to_string())
+            "Created replicaset {} for Function {}",
+            rs.metadata.name,
+            func.metadata.name
         );
         Ok(())
     }
 
     async fn create_hpa(&self, func: &Function) -> Result<()> {
-        let hpa = HorizontalPodAutoscaler::from_function(func);
-        let response = self
-            .client
-            .post(format!("{}{}", CONFIG.api_server_url, hpa.prefix()))
-            .json(&KubeObject::HorizontalPodAutoscaler(hpa))
-            .send()
-            .await?
-            .json::<Response<()>>()
+        let hpa = self
+            .hpa_api
+            .create(&HorizontalPodAutoscaler::from_function(func))
             .await
             .with_context(|| "Error creating HPA")?;
         tracing::info!(
-            "Created HPA for Function {}: {}",
-            func.metadata.name,
-            response.msg.unwrap_or_else(|| "".to_string())
+            "Created HPA {} for Function {}",
+            hpa.metadata.name,
+            func.metadata.name
         );
         Ok(())
     }
@@ -35,7 +35,7 @@ async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
     println!("api_server_url: {}", CONFIG.api_server_url);
 
-    let mut controller = FunctionController::new();
+    let mut controller = FunctionController::new()?;
     controller.run().await?;
     Ok(())
 }
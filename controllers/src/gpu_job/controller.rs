@@ -5,17 +5,23 @@ use std::{
     io::Write,
     path::PathBuf,
     process::Command,
+    sync::Arc,
 };
 
 use anyhow::{anyhow, Context, Error, Result};
 use resources::{
-    informer::Store,
-    models::Response,
+    client::{Api, Client},
+    informer::{
+        owner_index, owner_key, Event, EventHandler, Informer, ResyncHandler, ResyncNotification,
+        Store, OWNER_INDEX,
+    },
     objects::{
-        gpu_job::{GpuJob, GpuJobStatus},
+        condition::Condition,
+        gpu_job::{GpuJob, GpuJobConditionType, GpuJobStatus},
         object_reference::ObjectReference,
         pod::{Container, ImagePullPolicy, Pod, PodPhase, PodSpec, PodTemplateSpec, RestartPolicy},
-        KubeObject, Metadata, Object,
+        pod_group::{PodGroup, PodGroupSpec, POD_GROUP_LABEL},
+        Labels, Metadata, Object,
     },
 };
 use tokio::{
@@ -25,11 +31,15 @@ use tokio::{
 };
 
 use crate::{
-    utils::{create_informer, get_job_filename, get_job_status, Event, ResyncNotification},
+    utils::{get_job_filename, get_job_status},
     BASE_IMG, CONFIG, DOCKER_REGISTRY, GPU_SERVER_CONFIG, TMP_DIR,
 };
 
 pub struct GpuJobController {
+    job_api: Api<GpuJob>,
+    pod_api: Api<Pod>,
+    pod_group_api: Api<PodGroup>,
+
     job_rx: Receiver<Event<GpuJob>>,
     job_resync_rx: Receiver<ResyncNotification>,
     job_informer: Option<JoinHandle<Result<(), Error>>>,
@@ -42,21 +52,39 @@ pub struct GpuJobController {
 }
 
 impl GpuJobController {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self> {
+        let client = Client::new(CONFIG.to_owned())?;
+        let job_api = Api::<GpuJob>::new(client.to_owned(), "gpujobs");
+        let pod_api = Api::<Pod>::new(client.to_owned(), "pods");
+        let pod_group_api = Api::<PodGroup>::new(client, "podgroups");
+
         let (job_tx, job_rx) = mpsc::channel::<Event<GpuJob>>(16);
         let (job_resync_tx, job_resync_rx) = mpsc::channel::<ResyncNotification>(16);
-        let job_informer = create_informer::<GpuJob>("gpujobs".to_string(), job_tx, job_resync_tx);
+        let job_informer = Informer::new(
+            job_api.lister_watcher(),
+            EventHandler::forward(job_tx),
+            ResyncHandler::forward(job_resync_tx),
+        );
         let job_store = job_informer.get_store();
 
         let (pod_tx, pod_rx) = mpsc::channel::<Event<Pod>>(16);
         let (pod_resync_tx, pod_resync_rx) = mpsc::channel::<ResyncNotification>(16);
-        let pod_informer = create_informer::<Pod>("pods".to_string(), pod_tx, pod_resync_tx);
+        let pod_informer = Informer::new(
+            pod_api.lister_watcher(),
+            EventHandler::forward(pod_tx),
+            ResyncHandler::forward(pod_resync_tx),
+        )
+        .with_indexer(OWNER_INDEX, Arc::new(owner_index::<Pod>));
         let pod_store = pod_informer.get_store();
 
         let job_informer = tokio::spawn(async move { job_informer.run().await });
         let pod_informer = tokio::spawn(async move { pod_informer.run().await });
 
-        Self {
+        Ok(Self {
+            job_api,
+            pod_api,
+            pod_group_api,
+
             job_rx,
             job_resync_rx,
             job_informer: Some(job_informer),
@@ -66,7 +94,7 @@ impl GpuJobController {
             pod_resync_rx,
             pod_informer: Some(pod_informer),
             pod_store,
-        }
+        })
     }
 
     pub async fn run(&mut self) -> Result<()> {
@@ -82,7 +110,8 @@ impl GpuJobController {
                         Event::Delete(mut job) => {
                                 // FIXME: Remove all pods instead of one
                                 job.spec.parallelism = 0;
-                                self.reconcile(job).await
+                                let result = self.reconcile(job.to_owned()).await;
+                                self.delete_pod_group(&job).await.and(result)
                         },
                     };
                     if let Err(e) = result {
@@ -152,9 +181,15 @@ impl GpuJobController {
     async fn create_pod_template(&self, job: &mut GpuJob) -> Result<()> {
         self.prepare_file(job).await?;
         let image_name = self.build_image(job).await?;
+        self.create_pod_group(job).await?;
         let pod_template = PodTemplateSpec {
             metadata: Metadata {
-                name: job.name().to_owned(),
+                generate_name: Some(format!("{}-", job.name())),
+                // Parallel pods are scheduled as a whole
+                labels: Labels(HashMap::from([(
+                    POD_GROUP_LABEL.to_owned(),
+                    job.name().to_owned(),
+                )])),
                 owner_references: vec![job.object_reference()],
                 ..Default::default()
             },
@@ -166,6 +201,7 @@ impl GpuJobController {
                     ..Default::default()
                 }],
                 restart_policy: RestartPolicy::Never,
+                scheduler_name: job.spec.scheduler_name.to_owned(),
                 ..Default::default()
             },
         };
@@ -179,6 +215,67 @@ impl GpuJobController {
         Ok(())
     }
 
+    /// Create the pod group of the job,
+    /// so that its parallel pods are only bound when all of them can run.
+    async fn create_pod_group(&self, job: &GpuJob) -> Result<()> {
+        let pod_group = PodGroup {
+            metadata: Metadata {
+                name: job.name().to_owned(),
+                owner_references: vec![job.object_reference()],
+                ..Default::default()
+            },
+            spec: PodGroupSpec {
+                min_member: pod_group_min_member(job.spec.completions, job.spec.parallelism, 0),
+                schedule_timeout_seconds: None,
+            },
+            status: None,
+        };
+        match self.pod_group_api.create(&pod_group).await {
+            Ok(pod_group) => {
+                tracing::info!("podgroup/{} created", pod_group.metadata.name);
+                Ok(())
+            },
+            // Created before the controller restarts
+            Err(e) if e.is_conflict() => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Shrink minMember of the pod group as pods succeed,
+    /// otherwise the last pods never reach the old minMember and can't be scheduled.
+    async fn sync_pod_group(&self, job: &GpuJob, succeeded: u32) -> Result<()> {
+        let min_member =
+            pod_group_min_member(job.spec.completions, job.spec.parallelism, succeeded);
+        let mut pod_group = match self.pod_group_api.get(job.name()).await {
+            Ok(pod_group) => pod_group,
+            Err(e) if e.is_not_found() => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if pod_group.spec.min_member == min_member {
+            return Ok(());
+        }
+        tracing::info!(
+            "Updating minMember of podgroup/{}: {} -> {}",
+            job.name(),
+            pod_group.spec.min_member,
+            min_member
+        );
+        pod_group.spec.min_member = min_member;
+        self.pod_group_api.replace(&pod_group).await?;
+        Ok(())
+    }
+
+    async fn delete_pod_group(&self, job: &GpuJob) -> Result<()> {
+        match self.pod_group_api.delete(job.name()).await {
+            Ok(()) => {
+                tracing::info!("podgroup/{} deleted", job.name());
+                Ok(())
+            },
+            Err(e) if e.is_not_found() => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     async fn prepare_file(&self, job: &GpuJob) -> Result<()> {
         self.fetch_code(job).await?;
         self.gen_config_file(job).await?;
@@ -296,12 +393,18 @@ impl GpuJobController {
             .with_context(|| "GpuJob has no status")?;
         tracing::info!("Tuning GpuJob {}", job.metadata.name);
 
-        let want_active = min(
-            job.spec.completions - status.succeeded,
-            job.spec.parallelism,
-        );
+        let want_active = if status.conditions.is_true(&GpuJobConditionType::Failed) {
+            // Stop retrying once the backoff limit is exceeded
+            0
+        } else {
+            min(
+                job.spec.completions - status.succeeded,
+                job.spec.parallelism,
+            )
+        };
         match status.active.cmp(&want_active) {
             Ordering::Less => {
+                self.sync_pod_group(&job, status.succeeded).await?;
                 // Create a new pod, if more pods are needed, they'll be created later
                 self.create_pod(&job).await?;
             },
@@ -318,26 +421,12 @@ impl GpuJobController {
     }
 
     async fn post_status(&self, job: &GpuJob) -> Result<()> {
-        let client = reqwest::Client::new();
-        let name = job.metadata.name.to_owned();
-        let response = client
-            .put(format!("{}{}", CONFIG.api_server_url, job.uri()))
-            .json(&KubeObject::GpuJob(job.to_owned()))
-            .send()
-            .await?
-            .json::<Response<()>>()
-            .await
-            .with_context(|| "Error posting status")?;
-        tracing::info!(
-            "Posted status for GpuJob {}: {}",
-            name,
-            response.msg.unwrap_or_else(|| "".to_string())
-        );
+        self.job_api.replace(job).await?;
+        tracing::info!("Posted status for GpuJob {}", job.metadata.name);
         Ok(())
     }
 
     async fn create_pod(&self, job: &GpuJob) -> Result<()> {
-        let client = reqwest::Client::new();
         let job_status = get_job_status(job)?;
         let template = job_status
             .template
@@ -349,32 +438,14 @@ impl GpuJobController {
             spec: template.spec.clone(),
             status: None,
         };
-        let response = client
-            .post(format!("{}/api/v1/pods", CONFIG.api_server_url))
-            .json(&KubeObject::Pod(pod))
-            .send()
-            .await?
-            .json::<Response<()>>()
-            .await
-            .with_context(|| "Error creating pod")?;
-        if let Some(msg) = response.msg {
-            tracing::info!("{}", msg);
-        }
+        let pod = self.pod_api.create(&pod).await?;
+        tracing::info!("pod/{} created", pod.metadata.name);
         Ok(())
     }
 
     async fn delete_pod(&self, name: String) -> Result<()> {
-        let client = reqwest::Client::new();
-        let response = client
-            .delete(format!("{}/api/v1/pods/{}", CONFIG.api_server_url, name))
-            .send()
-            .await?
-            .json::<Response<()>>()
-            .await
-            .with_context(|| "Error deleting pod")?;
-        if let Some(msg) = response.msg {
-            tracing::info!("{}", msg);
-        }
+        self.pod_api.delete(&name).await?;
+        tracing::info!("pod/{} deleted", name);
         Ok(())
     }
 
@@ -397,28 +468,19 @@ impl GpuJobController {
     async fn get_pods(&self, job: &GpuJob) -> Vec<Pod> {
         let store = self.pod_store.read().await;
         store
-            .iter()
-            .filter(|(_, pod)| {
-                pod.metadata
-                    .owner_references
-                    .contains(&job.object_reference())
-            })
-            .map(|(_, pod)| pod.to_owned())
+            .by_index(OWNER_INDEX, &owner_key(&job.object_reference()))
+            .into_iter()
+            .cloned()
             .collect::<Vec<_>>()
     }
 
     async fn get_active_pods(&self, job: &GpuJob) -> Vec<Pod> {
         let store = self.pod_store.read().await;
         store
-            .iter()
-            .filter(|(_, pod)| {
-                pod.is_active()
-                    && pod
-                        .metadata
-                        .owner_references
-                        .contains(&job.object_reference())
-            })
-            .map(|(_, pod)| pod.to_owned())
+            .by_index(OWNER_INDEX, &owner_key(&job.object_reference()))
+            .into_iter()
+            .filter(|pod| pod.is_active())
+            .cloned()
             .collect::<Vec<_>>()
     }
 
@@ -440,15 +502,31 @@ impl GpuJobController {
             }
         });
 
-        GpuJobStatus {
+        let mut status = GpuJobStatus {
             active,
             failed,
             succeeded,
+            observed_generation: job.metadata.generation,
             ..job
                 .status
                 .to_owned()
                 .unwrap_or_else(|| panic!("job {} should have status", job.name()))
+        };
+        if succeeded >= job.spec.completions {
+            status.conditions.set(
+                Condition::new(GpuJobConditionType::Complete, true)
+                    .with_reason("Completed", format!("{} pods succeeded", succeeded)),
+            );
+        }
+        if failed > job.spec.back_off_limit {
+            status.conditions.set(
+                Condition::new(GpuJobConditionType::Failed, true).with_reason(
+                    "BackoffLimitExceeded",
+                    "Job has reached the specified backoff limit",
+                ),
+            );
         }
+        status
     }
 
     async fn get_pod_to_delete(&self, job: &GpuJob) -> String {
@@ -511,3 +589,33 @@ impl GpuJobController {
         }
     }
 }
+
+/// Pods of the job which must be placed together,
+/// the remaining completions may be fewer than the parallelism.
+fn pod_group_min_member(completions: u32, parallelism: u32, succeeded: u32) -> u32 {
+    min(completions.saturating_sub(succeeded), parallelism).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pod_group_min_member;
+
+    #[test]
+    fn min_member_limited_by_parallelism() {
+        assert_eq!(pod_group_min_member(3, 2, 0), 2);
+        assert_eq!(pod_group_min_member(1, 4, 0), 1);
+    }
+
+    #[test]
+    fn min_member_shrinks_with_remaining_completions() {
+        // The last pod must not wait for a second member which is never created
+        assert_eq!(pod_group_min_member(3, 2, 1), 2);
+        assert_eq!(pod_group_min_member(3, 2, 2), 1);
+    }
+
+    #[test]
+    fn min_member_at_least_one() {
+        assert_eq!(pod_group_min_member(3, 2, 3), 1);
+        assert_eq!(pod_group_min_member(3, 0, 0), 1);
+    }
+}
@@ -49,7 +49,7 @@ lazy_static! {
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    let mut controller = GpuJobController::new();
+    let mut controller = GpuJobController::new()?;
     controller.run().await?;
     Ok(())
 }
@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use resources::{
+    client::Api,
+    config::node_lifecycle::NodeLifecycleConfig,
+    controller::{async_trait, Action, Reconciler},
+    informer::Store,
+    objects::{
+        condition::{Condition, ConditionStatus, Conditions},
+        node::{Node, NodeConditionType, TAINT_NODE_NOT_READY, TAINT_NODE_UNREACHABLE},
+        taint::{Taint, TaintEffect},
+        Object,
+    },
+};
+
+use crate::taint_manager::TaintManager;
+
+/// Mark the Ready condition of nodes from heartbeats of rKubelet,
+/// and taint nodes that stop posting status.
+///
+/// A node missing heartbeats for the grace period becomes NotReady
+/// and repels new pods, after the eviction timeout it becomes Unknown
+/// and its pods are evicted by the [`TaintManager`], so that their owners recreate them elsewhere.
+pub struct NodeLifecycleController {
+    config: NodeLifecycleConfig,
+    node_store: Store<Node>,
+    node_api: Api<Node>,
+    taint_manager: TaintManager,
+}
+
+impl NodeLifecycleController {
+    pub fn new(
+        config: NodeLifecycleConfig,
+        node_store: Store<Node>,
+        node_api: Api<Node>,
+        taint_manager: TaintManager,
+    ) -> Self {
+        Self {
+            config,
+            node_store,
+            node_api,
+            taint_manager,
+        }
+    }
+
+    /// Update the Ready condition and lifecycle taints of a freshly fetched node.
+    ///
+    /// Only the Ready condition is posted, pressure conditions are owned by rKubelet.
+    async fn update_node(&self, mut node: Node) -> Result<()> {
+        let condition = self.ready_condition(&node);
+        let status = condition.status;
+        if node.status.conditions.set(condition.to_owned()) {
+            tracing::info!("Ready condition of node {} is {}", node.name(), status);
+            let mut update = node.to_owned();
+            update.status.conditions = Conditions(vec![condition]);
+            self.node_api.replace(&update).await?;
+        }
+        if set_lifecycle_taint(&mut node.spec.taints, lifecycle_taint(status)) {
+            tracing::info!(
+                "Updating taints of node {} for Ready {}",
+                node.name(),
+                status
+            );
+            self.node_api.patch(&node).await?;
+        }
+        Ok(())
+    }
+
+    /// Ready condition of the node judged from its last heartbeat
+    fn ready_condition(&self, node: &Node) -> Condition<NodeConditionType> {
+        let age = node.heartbeat_age().num_seconds();
+        if age < self.config.node_monitor_grace_period_seconds as i64 {
+            Condition::new(NodeConditionType::Ready, true)
+                .with_reason("KubeletReady", "rKubelet is posting ready status")
+        } else if age < self.config.pod_eviction_timeout_seconds as i64 {
+            Condition::new(NodeConditionType::Ready, false)
+                .with_reason("KubeletNotReady", "rKubelet missed status reports")
+        } else {
+            Condition::new(NodeConditionType::Ready, ConditionStatus::Unknown)
+                .with_reason("NodeStatusUnknown", "rKubelet stopped posting node status")
+        }
+    }
+}
+
+/// Taint added for the status of the Ready condition
+fn lifecycle_taint(status: ConditionStatus) -> Option<Taint> {
+    let (key, effect) = match status {
+        ConditionStatus::True => return None,
+        ConditionStatus::False => (TAINT_NODE_NOT_READY, TaintEffect::NoSchedule),
+        ConditionStatus::Unknown => (TAINT_NODE_UNREACHABLE, TaintEffect::NoExecute),
+    };
+    Some(Taint {
+        key: key.to_owned(),
+        value: None,
+        effect,
+        time_added: None,
+    })
+}
+
+/// Replace taints added for the Ready condition, return whether anything changed
+fn set_lifecycle_taint(taints: &mut Vec<Taint>, taint: Option<Taint>) -> bool {
+    let is_lifecycle_taint =
+        |t: &Taint| t.key == TAINT_NODE_NOT_READY || t.key == TAINT_NODE_UNREACHABLE;
+    let existing = taints
+        .iter()
+        .filter(|t| is_lifecycle_taint(t))
+        .collect::<Vec<_>>();
+    let up_to_date = match &taint {
+        Some(taint) => {
+            existing.len() == 1
+                && existing[0].key == taint.key
+                && existing[0].effect == taint.effect
+        },
+        None => existing.is_empty(),
+    };
+    if up_to_date {
+        return false;
+    }
+    taints.retain(|t| !is_lifecycle_taint(t));
+    taints.extend(taint);
+    true
+}
+
+#[async_trait]
+impl Reconciler for NodeLifecycleController {
+    async fn reconcile(&self, key: &str) -> Result<Action> {
+        let mut cached = match self.node_store.read().await.get(key) {
+            Some(node) => node.to_owned(),
+            None => return Ok(Action::AwaitChange),
+        };
+
+        let condition = self.ready_condition(&cached);
+        let taint = lifecycle_taint(condition.status);
+        if cached.status.conditions.set(condition)
+            || set_lifecycle_taint(&mut cached.spec.taints, taint)
+        {
+            // The cached node may miss updates of rKubelet and rkubectl, don't revert them
+            match self.node_api.get(cached.name()).await {
+                Ok(node) => self.update_node(node).await?,
+                Err(e) if e.is_not_found() => return Ok(Action::AwaitChange),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        // A node going silent triggers no event, so check it again periodically
+        let period = Duration::from_secs(self.config.node_monitor_period_seconds);
+        match self.taint_manager.reconcile(key).await? {
+            Action::Requeue(after) => Ok(Action::Requeue(after.min(period))),
+            Action::AwaitChange => Ok(Action::Requeue(period)),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate lazy_static;
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use config::{Config, File};
+use lifecycle::NodeLifecycleController;
+use resources::{
+    client::{Api, Client},
+    config::{load_or_default, node_lifecycle::NodeLifecycleConfig, ClusterConfig},
+    controller::Controller,
+    informer::{node_name_index, NODE_NAME_INDEX},
+    objects::{node::Node, pod::Pod},
+};
+use taint_manager::TaintManager;
+
+mod lifecycle;
+mod taint_manager;
+
+const CONFIG_PATH: &str = "/etc/rminik8s/nodelifecycle.yaml";
+
+lazy_static! {
+    pub static ref CONFIG: ClusterConfig = Config::builder()
+        .add_source(File::with_name("/etc/rminik8s/controller-manager.yaml").required(false))
+        .set_override_option("apiServerUrl", std::env::var("API_SERVER_URL").ok())
+        .unwrap()
+        .set_override_option(
+            "apiServerWatchUrl",
+            std::env::var("API_SERVER_WATCH_URL").ok(),
+        )
+        .unwrap()
+        .build()
+        .unwrap_or_default()
+        .try_deserialize::<ClusterConfig>()
+        .with_context(|| "Failed to parse config".to_string())
+        .unwrap_or_default();
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let config = load_or_default::<NodeLifecycleConfig>(CONFIG_PATH)?;
+    tracing::info!("Node lifecycle config: {:?}", config);
+    let client = Client::new(CONFIG.to_owned())?;
+    let pod_api = Api::<Pod>::new(client.to_owned(), "pods");
+    // Changes of pods affect the node they're bound to
+    let node_api = Api::<Node>::new(client, "nodes");
+    let controller = Controller::new(node_api.to_owned())
+        .watches(pod_api.to_owned(), |pod: &Pod| {
+            pod.spec.node_name.iter().cloned().collect()
+        });
+    let pod_store = controller
+        .store_of::<Pod>()
+        .expect("pods are watched by the controller");
+    pod_store
+        .write()
+        .await
+        .add_indexer(NODE_NAME_INDEX, Arc::new(node_name_index));
+    let taint_manager = TaintManager::new(controller.store(), pod_store, pod_api);
+    let lifecycle =
+        NodeLifecycleController::new(config, controller.store(), node_api, taint_manager);
+    controller.run(lifecycle).await
+}
@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{Local, NaiveDateTime};
+use resources::{
+    client::Api,
+    controller::{async_trait, Action, Reconciler},
+    informer::{Store, NODE_NAME_INDEX},
+    objects::{
+        node::Node,
+        pod::Pod,
+        taint::{Taint, TaintEffect},
+        Object,
+    },
+};
+
+/// Evict pods from nodes with NoExecute taints they don't tolerate.
+///
+/// Pods tolerating a taint for `toleration_seconds` are evicted
+/// once the period since the taint was added is over.
+pub struct TaintManager {
+    node_store: Store<Node>,
+    pod_store: Store<Pod>,
+    pod_api: Api<Pod>,
+}
+
+impl TaintManager {
+    pub fn new(node_store: Store<Node>, pod_store: Store<Pod>, pod_api: Api<Pod>) -> Self {
+        Self {
+            node_store,
+            pod_store,
+            pod_api,
+        }
+    }
+
+    async fn evict(&self, pod: &Pod, taint: &Taint) -> Result<()> {
+        tracing::info!("Evicting pod {} for taint {}", pod.name(), taint);
+        match self.pod_api.delete(pod.name()).await {
+            Err(e) if !e.is_not_found() => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// When the pod should be evicted for the taint, `None` if never.
+///
+/// A pod without any toleration of the taint is evicted right away,
+/// otherwise it's the shortest toleration period after the taint is added.
+fn eviction_time(pod: &Pod, taint: &Taint) -> Option<NaiveDateTime> {
+    let mut tolerations = pod
+        .spec
+        .tolerations
+        .iter()
+        .filter(|toleration| toleration.tolerates(taint))
+        .peekable();
+    if tolerations.peek().is_none() {
+        return Some(NaiveDateTime::from_timestamp(0, 0));
+    }
+    let seconds = tolerations
+        .map(|toleration| toleration.toleration_seconds)
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .min()?;
+    let time_added = taint.time_added.unwrap_or_else(|| Local::now().naive_utc());
+    Some(time_added + chrono::Duration::seconds(seconds))
+}
+
+#[async_trait]
+impl Reconciler for TaintManager {
+    async fn reconcile(&self, key: &str) -> Result<Action> {
+        let node = match self.node_store.read().await.get(key) {
+            Some(node) => node.to_owned(),
+            None => return Ok(Action::AwaitChange),
+        };
+        let taints = node
+            .spec
+            .taints
+            .iter()
+            .filter(|taint| taint.effect == TaintEffect::NoExecute)
+            .collect::<Vec<_>>();
+        if taints.is_empty() {
+            return Ok(Action::AwaitChange);
+        }
+
+        let pods = self
+            .pod_store
+            .read()
+            .await
+            .by_index(NODE_NAME_INDEX, &node.metadata.name)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let now = Local::now().naive_utc();
+        let mut next_eviction: Option<NaiveDateTime> = None;
+        for pod in pods.iter() {
+            let earliest = taints
+                .iter()
+                .filter_map(|taint| eviction_time(pod, taint).map(|time| (time, *taint)))
+                .min_by_key(|(time, _)| *time);
+            match earliest {
+                Some((time, taint)) if time <= now => self.evict(pod, taint).await?,
+                Some((time, _)) => {
+                    next_eviction = Some(next_eviction.map_or(time, |next| next.min(time)));
+                },
+                None => {},
+            }
+        }
+
+        match next_eviction {
+            Some(time) => Ok(Action::Requeue(
+                (time - now).to_std().unwrap_or(Duration::ZERO),
+            )),
+            None => Ok(Action::AwaitChange),
+        }
+    }
+}
@@ -1,39 +1,34 @@
 use std::{
     cmp::{max, min, Ordering},
-    collections::{HashMap, HashSet, LinkedList},
+    collections::{HashMap, LinkedList},
 };
 
 use anyhow::{Context, Error, Result};
 use chrono::{Duration, Local, NaiveDateTime};
-use futures_delay_queue::{delay_queue, DelayQueue};
-use futures_intrusive::{buffer::GrowingHeapBuf, channel::shared::GenericReceiver};
-use parking_lot::RawMutex;
 use resources::{
-    informer::{EventHandler, Informer, ResyncHandler, Store},
+    client::{Api, Client},
+    informer::{EventHandler, Informer, ResyncHandler, ResyncNotification, Store, WorkQueue},
     objects::{
+        condition::{Condition, Conditions},
         hpa::{
             FunctionMetricSource, HPAScalingRules, HorizontalPodAutoscaler,
-            HorizontalPodAutoscalerBehavior, HorizontalPodAutoscalerStatus, MetricSource,
-            MetricTarget, PolicySelection, ResourceMetricSource, ScalingPolicyType,
+            HorizontalPodAutoscalerBehavior, HorizontalPodAutoscalerConditionType as ConditionType,
+            HorizontalPodAutoscalerStatus, MetricSource, MetricTarget, PolicySelection,
+            ResourceMetricSource, ScalingPolicyType,
         },
+        metrics::Resource,
         pod::Pod,
+        replica_set::ReplicaSet,
         KubeObject, Labels, Object,
     },
 };
 use tokio::{
     select,
-    sync::{
-        mpsc,
-        mpsc::{Receiver, Sender},
-    },
+    sync::mpsc::{self, Receiver, Sender},
     task::JoinHandle,
 };
 
-use crate::{
-    replica_calculator::ReplicaCalculator,
-    utils::{create_lister_watcher, get_scale_target, post_update},
-    SYNC_PERIOD,
-};
+use crate::{replica_calculator::ReplicaCalculator, utils::get_scale_target, CONFIG, SYNC_PERIOD};
 
 #[derive(Debug, Clone)]
 struct Recommendation {
@@ -47,11 +42,11 @@ struct ScaleEvent {
     pub time: NaiveDateTime,
 }
 
-#[derive(Debug)]
-struct ResyncNotification;
-
 pub struct PodAutoscaler {
-    rx: Receiver<String>,
+    client: Client,
+    hpa_api: Api<HorizontalPodAutoscaler>,
+    rs_api: Api<ReplicaSet>,
+
     resync_rx: Receiver<ResyncNotification>,
     hpa_informer: Option<JoinHandle<Result<(), Error>>>,
     hpa_store: Store<HorizontalPodAutoscaler>,
@@ -63,41 +58,46 @@ pub struct PodAutoscaler {
     scale_up_events: HashMap<String, LinkedList<ScaleEvent>>,
     scale_down_events: HashMap<String, LinkedList<ScaleEvent>>,
 
-    work_queue: DelayQueue<String, GrowingHeapBuf<String>>,
-    work_queue_rx: GenericReceiver<RawMutex, String, GrowingHeapBuf<String>>,
-    in_queue: HashSet<String>,
+    /// Keyed by HPA URI
+    work_queue: WorkQueue,
 }
 
 impl PodAutoscaler {
-    pub fn new() -> Self {
-        let (tx, rx) = mpsc::channel::<String>(16);
+    pub fn new() -> Result<Self> {
+        let client = Client::new(CONFIG.to_owned())?;
+        let hpa_api =
+            Api::<HorizontalPodAutoscaler>::new(client.to_owned(), "horizontalpodautoscalers");
+        let rs_api = Api::<ReplicaSet>::new(client.to_owned(), "replicasets");
+        let pod_api = Api::<Pod>::new(client.to_owned(), "pods");
+
+        let work_queue = WorkQueue::default();
         let (resync_tx, resync_rx) = mpsc::channel::<ResyncNotification>(16);
-        let hpa_informer = PodAutoscaler::create_hpa_informer(tx, resync_tx);
+        let hpa_informer =
+            PodAutoscaler::create_hpa_informer(&hpa_api, work_queue.to_owned(), resync_tx);
         let hpa_store = hpa_informer.get_store();
-        let pod_informer = PodAutoscaler::create_pod_informer();
+        let pod_informer = PodAutoscaler::create_pod_informer(&pod_api);
         let pod_store = pod_informer.get_store();
 
         let hpa_informer = tokio::spawn(async move { hpa_informer.run().await });
         let pod_informer = tokio::spawn(async move { pod_informer.run().await });
 
-        let (work_queue, work_queue_rx) = delay_queue::<String>();
+        Ok(Self {
+            client: client.to_owned(),
+            hpa_api,
+            rs_api,
 
-        Self {
-            rx,
             resync_rx,
             hpa_informer: Some(hpa_informer),
             hpa_store,
             pod_informer: Some(pod_informer),
 
-            calculator: ReplicaCalculator::new(pod_store),
+            calculator: ReplicaCalculator::new(pod_store, client),
             recommendations: HashMap::new(),
             scale_up_events: HashMap::new(),
             scale_down_events: HashMap::new(),
 
             work_queue,
-            work_queue_rx,
-            in_queue: HashSet::new(),
-        }
+        })
     }
 
     pub async fn run(&mut self) -> Result<()> {
@@ -105,39 +105,13 @@ impl PodAutoscaler {
 
         loop {
             select! {
-                Some(hpa_name) = self.rx.recv() => {
-                    // Enqueue immediately if HPA is newly added of updated
-                    self.in_queue.insert(hpa_name.to_owned());
-                    self.work_queue.insert_at(hpa_name, std::time::Instant::now());
-                },
                 Some(_) = self.resync_rx.recv() => {
                     self.handle_resync().await;
                 },
-                Some(hpa_name) = self.work_queue_rx.receive() => {
-                    self.in_queue.remove(&hpa_name);
-                    let store = self.hpa_store.read().await;
-                    let object = store.get(&format!("/api/v1/horizontalpodautoscalers/{}", hpa_name));
-                    match object {
-                        Some(object) => {
-                            let object = object.clone();
-                            drop(store);
-                            let result = self.reconcile(object).await;
-                            match result {
-                                Ok(_) => tracing::info!("Reconciled HPA {}", hpa_name),
-                                Err(e) =>
-                                    tracing::error!("Error reconciling {}: {:#}", hpa_name, e)
-                                ,
-                            }
-                            self.enqueue_hpa(&hpa_name, std::time::Duration::from_secs(SYNC_PERIOD as u64));
-                        },
-                        None => {
-                            tracing::info!("Horizontal Pod Autoscaler {} deleted", hpa_name);
-                            self.recommendations.remove(&hpa_name);
-                            self.scale_up_events.remove(&hpa_name);
-                            self.scale_down_events.remove(&hpa_name);
-                        },
-                    }
-                }
+                Some(key) = self.work_queue.get() => {
+                    self.process(&key).await;
+                    self.work_queue.done(&key);
+                },
                 else => break
             }
         }
@@ -150,14 +124,56 @@ impl PodAutoscaler {
         Ok(())
     }
 
+    async fn process(&mut self, key: &str) {
+        let store = self.hpa_store.read().await;
+        let object = store.get(key).cloned();
+        drop(store);
+        match object {
+            Some(hpa) => {
+                let hpa_name = hpa.metadata.name.to_owned();
+                match self.reconcile(hpa).await {
+                    Ok(_) => {
+                        tracing::info!("Reconciled HPA {}", hpa_name);
+                        self.work_queue.forget(key);
+                        self.work_queue
+                            .add_after(key, std::time::Duration::from_secs(SYNC_PERIOD as u64));
+                    },
+                    Err(e) => {
+                        tracing::error!("Error reconciling {}: {:#}", hpa_name, e);
+                        self.work_queue.add_rate_limited(key);
+                    },
+                }
+            },
+            None => {
+                let hpa_name = key.rsplit('/').next().unwrap_or(key);
+                tracing::info!("Horizontal Pod Autoscaler {} deleted", hpa_name);
+                self.recommendations.remove(hpa_name);
+                self.scale_up_events.remove(hpa_name);
+                self.scale_down_events.remove(hpa_name);
+                self.work_queue.forget(key);
+            },
+        }
+    }
+
     async fn reconcile(&mut self, mut hpa: HorizontalPodAutoscaler) -> Result<()> {
         let now = Local::now().naive_utc();
         let hpa_name = &hpa.metadata.name;
-        let mut target = get_scale_target(&hpa.spec.scale_target_ref).await?;
         let status = hpa
             .status
             .as_ref()
             .with_context(|| "Failed to get HPA status")?;
+        let mut conditions = status.conditions.to_owned();
+        let target = match get_scale_target(&self.client, &hpa.spec.scale_target_ref).await {
+            Ok(target) => target,
+            Err(e) => {
+                conditions.set(
+                    Condition::new(ConditionType::AbleToScale, false)
+                        .with_reason("FailedGetScale", format!("{:#}", e)),
+                );
+                self.post_conditions(&hpa, conditions).await;
+                return Err(e);
+            },
+        };
 
         match target {
             KubeObject::ReplicaSet(mut rs) => {
@@ -187,10 +203,28 @@ impl PodAutoscaler {
                 let desired_replicas = if current_replicas == 0 {
                     // Scaling disabled
                     tracing::info!("Scaling disabled for {}", hpa_name);
+                    conditions.set(
+                        Condition::new(ConditionType::ScalingActive, false).with_reason(
+                            "ScalingDisabled",
+                            "scaling is disabled since the replica count of the target is zero",
+                        ),
+                    );
                     0
                 } else if current_replicas > hpa.spec.max_replicas {
+                    conditions.set(
+                        Condition::new(ConditionType::ScalingLimited, true).with_reason(
+                            "TooManyReplicas",
+                            "the desired replica count is more than the maximum replica count",
+                        ),
+                    );
                     hpa.spec.max_replicas
                 } else if current_replicas < hpa.spec.min_replicas {
+                    conditions.set(
+                        Condition::new(ConditionType::ScalingLimited, true).with_reason(
+                            "TooFewReplicas",
+                            "the desired replica count is less than the minimum replica count",
+                        ),
+                    );
                     hpa.spec.min_replicas
                 } else {
                     let desired_replicas = match &hpa.spec.metrics {
@@ -200,30 +234,76 @@ impl PodAutoscaler {
                                 current_replicas,
                                 &rs.spec.selector,
                             )
-                            .await?
+                            .await
                         },
                         MetricSource::Function(metrics) => {
                             self.compute_function_replicas(metrics, current_replicas)
-                                .await?
+                                .await
+                        },
+                    };
+                    let desired_replicas = match desired_replicas {
+                        Ok(desired_replicas) => desired_replicas,
+                        Err(e) => {
+                            conditions.set(
+                                Condition::new(ConditionType::ScalingActive, false)
+                                    .with_reason("FailedGetMetrics", format!("{:#}", e)),
+                            );
+                            self.post_conditions(&hpa, conditions).await;
+                            return Err(e);
                         },
                     };
+                    conditions.set(
+                        Condition::new(ConditionType::ScalingActive, true).with_reason(
+                            "ValidMetricFound",
+                            "the autoscaler was able to successfully calculate a replica count",
+                        ),
+                    );
                     tracing::info!("Desired replicas for {}: {}", hpa_name, desired_replicas);
-                    self.normalize_desired_replicas(
+                    let normalized = self.normalize_desired_replicas(
                         hpa_name,
                         &hpa,
                         current_replicas,
                         desired_replicas,
-                    )
+                    );
+                    conditions.set(if desired_replicas > hpa.spec.max_replicas {
+                        Condition::new(ConditionType::ScalingLimited, true).with_reason(
+                            "TooManyReplicas",
+                            "the desired replica count is more than the maximum replica count",
+                        )
+                    } else if desired_replicas < hpa.spec.min_replicas {
+                        Condition::new(ConditionType::ScalingLimited, true).with_reason(
+                            "TooFewReplicas",
+                            "the desired replica count is less than the minimum replica count",
+                        )
+                    } else {
+                        Condition::new(ConditionType::ScalingLimited, false).with_reason(
+                            "DesiredWithinRange",
+                            "the desired count is within the acceptable range",
+                        )
+                    });
+                    normalized
                 };
 
                 if current_replicas != desired_replicas {
                     // Do scale
                     rs.spec.replicas = desired_replicas;
-                    target = KubeObject::ReplicaSet(rs);
-                    post_update(&target).await?;
+                    if let Err(e) = self.rs_api.replace(&rs).await {
+                        conditions.set(
+                            Condition::new(ConditionType::AbleToScale, false)
+                                .with_reason("FailedUpdateScale", format!("{:#}", e)),
+                        );
+                        self.post_conditions(&hpa, conditions).await;
+                        return Err(e.into());
+                    }
+                    conditions.set(
+                        Condition::new(ConditionType::AbleToScale, true).with_reason(
+                            "SucceededRescale",
+                            format!("the autoscaler set the new size to {}", desired_replicas),
+                        ),
+                    );
                     tracing::info!(
                         "Scaled {} from {} to {}",
-                        target.name(),
+                        rs.name(),
                         current_replicas,
                         desired_replicas
                     );
@@ -233,6 +313,13 @@ impl PodAutoscaler {
                         current_replicas,
                         desired_replicas,
                     );
+                } else {
+                    conditions.set(
+                        Condition::new(ConditionType::AbleToScale, true).with_reason(
+                            "ReadyForNewScale",
+                            "recommended size matches current size",
+                        ),
+                    );
                 }
                 let new_status = HorizontalPodAutoscalerStatus {
                     current_replicas: if desired_replicas == 0 {
@@ -246,11 +333,14 @@ impl PodAutoscaler {
                     } else {
                         status.last_scale_time
                     },
+                    observed_generation: hpa.metadata.generation,
+                    conditions,
                 };
                 if !status.eq(&new_status) {
                     // Update and post status
                     hpa.status = Some(new_status);
-                    post_update(&KubeObject::HorizontalPodAutoscaler(hpa)).await?;
+                    self.hpa_api.replace(&hpa).await?;
+                    tracing::info!("Updated status of HPA {}", hpa.metadata.name);
                 }
             },
             _ => {
@@ -260,6 +350,26 @@ impl PodAutoscaler {
         Ok(())
     }
 
+    /// Post conditions along with the last status, when reconciling fails halfway
+    async fn post_conditions(
+        &self,
+        hpa: &HorizontalPodAutoscaler,
+        conditions: Conditions<ConditionType>,
+    ) {
+        let mut hpa = hpa.to_owned();
+        match hpa.status.as_mut() {
+            Some(status) if status.conditions != conditions => status.conditions = conditions,
+            _ => return,
+        }
+        if let Err(e) = self.hpa_api.replace(&hpa).await {
+            tracing::warn!(
+                "Failed to update conditions of HPA {}: {:#}",
+                hpa.metadata.name,
+                e
+            );
+        }
+    }
+
     async fn compute_resource_replicas(
         &self,
         metrics: &ResourceMetricSource,
@@ -278,6 +388,12 @@ impl PodAutoscaler {
                     .await
             },
             MetricTarget::AverageValue(target_value) => {
+                // Metrics are collected in milli CPU and bytes
+                let target_value = match metrics.name {
+                    Resource::CPU => target_value.milli_value(),
+                    Resource::Memory => target_value.value(),
+                }
+                .max(0) as u64;
                 self.calculator
                     .calc_replicas_by_value(current_replicas, target_value, &metrics.name, selector)
                     .await
@@ -580,45 +696,38 @@ impl PodAutoscaler {
     }
 
     fn create_hpa_informer(
-        tx: Sender<String>,
+        hpa_api: &Api<HorizontalPodAutoscaler>,
+        work_queue: WorkQueue,
         resync_tx: Sender<ResyncNotification>,
     ) -> Informer<HorizontalPodAutoscaler> {
-        let lw = create_lister_watcher("horizontalpodautoscalers".to_string());
+        let lw = hpa_api.lister_watcher();
 
-        let tx_add = tx;
-        let tx_update = tx_add.clone();
+        let queue_add = work_queue;
+        let queue_update = queue_add.clone();
+        let queue_delete = queue_add.clone();
         let eh = EventHandler::<HorizontalPodAutoscaler> {
             add_cls: Box::new(move |new| {
-                let tx_add = tx_add.clone();
-                Box::pin(async move {
-                    tx_add.send(new.metadata.name).await?;
-                    Ok(())
-                })
+                // Reconcile immediately if HPA is newly added or updated
+                queue_add.add(&new.uri());
+                Box::pin(async move { Ok(()) })
             }),
             update_cls: Box::new(move |(old, new)| {
-                let tx_update = tx_update.clone();
-                Box::pin(async move {
-                    if old.spec != new.spec {
-                        tx_update.send(new.metadata.name).await?;
-                    }
-                    Ok(())
-                })
+                if old.spec != new.spec {
+                    queue_update.add(&new.uri());
+                }
+                Box::pin(async move { Ok(()) })
+            }),
+            delete_cls: Box::new(move |old| {
+                queue_delete.add(&old.uri());
+                Box::pin(async move { Ok(()) })
             }),
-            delete_cls: Box::new(move |_| Box::pin(async move { Ok(()) })),
         };
-        let rh = ResyncHandler(Box::new(move |()| {
-            let resync_tx = resync_tx.clone();
-            Box::pin(async move {
-                resync_tx.send(ResyncNotification).await?;
-                Ok(())
-            })
-        }));
 
-        Informer::new(lw, eh, rh)
+        Informer::new(lw, eh, ResyncHandler::forward(resync_tx))
     }
 
-    fn create_pod_informer() -> Informer<Pod> {
-        let lw = create_lister_watcher("pods".to_string());
+    fn create_pod_informer(pod_api: &Api<Pod>) -> Informer<Pod> {
+        let lw = pod_api.lister_watcher();
         let eh = EventHandler::<Pod> {
             add_cls: Box::new(move |_| Box::pin(async move { Ok(()) })),
             update_cls: Box::new(move |(_, __)| Box::pin(async move { Ok(()) })),
@@ -630,19 +739,8 @@ impl PodAutoscaler {
 
     async fn handle_resync(&mut self) {
         let store = self.hpa_store.read().await;
-        for hpa in store.values() {
-            let hpa_name = &hpa.metadata.name;
-            if !self.in_queue.contains(hpa_name) {
-                self.in_queue.insert(hpa_name.to_owned());
-                self.work_queue
-                    .insert_at(hpa_name.to_owned(), std::time::Instant::now());
-            }
-        }
-    }
-
-    fn enqueue_hpa(&self, hpa_name: &String, duration: std::time::Duration) {
-        if !self.in_queue.contains(hpa_name) {
-            self.work_queue.insert(hpa_name.to_owned(), duration);
+        for key in store.keys() {
+            self.work_queue.add(key);
         }
     }
 }
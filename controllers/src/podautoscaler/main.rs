@@ -34,7 +34,7 @@ pub static SYNC_PERIOD: u32 = 15;
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    let mut controller = horizontal::PodAutoscaler::new();
+    let mut controller = horizontal::PodAutoscaler::new()?;
     controller.run().await?;
     Ok(())
 }
@@ -1,23 +1,20 @@
-use anyhow::Result;
-use reqwest::Client;
+use anyhow::{Context, Result};
 use resources::{
-    models::Response,
+    client::Client,
     objects::{
         metrics::{FunctionMetric, PodMetric, PodMetrics, PodMetricsInfo, Resource},
         Labels,
     },
 };
 
-use crate::CONFIG;
-
 pub struct MetricsClient {
     client: Client,
 }
 
 impl MetricsClient {
-    pub fn new() -> Self {
+    pub fn new(client: Client) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client,
         }
     }
 
@@ -66,34 +63,19 @@ impl MetricsClient {
     }
 
     async fn get_pod_metrics(&self, selector: &Labels) -> Result<Vec<PodMetrics>> {
-        let response = self
-            .client
-            .get(format!("{}/api/v1/metrics/pods", CONFIG.api_server_url,))
-            .query::<Vec<(&str, String)>>(&vec![("selector", selector.to_string())])
-            .send()
-            .await?
-            .json::<Response<Vec<PodMetrics>>>()
-            .await?;
-        match response.data {
-            Some(data) => Ok(data),
-            None => Err(anyhow::anyhow!("Failed to get pod metrics")),
-        }
+        self.client
+            .get::<Vec<PodMetrics>>(
+                "/api/v1/metrics/pods",
+                &[("selector", selector.to_string())],
+            )
+            .await
+            .context("Failed to get pod metrics")
     }
 
     pub async fn get_function_metric(&self, func_name: &str) -> Result<FunctionMetric> {
-        let response = self
-            .client
-            .get(format!(
-                "{}/api/v1/metrics/functions/{}",
-                CONFIG.api_server_url, func_name
-            ))
-            .send()
-            .await?
-            .json::<Response<FunctionMetric>>()
-            .await?;
-        match response.data {
-            Some(data) => Ok(data),
-            None => Err(anyhow::anyhow!("Failed to get function metrics")),
-        }
+        self.client
+            .get::<FunctionMetric>(&format!("/api/v1/metrics/functions/{}", func_name), &[])
+            .await
+            .context("Failed to get function metrics")
     }
 }
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use anyhow::{Context, Result};
 use chrono::Local;
 use resources::{
+    client::Client,
     informer::Store,
     objects::{
         metrics::{PodMetric, PodMetricsInfo, Resource},
@@ -19,9 +20,9 @@ pub struct ReplicaCalculator {
 }
 
 impl ReplicaCalculator {
-    pub fn new(pod_store: Store<Pod>) -> Self {
+    pub fn new(pod_store: Store<Pod>, client: Client) -> Self {
         Self {
-            client: MetricsClient::new(),
+            client: MetricsClient::new(client),
             pod_store,
         }
     }
@@ -162,11 +163,7 @@ impl ReplicaCalculator {
                 continue;
             }
             if status.phase == PodPhase::Failed
-                || !status
-                    .conditions
-                    .get(&PodConditionType::Ready)
-                    .map(|c| c.status)
-                    .unwrap_or(false)
+                || !status.conditions.is_true(&PodConditionType::Ready)
             {
                 tracing::info!("Ignored Pod {} since it's not ready", pod.metadata.name);
                 metrics.remove(&pod.metadata.name);
@@ -1,17 +1,22 @@
 use std::{
     cmp::Ordering,
     collections::{HashMap, HashSet},
+    sync::Arc,
 };
 
 use anyhow::{Context, Error, Result};
 use resources::{
-    informer::Store,
-    models::Response,
+    client::{Api, Client},
+    informer::{
+        label_index, Event, EventHandler, Informer, ResyncHandler, ResyncNotification, Store,
+        LABEL_INDEX,
+    },
     objects::{
+        condition::Condition,
         object_reference::ObjectReference,
         pod::{Pod, PodPhase},
-        replica_set::{ReplicaSet, ReplicaSetStatus},
-        KubeObject, Object,
+        replica_set::{ReplicaSet, ReplicaSetConditionType, ReplicaSetStatus},
+        Object,
     },
 };
 use tokio::{
@@ -20,12 +25,12 @@ use tokio::{
     task::JoinHandle,
 };
 
-use crate::{
-    utils::{create_informer, Event, ResyncNotification},
-    CONFIG,
-};
+use crate::CONFIG;
 
 pub struct ReplicaSetController {
+    rs_api: Api<ReplicaSet>,
+    pod_api: Api<Pod>,
+
     rs_rx: Receiver<Event<ReplicaSet>>,
     rs_resync_rx: Receiver<ResyncNotification>,
     rs_informer: Option<JoinHandle<Result<(), Error>>>,
@@ -38,22 +43,37 @@ pub struct ReplicaSetController {
 }
 
 impl ReplicaSetController {
-    pub fn new() -> Self {
+    pub fn new() -> Result<Self> {
+        let client = Client::new(CONFIG.to_owned())?;
+        let rs_api = Api::<ReplicaSet>::new(client.to_owned(), "replicasets");
+        let pod_api = Api::<Pod>::new(client, "pods");
+
         let (rs_tx, rs_rx) = mpsc::channel::<Event<ReplicaSet>>(16);
         let (rs_resync_tx, rs_resync_rx) = mpsc::channel::<ResyncNotification>(16);
-        let rs_informer =
-            create_informer::<ReplicaSet>("replicasets".to_string(), rs_tx, rs_resync_tx);
+        let rs_informer = Informer::new(
+            rs_api.lister_watcher(),
+            EventHandler::forward(rs_tx),
+            ResyncHandler::forward(rs_resync_tx),
+        );
         let rs_store = rs_informer.get_store();
 
         let (pod_tx, pod_rx) = mpsc::channel::<Event<Pod>>(16);
         let (pod_resync_tx, pod_resync_rx) = mpsc::channel::<ResyncNotification>(16);
-        let pod_informer = create_informer::<Pod>("pods".to_string(), pod_tx, pod_resync_tx);
+        let pod_informer = Informer::new(
+            pod_api.lister_watcher(),
+            EventHandler::forward(pod_tx),
+            ResyncHandler::forward(pod_resync_tx),
+        )
+        .with_indexer(LABEL_INDEX, Arc::new(label_index::<Pod>));
         let pod_store = pod_informer.get_store();
 
         let rs_informer = tokio::spawn(async move { rs_informer.run().await });
         let pod_informer = tokio::spawn(async move { pod_informer.run().await });
 
-        Self {
+        Ok(Self {
+            rs_api,
+            pod_api,
+
             rs_rx,
             rs_resync_rx,
             rs_informer: Some(rs_informer),
@@ -63,7 +83,7 @@ impl ReplicaSetController {
             pod_resync_rx,
             pod_informer: Some(pod_informer),
             pod_store,
-        }
+        })
     }
 
     pub async fn run(&mut self) -> Result<()> {
@@ -153,46 +173,76 @@ impl ReplicaSetController {
         );
         let current = status.replicas;
         let desired = rs.spec.replicas;
-        match current.cmp(&desired) {
+        let (reason, result) = match current.cmp(&desired) {
             Ordering::Less => {
                 // Create a new pod, if more pods are needed, they'll be created later
-                self.create_pod(&rs).await?;
+                ("FailedCreate", self.create_pod(&rs).await)
             },
             Ordering::Greater => {
                 // Delete existing pods
                 let pod_name = self.get_pod_to_delete(&rs).await;
-                self.delete_pod(pod_name).await?;
+                ("FailedDelete", self.delete_pod(pod_name).await)
             },
             Ordering::Equal => {
                 // Nothing to do
+                ("", Ok(()))
             },
+        };
+        let failure = result.as_ref().err().map(|e| {
+            Condition::new(ReplicaSetConditionType::ReplicaFailure, true)
+                .with_reason(reason, format!("{:#}", e))
+        });
+        if let Err(e) = self.update_replica_failure(&rs, failure).await {
+            tracing::warn!(
+                "Failed to update conditions of ReplicaSet {}: {:#}",
+                rs.metadata.name,
+                e
+            );
+        }
+        result
+    }
+
+    /// Set or clear the ReplicaFailure condition on the latest ReplicaSet in store
+    async fn update_replica_failure(
+        &self,
+        rs: &ReplicaSet,
+        failure: Option<Condition<ReplicaSetConditionType>>,
+    ) -> Result<()> {
+        let latest = self.rs_store.read().await.get(&rs.uri()).cloned();
+        // Deleted already
+        let mut rs = match latest {
+            Some(rs) => rs,
+            None => return Ok(()),
+        };
+        let conditions = &mut rs
+            .status
+            .as_mut()
+            .with_context(|| "ReplicaSet has no status")?
+            .conditions;
+        let changed = match failure {
+            Some(condition) => conditions.set(condition),
+            None => conditions
+                .remove(&ReplicaSetConditionType::ReplicaFailure)
+                .is_some(),
+        };
+        if changed {
+            self.post_status(rs).await?;
         }
         Ok(())
     }
 
     async fn post_status(&self, rs: ReplicaSet) -> Result<()> {
-        let client = reqwest::Client::new();
-        let name = rs.metadata.name.to_owned();
-        let response = client
-            .put(format!("{}{}", CONFIG.api_server_url, rs.uri()))
-            .json(&KubeObject::ReplicaSet(rs))
-            .send()
-            .await?
-            .json::<Response<()>>()
-            .await
-            .with_context(|| "Error posting status")?;
-        tracing::info!(
-            "Posted status for ReplicaSet {}: {}",
-            name,
-            response.msg.unwrap_or_else(|| "".to_string())
-        );
+        self.rs_api.replace(&rs).await?;
+        tracing::info!("Posted status for ReplicaSet {}", rs.metadata.name);
         Ok(())
     }
 
     async fn create_pod(&self, rs: &ReplicaSet) -> Result<()> {
-        let client = reqwest::Client::new();
         let template = &rs.spec.template;
         let mut metadata = template.metadata.clone();
+        // Let the server generate a unique name for each replica
+        metadata.name = String::new();
+        metadata.generate_name = Some(format!("{}-", rs.metadata.name));
         metadata.owner_references.push(ObjectReference {
             kind: "ReplicaSet".to_string(),
             name: rs.metadata.name.to_owned(),
@@ -202,32 +252,14 @@ impl ReplicaSetController {
             spec: template.spec.clone(),
             status: None,
         };
-        let response = client
-            .post(format!("{}/api/v1/pods", CONFIG.api_server_url))
-            .json(&KubeObject::Pod(pod))
-            .send()
-            .await?
-            .json::<Response<()>>()
-            .await
-            .with_context(|| "Error creating pod")?;
-        if let Some(msg) = response.msg {
-            tracing::info!("{}", msg);
-        }
+        let pod = self.pod_api.create(&pod).await?;
+        tracing::info!("pod/{} created", pod.metadata.name);
         Ok(())
     }
 
     async fn delete_pod(&self, name: String) -> Result<()> {
-        let client = reqwest::Client::new();
-        let response = client
-            .delete(format!("{}/api/v1/pods/{}", CONFIG.api_server_url, name))
-            .send()
-            .await?
-            .json::<Response<()>>()
-            .await
-            .with_context(|| "Error deleting pod")?;
-        if let Some(msg) = response.msg {
-            tracing::info!("{}", msg);
-        }
+        self.pod_api.delete(&name).await?;
+        tracing::info!("pod/{} deleted", name);
         Ok(())
     }
 
@@ -250,9 +282,9 @@ impl ReplicaSetController {
     async fn get_pods(&self, rs: &ReplicaSet) -> Vec<Pod> {
         let store = self.pod_store.read().await;
         store
-            .iter()
-            .filter(|(_, pod)| pod.metadata.labels.matches(&rs.spec.selector))
-            .map(|(_, pod)| pod.to_owned())
+            .select(&rs.spec.selector)
+            .into_iter()
+            .cloned()
             .collect::<Vec<_>>()
     }
 
@@ -263,6 +295,12 @@ impl ReplicaSetController {
         ReplicaSetStatus {
             replicas,
             ready_replicas,
+            observed_generation: rs.metadata.generation,
+            conditions: rs
+                .status
+                .as_ref()
+                .map(|status| status.conditions.to_owned())
+                .unwrap_or_default(),
         }
     }
 
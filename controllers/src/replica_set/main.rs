@@ -7,7 +7,6 @@ use controller::ReplicaSetController;
 use resources::config::ClusterConfig;
 
 mod controller;
-mod utils;
 
 lazy_static! {
     pub static ref CONFIG: ClusterConfig = Config::builder()
@@ -30,7 +29,7 @@ lazy_static! {
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
-    let mut controller = ReplicaSetController::new();
+    let mut controller = ReplicaSetController::new()?;
     controller.run().await?;
     Ok(())
 }
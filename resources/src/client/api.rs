@@ -0,0 +1,154 @@
+use std::marker::PhantomData;
+
+use reqwest::header;
+use tokio_tungstenite::{
+    connect_async_tls_with_config, tungstenite::client::IntoClientRequest, Connector,
+};
+
+use super::{ApiError, Client};
+use crate::{
+    informer::{ListerWatcher, WsStream},
+    objects::{binding::Binding, pod::Pod, KubeObject, Object},
+};
+
+/// Typed client of a kind of objects, e.g. `Api::<Pod>::new(client, "pods")`.
+#[derive(Clone)]
+pub struct Api<T> {
+    client: Client,
+    /// Lowercase plural kind used in URL, e.g. "pods"
+    kind_plural: String,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Api<T>
+where
+    T: Object + Into<KubeObject>,
+{
+    pub fn new(client: Client, kind_plural: &str) -> Self {
+        Self {
+            client,
+            kind_plural: kind_plural.to_lowercase(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Lowercase plural kind used in URL, e.g. "pods"
+    pub fn kind_plural(&self) -> &str {
+        &self.kind_plural
+    }
+
+    pub async fn list(&self) -> Result<Vec<T>, ApiError> {
+        let data = self
+            .client
+            .send::<Vec<T>, _>(|| self.client.http.get(self.url(None)), true)
+            .await?;
+        Ok(data.unwrap_or_default())
+    }
+
+    pub async fn get(&self, name: &str) -> Result<T, ApiError> {
+        self.client
+            .send::<T, _>(|| self.client.http.get(self.url(Some(name))), true)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("{}/{}", self.kind_plural, name)))
+    }
+
+    /// Create the object, return it with fields populated by API server,
+    /// e.g. the name generated from `generateName`.
+    pub async fn create(&self, object: &T) -> Result<T, ApiError> {
+        let body: KubeObject = object.to_owned().into();
+        self.client
+            .send::<T, _>(|| self.client.http.post(self.url(None)).json(&body), false)
+            .await?
+            .ok_or_else(|| {
+                ApiError::Server(
+                    reqwest::StatusCode::OK,
+                    format!("Created object {} is not returned", object.name()),
+                )
+            })
+    }
+
+    /// Replace the whole object, mostly used to post status.
+    pub async fn replace(&self, object: &T) -> Result<Option<T>, ApiError> {
+        let body: KubeObject = object.to_owned().into();
+        let url = self.url(Some(object.name().as_str()));
+        self.client
+            .send::<T, _>(|| self.client.http.put(url.as_str()).json(&body), true)
+            .await
+    }
+
+    pub async fn patch(&self, object: &T) -> Result<Option<T>, ApiError> {
+        let body: KubeObject = object.to_owned().into();
+        let url = self.url(Some(object.name().as_str()));
+        self.client
+            .send::<T, _>(|| self.client.http.patch(url.as_str()).json(&body), false)
+            .await
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<(), ApiError> {
+        self.client
+            .send::<T, _>(|| self.client.http.delete(self.url(Some(name))), false)
+            .await?;
+        Ok(())
+    }
+
+    /// Open a websocket receiving watch events of all objects of this kind.
+    pub async fn watch(&self) -> Result<WsStream, ApiError> {
+        let url = format!(
+            "{}/api/v1/watch/{}",
+            self.client.config.api_server_watch_url, self.kind_plural
+        );
+        let mut request = url.into_client_request()?;
+        if let Some(value) = Client::auth_header(&self.client.config).ok().flatten() {
+            request.headers_mut().insert(header::AUTHORIZATION, value);
+        }
+        let connector = Connector::NativeTls(self.client.tls.to_owned());
+        let (stream, _) = connect_async_tls_with_config(request, None, Some(connector)).await?;
+        Ok(stream)
+    }
+
+    /// Build a [`ListerWatcher`] for informers on top of this client.
+    pub fn lister_watcher(&self) -> ListerWatcher<T> {
+        let lister_api = self.to_owned();
+        let watcher_api = self.to_owned();
+        ListerWatcher {
+            lister: Box::new(move |_| {
+                let api = lister_api.to_owned();
+                Box::pin(async move { Ok(api.list().await?) })
+            }),
+            watcher: Box::new(move |_| {
+                let api = watcher_api.to_owned();
+                Box::pin(async move { Ok(api.watch().await?) })
+            }),
+        }
+    }
+
+    fn url(&self, name: Option<&str>) -> String {
+        match name {
+            Some(name) => format!(
+                "{}/api/v1/{}/{}",
+                self.client.config.api_server_url, self.kind_plural, name
+            ),
+            None => format!(
+                "{}/api/v1/{}",
+                self.client.config.api_server_url, self.kind_plural
+            ),
+        }
+    }
+}
+
+impl Api<Pod> {
+    /// Bind a pod to the node targeted by `binding`, return the bound pod.
+    pub async fn bind(&self, binding: &Binding) -> Result<Pod, ApiError> {
+        let body = KubeObject::Binding(binding.to_owned());
+        let url = format!("{}/api/v1/bindings", self.client.config.api_server_url);
+        self.client
+            .send::<Pod, _>(|| self.client.http.post(url.as_str()).json(&body), false)
+            .await?
+            .ok_or_else(|| {
+                ApiError::Server(
+                    reqwest::StatusCode::OK,
+                    format!("Bound pod {} is not returned", binding.name()),
+                )
+            })
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+use tokio_tungstenite::tungstenite;
+
+#[derive(Debug)]
+pub enum ApiError {
+    /// The requested object doesn't exist
+    NotFound(String),
+    /// The object already exists
+    Conflict(String),
+    /// The request is rejected as invalid
+    Invalid(String),
+    /// Other errors reported by API server
+    Server(StatusCode, String),
+    /// Failed to send the request or decode the response
+    Transport(reqwest::Error),
+    /// Failed to establish the watch connection
+    Watch(tungstenite::Error),
+}
+
+impl ApiError {
+    pub(super) fn from_status(status: StatusCode, msg: String) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => ApiError::NotFound(msg),
+            StatusCode::CONFLICT => ApiError::Conflict(msg),
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => ApiError::Invalid(msg),
+            _ => ApiError::Server(status, msg),
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, ApiError::NotFound(_))
+    }
+
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, ApiError::Conflict(_))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            ApiError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            ApiError::Invalid(msg) => write!(f, "Invalid: {}", msg),
+            ApiError::Server(status, msg) => write!(f, "{}: {}", status, msg),
+            ApiError::Transport(e) => write!(f, "Request failed: {}", e),
+            ApiError::Watch(e) => write!(f, "Watch failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApiError::Transport(e) => Some(e),
+            ApiError::Watch(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Transport(e)
+    }
+}
+
+impl From<tungstenite::Error> for ApiError {
+    fn from(e: tungstenite::Error) -> Self {
+        ApiError::Watch(e)
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::{
+    header::{self, HeaderMap, HeaderValue},
+    Certificate, RequestBuilder,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub use self::{api::Api, error::ApiError};
+use crate::{
+    config::ClusterConfig,
+    models::{ErrResponse, Response},
+};
+
+mod api;
+mod error;
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Client of API server, shared by all typed [`Api`]s.
+///
+/// It's cheap to clone since the underlying connection pool is reference counted.
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    /// TLS settings of watch connections, the same as those of `http`
+    tls: native_tls::TlsConnector,
+    config: ClusterConfig,
+}
+
+impl Client {
+    pub fn new(config: ClusterConfig) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .danger_accept_invalid_certs(config.insecure_skip_tls_verify);
+        if let Some(header) = Client::auth_header(&config)? {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::AUTHORIZATION, header);
+            builder = builder.default_headers(headers);
+        }
+        let mut tls = native_tls::TlsConnector::builder();
+        tls.danger_accept_invalid_certs(config.insecure_skip_tls_verify);
+        if let Some(ref ca_file) = config.ca_file {
+            let pem = std::fs::read(ca_file)
+                .with_context(|| format!("Failed to read CA certificate {}", ca_file))?;
+            builder = builder.add_root_certificate(Certificate::from_pem(&pem)?);
+            tls.add_root_certificate(native_tls::Certificate::from_pem(&pem)?);
+        }
+        Ok(Self {
+            http: builder.build()?,
+            tls: tls.build()?,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &ClusterConfig {
+        &self.config
+    }
+
+    /// Get data which is not an object, e.g. metrics,
+    /// `path` is relative to API server URL, e.g. "/api/v1/metrics/pods".
+    pub async fn get<R>(&self, path: &str, query: &[(&str, String)]) -> Result<R, ApiError>
+    where
+        R: DeserializeOwned + Serialize,
+    {
+        let url = format!("{}{}", self.config.api_server_url, path);
+        self.send::<R, _>(|| self.http.get(url.as_str()).query(query), true)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(path.to_string()))
+    }
+
+    /// Send the request built by `build`, retrying when API server is unreachable.
+    /// Requests which are not `idempotent` won't be retried on timeout,
+    /// since they may have been processed.
+    async fn send<R, F>(&self, build: F, idempotent: bool) -> Result<Option<R>, ApiError>
+    where
+        R: DeserializeOwned + Serialize,
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempts = 0;
+        let response = loop {
+            match build().send().await {
+                Ok(response) => break response,
+                Err(e)
+                    if attempts < self.config.retries
+                        && (e.is_connect() || (idempotent && e.is_timeout())) =>
+                {
+                    attempts += 1;
+                    tracing::debug!("Request failed, retrying ({}): {}", attempts, e);
+                    tokio::time::sleep(RETRY_INTERVAL * attempts).await;
+                },
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(response.json::<Response<R>>().await?.data)
+        } else {
+            let msg = match response.json::<ErrResponse>().await {
+                Ok(ErrResponse {
+                    msg,
+                    cause: Some(cause),
+                    ..
+                }) => format!("{}: {}", msg, cause),
+                Ok(err) => err.msg,
+                Err(_) => status.to_string(),
+            };
+            Err(ApiError::from_status(status, msg))
+        }
+    }
+
+    fn auth_header(config: &ClusterConfig) -> Result<Option<HeaderValue>> {
+        match config.token {
+            Some(ref token) => {
+                let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+                    .with_context(|| "Invalid token")?;
+                value.set_sensitive(true);
+                Ok(Some(value))
+            },
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+/// Strategies of the descheduler to find pods worth moving.
+///
+/// A strategy with arguments is disabled by `null`, one without by `false`,
+/// one that's not specified keeps its default.
+///
+/// # Examples
+/// ```
+/// use resources::config::descheduler::DeschedulerPolicy;
+///
+/// let policy: DeschedulerPolicy = serde_yaml::from_str(
+///     "removeDuplicates: false\nremovePodsHavingTooManyRestarts:\n  podRestartThreshold: 10\n",
+/// )
+/// .unwrap();
+/// assert!(!policy.remove_duplicates);
+/// assert_eq!(
+///     policy
+///         .remove_pods_having_too_many_restarts
+///         .unwrap()
+///         .pod_restart_threshold,
+///     10
+/// );
+/// assert!(policy.low_node_utilization.is_some());
+///
+/// let policy: DeschedulerPolicy = serde_yaml::from_str("lowNodeUtilization: null\n").unwrap();
+/// assert!(policy.low_node_utilization.is_none());
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DeschedulerPolicy {
+    /// Seconds between two rounds of descheduling
+    pub interval_seconds: u64,
+    /// Maximum number of pods evicted from a node in a round
+    pub max_pods_to_evict_per_node: Option<u32>,
+    /// Move pods from overutilized nodes to underutilized ones
+    pub low_node_utilization: Option<LowNodeUtilizationArgs>,
+    /// Spread pods of the same owner on one node to other nodes
+    pub remove_duplicates: bool,
+    /// Evict pods not tolerating NoSchedule taints added to their node
+    pub remove_pods_violating_node_taints: bool,
+    /// Evict pods whose node no longer matches their node selector
+    /// or required node affinity
+    pub remove_pods_violating_node_affinity: bool,
+    /// Evict pods restarting too often, they may run better on another node
+    pub remove_pods_having_too_many_restarts: Option<TooManyRestartsArgs>,
+}
+
+impl Default for DeschedulerPolicy {
+    fn default() -> Self {
+        DeschedulerPolicy {
+            interval_seconds: 60,
+            max_pods_to_evict_per_node: None,
+            low_node_utilization: Some(LowNodeUtilizationArgs::default()),
+            remove_duplicates: true,
+            remove_pods_violating_node_taints: true,
+            remove_pods_violating_node_affinity: true,
+            remove_pods_having_too_many_restarts: Some(TooManyRestartsArgs::default()),
+        }
+    }
+}
+
+/// Nodes with all resources requested below `thresholds` are underutilized,
+/// nodes with any resource requested above `target_thresholds` are overutilized.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LowNodeUtilizationArgs {
+    pub thresholds: ResourceThresholds,
+    pub target_thresholds: ResourceThresholds,
+}
+
+impl Default for LowNodeUtilizationArgs {
+    fn default() -> Self {
+        LowNodeUtilizationArgs {
+            thresholds: ResourceThresholds {
+                cpu: 20,
+                memory: 20,
+            },
+            target_thresholds: ResourceThresholds {
+                cpu: 50,
+                memory: 50,
+            },
+        }
+    }
+}
+
+/// Percentages of allocatable resources of a node requested by its pods
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResourceThresholds {
+    pub cpu: i64,
+    pub memory: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TooManyRestartsArgs {
+    /// Pods whose containers restarted this many times in total are evicted
+    pub pod_restart_threshold: u32,
+}
+
+impl Default for TooManyRestartsArgs {
+    fn default() -> Self {
+        TooManyRestartsArgs {
+            pod_restart_threshold: 100,
+        }
+    }
+}
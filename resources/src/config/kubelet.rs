@@ -21,6 +21,17 @@ pub struct KubeletConfig {
     /// Frequency that kubelet computes pod status.
     /// In seconds. Default: 10 sec
     pub pod_status_update_frequency: u64,
+    /// Available resources below which the node reports pressure conditions,
+    /// so that no new pods are scheduled onto it.
+    pub eviction_soft: EvictionThresholds,
+    /// Available resources below which pods are evicted from the node.
+    pub eviction_hard: EvictionThresholds,
+    /// Frequency that kubelet checks eviction thresholds.
+    /// In seconds. Default: 10 sec
+    pub eviction_monitoring_period: u64,
+    /// Path on the filesystem whose available space is checked for disk pressure.
+    /// Defaults to "/var/lib/docker".
+    pub eviction_fs_path: String,
     pub cluster: ClusterConfig,
     pub port: u16,
 }
@@ -32,8 +43,49 @@ impl Default for KubeletConfig {
             node_status_update_frequency: 10,
             node_status_report_frequency: 30,
             pod_status_update_frequency: 10,
+            eviction_soft: EvictionThresholds {
+                memory_available_percent: 10,
+                disk_available_percent: 15,
+                pid_available_percent: 10,
+            },
+            eviction_hard: EvictionThresholds::default(),
+            eviction_monitoring_period: 10,
+            eviction_fs_path: "/var/lib/docker".to_string(),
             cluster: ClusterConfig::default(),
             port: 10250,
         }
     }
 }
+
+/// Minimum percentages of node resources that should stay available.
+///
+/// # Examples
+/// ```
+/// use resources::config::kubelet::KubeletConfig;
+///
+/// let config: KubeletConfig =
+///     serde_yaml::from_str("evictionHard:\n  memoryAvailablePercent: 3\n").unwrap();
+/// assert_eq!(config.eviction_hard.memory_available_percent, 3);
+/// assert_eq!(config.eviction_hard.disk_available_percent, 10);
+/// assert_eq!(config.eviction_soft.memory_available_percent, 10);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EvictionThresholds {
+    /// Percentage of memory available
+    pub memory_available_percent: u64,
+    /// Percentage of space available on the filesystem of `eviction_fs_path`
+    pub disk_available_percent: u64,
+    /// Percentage of process IDs available
+    pub pid_available_percent: u64,
+}
+
+impl Default for EvictionThresholds {
+    fn default() -> Self {
+        EvictionThresholds {
+            memory_available_percent: 5,
+            disk_available_percent: 10,
+            pid_available_percent: 5,
+        }
+    }
+}
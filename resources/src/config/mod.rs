@@ -1,6 +1,12 @@
+pub mod descheduler;
 pub mod kubelet;
+pub mod node_lifecycle;
+pub mod scheduler;
 
-use serde::{Deserialize, Serialize};
+use std::{fs, io::ErrorKind, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase", default)]
@@ -9,6 +15,16 @@ pub struct ClusterConfig {
     pub api_server_url: String,
     /// API server watch URL
     pub api_server_watch_url: String,
+    /// Bearer token to authenticate with API server
+    pub token: Option<String>,
+    /// Path to the PEM encoded CA certificate of API server
+    pub ca_file: Option<String>,
+    /// Skip verification of API server certificate, insecure
+    pub insecure_skip_tls_verify: bool,
+    /// Timeout of each request in seconds
+    pub timeout: u64,
+    /// Times to retry a request when API server is unreachable
+    pub retries: u32,
 }
 
 impl Default for ClusterConfig {
@@ -16,6 +32,22 @@ impl Default for ClusterConfig {
         ClusterConfig {
             api_server_url: "http://localhost:8080".to_string(),
             api_server_watch_url: "ws://localhost:8080".to_string(),
+            token: None,
+            ca_file: None,
+            insecure_skip_tls_verify: false,
+            timeout: 30,
+            retries: 3,
         }
     }
 }
+
+/// Load a YAML config file, use the default config if the file doesn't exist
+pub fn load_or_default<T: DeserializeOwned + Default>(path: impl AsRef<Path>) -> Result<T> {
+    let path = path.as_ref();
+    match fs::read_to_string(path) {
+        Ok(content) => serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse config {}", path.display())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read config {}", path.display())),
+    }
+}
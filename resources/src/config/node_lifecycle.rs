@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+/// Timing of the node lifecycle controller, all in seconds.
+///
+/// # Examples
+/// ```
+/// use resources::config::node_lifecycle::NodeLifecycleConfig;
+///
+/// let config: NodeLifecycleConfig =
+///     serde_yaml::from_str("nodeMonitorGracePeriodSeconds: 60\n").unwrap();
+/// assert_eq!(config.node_monitor_grace_period_seconds, 60);
+/// assert_eq!(config.pod_eviction_timeout_seconds, 120);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeLifecycleConfig {
+    /// Period of checking heartbeats of nodes
+    pub node_monitor_period_seconds: u64,
+    /// A node not posting status for this long is marked NotReady,
+    /// and tainted so that no new pods are scheduled onto it.
+    /// Should cover the status report frequency of rKubelet.
+    pub node_monitor_grace_period_seconds: u64,
+    /// A node not posting status for this long is marked Unknown,
+    /// and tainted so that its pods are evicted.
+    pub pod_eviction_timeout_seconds: u64,
+}
+
+impl Default for NodeLifecycleConfig {
+    fn default() -> Self {
+        NodeLifecycleConfig {
+            node_monitor_period_seconds: 5,
+            node_monitor_grace_period_seconds: 40,
+            pod_eviction_timeout_seconds: 120,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::objects::pod::DEFAULT_SCHEDULER_NAME;
+
+/// Plugins enabled at each extension point of the scheduling framework.
+///
+/// Plugins run in the order they are listed.
+/// An extension point that's not specified keeps its default plugins.
+///
+/// # Examples
+/// ```
+/// use resources::config::scheduler::SchedulerProfile;
+///
+/// let profile: SchedulerProfile = serde_yaml::from_str(
+///     "score:\n  - name: NodeResourcesLeastAllocated\n    weight: 2\n",
+/// )
+/// .unwrap();
+/// assert_eq!(profile.score.len(), 1);
+/// assert_eq!(profile.score[0].weight, 2);
+/// assert_eq!(profile.filter, SchedulerProfile::default().filter);
+/// assert_eq!(profile.scheduler_name, "default-scheduler");
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SchedulerProfile {
+    /// The scheduler only schedules pods whose `schedulerName` is this
+    pub scheduler_name: String,
+    /// Plugins that check the pod before any node is filtered
+    pub pre_filter: Vec<String>,
+    /// Plugins that filter out nodes the pod can't run on
+    pub filter: Vec<String>,
+    /// Plugins that rank the remaining nodes
+    pub score: Vec<WeightedPlugin>,
+    /// Plugins that reserve resources on the chosen node before binding
+    pub reserve: Vec<String>,
+    /// Plugins that bind the pod to the chosen node,
+    /// the first one that doesn't skip handles the binding
+    pub bind: Vec<String>,
+    /// HTTP extenders called in order after the plugins at each extension point
+    pub extenders: Vec<Extender>,
+}
+
+/// A score plugin and the weight of its score.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightedPlugin {
+    pub name: String,
+    /// Defaults to 1.
+    #[serde(default = "default_weight")]
+    pub weight: i64,
+}
+
+fn default_weight() -> i64 {
+    1
+}
+
+/// An HTTP webhook that filters, prioritizes or binds
+/// in addition to the plugins of the profile.
+///
+/// A verb that's not set skips the extender at that extension point.
+///
+/// # Examples
+/// ```
+/// use resources::config::scheduler::SchedulerProfile;
+///
+/// let profile: SchedulerProfile = serde_yaml::from_str(
+///     "extenders:\n  - urlPrefix: http://127.0.0.1:8888/scheduler\n    filterVerb: filter\n",
+/// )
+/// .unwrap();
+/// let extender = &profile.extenders[0];
+/// assert_eq!(extender.filter_verb.as_deref(), Some("filter"));
+/// assert_eq!(extender.prioritize_verb, None);
+/// assert_eq!(extender.timeout_seconds, 5);
+/// assert!(!extender.ignorable);
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Extender {
+    /// Calls are made to `<urlPrefix>/<verb>`
+    pub url_prefix: String,
+    pub filter_verb: Option<String>,
+    pub prioritize_verb: Option<String>,
+    /// If set, the extender binds pods instead of the bind plugins
+    pub bind_verb: Option<String>,
+    /// Weight of the scores from prioritize, defaults to 1.
+    #[serde(default = "default_weight")]
+    pub weight: i64,
+    /// Timeout of each call, defaults to 5 seconds.
+    #[serde(default = "default_extender_timeout")]
+    pub timeout_seconds: u64,
+    /// Skip the extender if it fails or is unreachable,
+    /// instead of failing the scheduling attempt.
+    #[serde(default)]
+    pub ignorable: bool,
+}
+
+fn default_extender_timeout() -> u64 {
+    5
+}
+
+impl WeightedPlugin {
+    pub fn new(name: &str, weight: i64) -> Self {
+        WeightedPlugin {
+            name: name.to_owned(),
+            weight,
+        }
+    }
+}
+
+impl Default for SchedulerProfile {
+    fn default() -> Self {
+        SchedulerProfile {
+            scheduler_name: DEFAULT_SCHEDULER_NAME.to_string(),
+            pre_filter: vec![
+                "NodeResourcesFit".to_string(),
+                "InterPodAffinity".to_string(),
+                "PodTopologySpread".to_string(),
+            ],
+            filter: vec![
+                "NodeReady".to_string(),
+                "NodeUnschedulable".to_string(),
+                "NodePressure".to_string(),
+                "TaintToleration".to_string(),
+                "NodeSelector".to_string(),
+                "NodeAffinity".to_string(),
+                "NodeResourcesFit".to_string(),
+                "InterPodAffinity".to_string(),
+                "PodTopologySpread".to_string(),
+            ],
+            score: vec![
+                WeightedPlugin::new("NodeAffinity", 2),
+                WeightedPlugin::new("InterPodAffinity", 2),
+                WeightedPlugin::new("TaintToleration", 3),
+                WeightedPlugin::new("PodTopologySpread", 2),
+                WeightedPlugin::new("NodeResourcesLeastAllocated", 1),
+                WeightedPlugin::new("NodeResourcesBalancedAllocation", 1),
+                WeightedPlugin::new("LeastPods", 1),
+            ],
+            reserve: vec![],
+            bind: vec!["DefaultBinder".to_string()],
+            extenders: vec![],
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+pub use async_trait::async_trait;
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    stream::{FuturesUnordered, StreamExt},
+};
+use tokio::{select, task::JoinHandle};
+
+use crate::{
+    client::Api,
+    informer::{EventHandler, Informer, ResyncHandler, Store, WorkQueue, WorkQueueConfig},
+    objects::{KubeObject, Object},
+};
+
+/// What to do with an object after it's reconciled successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Reconcile it again after the duration, even if nothing changes
+    Requeue(Duration),
+    /// Do nothing until the object or something it depends on changes
+    AwaitChange,
+}
+
+/// Business logic of a controller, driven by [`Controller`].
+#[async_trait]
+pub trait Reconciler: Send + Sync + 'static {
+    /// Bring the actual state of the primary object identified by `key` (its URI)
+    /// closer to the desired state.
+    ///
+    /// The object may have been deleted, in which case it's absent from the store.
+    /// Errors are logged and the key is retried with exponential backoff.
+    async fn reconcile(&self, key: &str) -> Result<Action>;
+}
+
+/// Map a watched object to names of the primary objects it affects.
+pub type Mapper<O> = Arc<dyn Fn(&O) -> Vec<String> + Send + Sync>;
+
+/// Drive a [`Reconciler`] with informers of the primary kind, owned kinds and watched kinds.
+///
+/// Every change of a primary object, of an object owned by a primary object,
+/// or of a watched object enqueues the key of the affected primary objects.
+/// Keys are deduplicated, so a burst of changes results in a single reconcile.
+///
+/// ```no_run
+/// # use anyhow::Result;
+/// # use resources::{client::{Api, Client}, objects::{pod::Pod, replica_set::ReplicaSet}};
+/// use resources::controller::{async_trait, Action, Controller, Reconciler};
+///
+/// struct ReplicaSetReconciler;
+///
+/// #[async_trait]
+/// impl Reconciler for ReplicaSetReconciler {
+///     async fn reconcile(&self, key: &str) -> Result<Action> {
+///         println!("reconciling {}", key);
+///         Ok(Action::AwaitChange)
+///     }
+/// }
+///
+/// # async fn example(client: Client) -> Result<()> {
+/// Controller::new(Api::<ReplicaSet>::new(client.to_owned(), "replicasets"))
+///     .owns(Api::<Pod>::new(client, "pods"))
+///     .run(ReplicaSetReconciler)
+///     .await
+/// # }
+/// ```
+pub struct Controller<K: Object + Into<KubeObject>> {
+    api: Api<K>,
+    store: Store<K>,
+    queue: WorkQueue,
+    workers: usize,
+    /// Informers to start, in the order they're declared
+    informers: Vec<BoxFuture<'static, Result<()>>>,
+    /// Stores of owned and watched kinds, keyed by their types
+    stores: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    shutdown_signal: BoxFuture<'static, ()>,
+}
+
+impl<K: Object + Into<KubeObject>> Controller<K> {
+    /// Create a controller whose primary kind is that of `api`.
+    pub fn new(api: Api<K>) -> Self {
+        Self::with_config(api, WorkQueueConfig::default())
+    }
+
+    /// Create a controller whose work queue is rate limited by `config`.
+    pub fn with_config(api: Api<K>, config: WorkQueueConfig) -> Self {
+        let queue = WorkQueue::new(config);
+
+        let queue_add = queue.to_owned();
+        let queue_update = queue.to_owned();
+        let queue_delete = queue.to_owned();
+        let eh = EventHandler::<K> {
+            add_cls: Box::new(move |new| {
+                queue_add.add(&new.uri());
+                Box::pin(async { Ok(()) })
+            }),
+            update_cls: Box::new(move |(_, new)| {
+                queue_update.add(&new.uri());
+                Box::pin(async { Ok(()) })
+            }),
+            delete_cls: Box::new(move |old| {
+                queue_delete.add(&old.uri());
+                Box::pin(async { Ok(()) })
+            }),
+        };
+        let informer = Informer::new(api.lister_watcher(), eh, ResyncHandler::noop());
+        let store = informer.get_store();
+        let informer =
+            informer.with_resync_handler(enqueue_all(store.to_owned(), queue.to_owned()));
+
+        Self {
+            api,
+            store,
+            queue,
+            workers: 1,
+            informers: vec![async move { informer.run().await }.boxed()],
+            stores: HashMap::new(),
+            shutdown_signal: async {
+                tokio::signal::ctrl_c().await.ok();
+            }
+            .boxed(),
+        }
+    }
+
+    /// Watch objects owned by primary objects,
+    /// their changes are mapped to owners through `owner_references`.
+    pub fn owns<O: Object + Into<KubeObject>>(self, api: Api<O>) -> Self {
+        let kind_plural = self.api.kind_plural().to_owned();
+        self.watches(api, move |object: &O| {
+            object
+                .metadata()
+                .owner_references
+                .iter()
+                .filter(|owner| kind_plural_of(&owner.kind) == kind_plural)
+                .map(|owner| owner.name.to_owned())
+                .collect()
+        })
+    }
+
+    /// Watch objects of another kind, `mapper` returns names of primary objects
+    /// which should be reconciled when a watched object changes.
+    pub fn watches<O, F>(mut self, api: Api<O>, mapper: F) -> Self
+    where
+        O: Object + Into<KubeObject>,
+        F: Fn(&O) -> Vec<String> + Send + Sync + 'static,
+    {
+        let mapper: Mapper<O> = Arc::new(mapper);
+        let prefix = format!("/api/v1/{}", self.api.kind_plural());
+        let enqueue = {
+            let queue = self.queue.to_owned();
+            move |object: &O| {
+                for name in mapper(object) {
+                    queue.add(&format!("{}/{}", prefix, name));
+                }
+            }
+        };
+
+        let enqueue_add = enqueue.to_owned();
+        let enqueue_update = enqueue.to_owned();
+        let enqueue_delete = enqueue;
+        let eh = EventHandler::<O> {
+            add_cls: Box::new(move |new| {
+                enqueue_add(&new);
+                Box::pin(async { Ok(()) })
+            }),
+            update_cls: Box::new(move |(old, new)| {
+                // Both the previous and the current owners are affected
+                enqueue_update(&old);
+                enqueue_update(&new);
+                Box::pin(async { Ok(()) })
+            }),
+            delete_cls: Box::new(move |old| {
+                enqueue_delete(&old);
+                Box::pin(async { Ok(()) })
+            }),
+        };
+        let resync = enqueue_all(self.store.to_owned(), self.queue.to_owned());
+        let informer = Informer::new(api.lister_watcher(), eh, resync);
+        self.stores
+            .insert(TypeId::of::<O>(), Box::new(informer.get_store()));
+        self.informers
+            .push(async move { informer.run().await }.boxed());
+        self
+    }
+
+    /// Number of keys reconciled concurrently, 1 by default.
+    /// The same key is never reconciled concurrently.
+    pub fn workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Stop when `signal` completes, instead of on Ctrl-C.
+    pub fn graceful_shutdown_on<F>(mut self, signal: F) -> Self
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown_signal = signal.boxed();
+        self
+    }
+
+    /// Store of the primary kind
+    pub fn store(&self) -> Store<K> {
+        self.store.to_owned()
+    }
+
+    /// Store of an owned or watched kind, `None` if it's not declared.
+    pub fn store_of<O: Object>(&self) -> Option<Store<O>> {
+        self.stores
+            .get(&TypeId::of::<O>())
+            .and_then(|store| store.downcast_ref::<Store<O>>())
+            .cloned()
+    }
+
+    /// Work queue of primary keys, e.g. to enqueue keys from outside informers
+    pub fn queue(&self) -> WorkQueue {
+        self.queue.to_owned()
+    }
+
+    /// Run until the shutdown signal, then wait for in-flight reconciles to finish.
+    ///
+    /// If any informer stops, the controller shuts down the same way
+    /// and returns the informer's error, since its store would go stale.
+    pub async fn run<R: Reconciler>(self, reconciler: R) -> Result<()> {
+        let reconciler = Arc::new(reconciler);
+        let mut informers = self
+            .informers
+            .into_iter()
+            .map(tokio::spawn)
+            .collect::<FuturesUnordered<JoinHandle<Result<()>>>>();
+
+        let queue = self.queue.to_owned();
+        let signal = self.shutdown_signal;
+        tokio::spawn(async move {
+            signal.await;
+            tracing::info!("Shutting down, waiting for in-flight reconciles...");
+            queue.shut_down();
+        });
+
+        tracing::info!("Controller of {} started", self.api.kind_plural());
+        let workers = (0..self.workers).map(|_| {
+            let reconciler = reconciler.to_owned();
+            let queue = self.queue.to_owned();
+            async move {
+                while let Some(key) = queue.get().await {
+                    match reconciler.reconcile(&key).await {
+                        Ok(Action::Requeue(after)) => {
+                            queue.forget(&key);
+                            queue.add_after(&key, after);
+                        },
+                        Ok(Action::AwaitChange) => queue.forget(&key),
+                        Err(e) => {
+                            tracing::error!(
+                                "Error reconciling {} (retried {} times): {:#}",
+                                key,
+                                queue.num_requeues(&key),
+                                e
+                            );
+                            queue.add_rate_limited(&key);
+                        },
+                    }
+                    queue.done(&key);
+                }
+            }
+        });
+        let workers = future::join_all(workers);
+        tokio::pin!(workers);
+
+        let result = select! {
+            _ = &mut workers => Ok(()),
+            Some(informer_result) = informers.next() => {
+                let e = match informer_result {
+                    Ok(Ok(())) => anyhow!("Informer exited unexpectedly"),
+                    Ok(Err(e)) => e.context("Informer failed"),
+                    Err(e) => anyhow!(e).context("Informer panicked"),
+                };
+                tracing::error!("{:#}, shutting down...", e);
+                self.queue.shut_down();
+                workers.await;
+                Err(e)
+            },
+        };
+
+        for informer in informers.iter() {
+            informer.abort();
+        }
+        tracing::info!("Controller of {} exited", self.api.kind_plural());
+        result
+    }
+}
+
+/// Enqueue all primary objects when an informer resyncs,
+/// since events may have been missed.
+fn enqueue_all<K: Object>(store: Store<K>, queue: WorkQueue) -> ResyncHandler {
+    ResyncHandler(Box::new(move |()| {
+        let store = store.to_owned();
+        let queue = queue.to_owned();
+        Box::pin(async move {
+            for key in store.read().await.keys() {
+                queue.add(key);
+            }
+            Ok(())
+        })
+    }))
+}
+
+/// Lowercase plural of a kind used in URL, e.g. "Ingress" -> "ingresses"
+fn kind_plural_of(kind: &str) -> String {
+    let kind = kind.to_lowercase();
+    if kind.ends_with('s') {
+        kind + "es"
+    } else {
+        kind + "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::Notify;
+
+    use super::*;
+    use crate::{
+        client::Client,
+        config::ClusterConfig,
+        objects::{pod::Pod, replica_set::ReplicaSet},
+    };
+
+    /// Notify once a key is reconciled
+    struct NotifyingReconciler(Arc<Notify>);
+
+    #[async_trait]
+    impl Reconciler for NotifyingReconciler {
+        async fn reconcile(&self, _key: &str) -> Result<Action> {
+            self.0.notify_one();
+            Ok(Action::AwaitChange)
+        }
+    }
+
+    fn client() -> Client {
+        Client::new(ClusterConfig::default()).unwrap()
+    }
+
+    fn api() -> Api<ReplicaSet> {
+        Api::new(client(), "replicasets")
+    }
+
+    /// A controller whose only informer is `informer` and which never receives a shutdown signal
+    fn controller(informer: BoxFuture<'static, Result<()>>) -> Controller<ReplicaSet> {
+        let mut controller = Controller::new(api()).graceful_shutdown_on(future::pending());
+        controller.informers = vec![informer];
+        controller
+    }
+
+    async fn panicking_informer() -> Result<()> {
+        panic!("informer panicked")
+    }
+
+    #[test]
+    fn plural_of_kinds() {
+        assert_eq!(kind_plural_of("ReplicaSet"), "replicasets");
+        assert_eq!(kind_plural_of("Pod"), "pods");
+        assert_eq!(kind_plural_of("Ingress"), "ingresses");
+    }
+
+    #[test]
+    fn stores_of_declared_kinds() {
+        let controller = Controller::new(api()).owns(Api::<Pod>::new(client(), "pods"));
+        assert!(controller.store_of::<Pod>().is_some());
+        assert!(controller.store_of::<ReplicaSet>().is_none());
+    }
+
+    #[tokio::test]
+    async fn shut_down_after_reconcile() {
+        let reconciled = Arc::new(Notify::new());
+        let signal = reconciled.to_owned();
+        let controller = controller(future::pending().boxed())
+            .graceful_shutdown_on(async move { signal.notified().await });
+        controller.queue().add("/api/v1/replicasets/rs");
+
+        let result = controller.run(NotifyingReconciler(reconciled)).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn return_informer_error() {
+        let controller = controller(async { Err(anyhow!("watch failed")) }.boxed());
+        let reconciler = NotifyingReconciler(Arc::new(Notify::new()));
+        let e = controller.run(reconciler).await.unwrap_err();
+        assert_eq!(format!("{:#}", e), "Informer failed: watch failed");
+    }
+
+    #[tokio::test]
+    async fn return_informer_exit() {
+        let controller = controller(async { Ok(()) }.boxed());
+        let reconciler = NotifyingReconciler(Arc::new(Notify::new()));
+        let e = controller.run(reconciler).await.unwrap_err();
+        assert_eq!(e.to_string(), "Informer exited unexpectedly");
+    }
+
+    #[tokio::test]
+    async fn return_informer_panic() {
+        let controller = controller(panicking_informer().boxed());
+        let reconciler = NotifyingReconciler(Arc::new(Notify::new()));
+        let e = controller.run(reconciler).await.unwrap_err();
+        assert_eq!(e.to_string(), "Informer panicked");
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Deref,
+    sync::Arc,
+};
+
+use crate::objects::{object_reference::ObjectReference, pod::Pod, Labels, Object};
+
+/// Index on `spec.nodeName` of pods, see [`node_name_index`]
+pub const NODE_NAME_INDEX: &str = "nodeName";
+/// Index on owner references, see [`owner_index`]
+pub const OWNER_INDEX: &str = "owner";
+/// Index on labels, see [`label_index`]
+pub const LABEL_INDEX: &str = "label";
+
+/// Compute the index values of an object, an object can have zero or more values.
+pub type IndexFunc<T> = Arc<dyn Fn(&T) -> Vec<String> + Send + Sync>;
+
+/// Index pods by the node they're bound to, unscheduled pods are not indexed.
+pub fn node_name_index(pod: &Pod) -> Vec<String> {
+    pod.spec.node_name.iter().cloned().collect()
+}
+
+/// Index objects by their owners, in the form of [`owner_key`].
+pub fn owner_index<T: Object>(object: &T) -> Vec<String> {
+    object
+        .metadata()
+        .owner_references
+        .iter()
+        .map(owner_key)
+        .collect()
+}
+
+/// Index objects by each of their labels, in the form of [`label_key`].
+pub fn label_index<T: Object>(object: &T) -> Vec<String> {
+    object
+        .metadata()
+        .labels
+        .0
+        .iter()
+        .map(|(key, value)| label_key(key, value))
+        .collect()
+}
+
+/// Index value of an owner in [`OWNER_INDEX`]
+///
+/// # Examples
+/// ```
+/// use resources::{informer::owner_key, objects::object_reference::ObjectReference};
+/// let owner = ObjectReference {
+///     kind: "ReplicaSet".to_string(),
+///     name: "nginx".to_string(),
+/// };
+/// assert_eq!(owner_key(&owner), "ReplicaSet/nginx");
+/// ```
+pub fn owner_key(owner: &ObjectReference) -> String {
+    format!("{}/{}", owner.kind, owner.name)
+}
+
+/// Index value of a label in [`LABEL_INDEX`]
+pub fn label_key(key: &str, value: &str) -> String {
+    format!("{}={}", key, value)
+}
+
+/// Objects keyed by URI, along with indices on them.
+///
+/// It dereferences to the underlying map for reading,
+/// all modifications must go through it to keep indices consistent.
+pub struct IndexedStore<T: Object> {
+    objects: HashMap<String, T>,
+    indexers: HashMap<String, IndexFunc<T>>,
+    /// index name -> index value -> object keys
+    indices: HashMap<String, HashMap<String, HashSet<String>>>,
+}
+
+impl<T: Object> Default for IndexedStore<T> {
+    fn default() -> Self {
+        Self {
+            objects: HashMap::new(),
+            indexers: HashMap::new(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Object> Deref for IndexedStore<T> {
+    type Target = HashMap<String, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.objects
+    }
+}
+
+impl<T: Object> IndexedStore<T> {
+    /// Register an index, existing objects are indexed immediately.
+    pub fn add_indexer(&mut self, name: &str, index_fn: IndexFunc<T>) {
+        let mut index = HashMap::<String, HashSet<String>>::new();
+        for (key, object) in self.objects.iter() {
+            for value in index_fn(object) {
+                index.entry(value).or_default().insert(key.to_owned());
+            }
+        }
+        self.indices.insert(name.to_owned(), index);
+        self.indexers.insert(name.to_owned(), index_fn);
+    }
+
+    pub fn insert(&mut self, key: String, object: T) -> Option<T> {
+        let old = self.objects.remove(&key);
+        if let Some(ref old) = old {
+            self.unindex(&key, old);
+        }
+        self.index(&key, &object);
+        self.objects.insert(key, object);
+        old
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<T> {
+        let old = self.objects.remove(key);
+        if let Some(ref old) = old {
+            self.unindex(key, old);
+        }
+        old
+    }
+
+    /// Replace all objects, e.g. after relisting, and rebuild indices.
+    pub fn replace(&mut self, objects: HashMap<String, T>) {
+        self.objects = objects;
+        let indexers = std::mem::take(&mut self.indexers);
+        for (name, index_fn) in indexers {
+            self.add_indexer(&name, index_fn);
+        }
+    }
+
+    /// Keys of objects whose `index` contains `value`.
+    /// Return an empty list if the index doesn't exist.
+    pub fn index_keys(&self, index: &str, value: &str) -> Vec<&String> {
+        self.indices
+            .get(index)
+            .and_then(|index| index.get(value))
+            .map_or_else(Vec::new, |keys| keys.iter().collect())
+    }
+
+    /// Objects whose `index` contains `value`.
+    /// Return an empty list if the index doesn't exist.
+    pub fn by_index(&self, index: &str, value: &str) -> Vec<&T> {
+        self.index_keys(index, value)
+            .into_iter()
+            .filter_map(|key| self.objects.get(key))
+            .collect()
+    }
+
+    /// All values of `index` which have at least one object.
+    pub fn index_values(&self, index: &str) -> Vec<&String> {
+        self.indices
+            .get(index)
+            .map_or_else(Vec::new, |index| index.keys().collect())
+    }
+
+    /// Objects whose labels match `selector`, using [`LABEL_INDEX`] if it exists.
+    pub fn select(&self, selector: &Labels) -> Vec<&T> {
+        let label_index = match self.indices.get(LABEL_INDEX) {
+            Some(index) if !selector.0.is_empty() => index,
+            // Nothing to narrow down with, fall back to a full scan
+            _ => {
+                return self
+                    .objects
+                    .values()
+                    .filter(|object| object.metadata().labels.matches(selector))
+                    .collect();
+            },
+        };
+        // Start from the most selective label
+        let candidates = selector
+            .0
+            .iter()
+            .map(|(key, value)| label_index.get(&label_key(key, value)))
+            .min_by_key(|keys| keys.map_or(0, |keys| keys.len()))
+            .flatten();
+        candidates.map_or_else(Vec::new, |keys| {
+            keys.iter()
+                .filter_map(|key| self.objects.get(key))
+                .filter(|object| object.metadata().labels.matches(selector))
+                .collect()
+        })
+    }
+
+    fn index(&mut self, key: &str, object: &T) {
+        for (name, index_fn) in self.indexers.iter() {
+            let index = self.indices.entry(name.to_owned()).or_default();
+            for value in index_fn(object) {
+                index.entry(value).or_default().insert(key.to_owned());
+            }
+        }
+    }
+
+    fn unindex(&mut self, key: &str, object: &T) {
+        for (name, index_fn) in self.indexers.iter() {
+            if let Some(index) = self.indices.get_mut(name) {
+                for value in index_fn(object) {
+                    if let Some(keys) = index.get_mut(&value) {
+                        keys.remove(key);
+                        if keys.is_empty() {
+                            index.remove(&value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
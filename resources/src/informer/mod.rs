@@ -1,22 +1,33 @@
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::Result;
 use futures_util::future::BoxFuture;
+pub use indexer::{
+    label_index, label_key, node_name_index, owner_index, owner_key, IndexFunc, IndexedStore,
+    LABEL_INDEX, NODE_NAME_INDEX, OWNER_INDEX,
+};
+pub use reflector::ResyncNotification;
 use reflector::{Reflector, ReflectorNotification};
 use tokio::{
     net::TcpStream,
     select,
-    sync::{mpsc, RwLock},
+    sync::{
+        mpsc::{self, Sender},
+        RwLock,
+    },
 };
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+pub use work_queue::{WorkQueue, WorkQueueConfig};
 
-use crate::{informer::reflector::ResyncNotification, objects::Object};
+use crate::objects::Object;
 
+mod indexer;
 mod reflector;
+mod work_queue;
 
 pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
-pub type Store<T> = Arc<RwLock<HashMap<String, T>>>;
+pub type Store<T> = Arc<RwLock<IndexedStore<T>>>;
 
 pub type CLS<ARG, RES> = Box<dyn Fn(ARG) -> BoxFuture<'static, Result<RES>> + Send + Sync>;
 
@@ -33,6 +44,73 @@ pub struct EventHandler<T: Object> {
 
 pub struct ResyncHandler(pub CLS<(), ()>);
 
+#[derive(Debug)]
+pub enum Event<T> {
+    Add(T),
+    /// old value, new value
+    Update(T, T),
+    Delete(T),
+}
+
+impl<T: Object> EventHandler<T> {
+    /// Create an event handler which ignores all events, for informers only used as caches
+    pub fn noop() -> Self {
+        Self {
+            add_cls: Box::new(move |_| Box::pin(async move { Ok(()) })),
+            update_cls: Box::new(move |_| Box::pin(async move { Ok(()) })),
+            delete_cls: Box::new(move |_| Box::pin(async move { Ok(()) })),
+        }
+    }
+
+    /// Create an event handler which forwards all events to `tx`
+    pub fn forward(tx: Sender<Event<T>>) -> Self {
+        let tx_add = tx;
+        let tx_update = tx_add.clone();
+        let tx_delete = tx_add.clone();
+        Self {
+            add_cls: Box::new(move |new| {
+                let tx_add = tx_add.clone();
+                Box::pin(async move {
+                    tx_add.send(Event::Add(new)).await?;
+                    Ok(())
+                })
+            }),
+            update_cls: Box::new(move |(old, new)| {
+                let tx_update = tx_update.clone();
+                Box::pin(async move {
+                    tx_update.send(Event::Update(old, new)).await?;
+                    Ok(())
+                })
+            }),
+            delete_cls: Box::new(move |old| {
+                let tx_delete = tx_delete.clone();
+                Box::pin(async move {
+                    tx_delete.send(Event::Delete(old)).await?;
+                    Ok(())
+                })
+            }),
+        }
+    }
+}
+
+impl ResyncHandler {
+    /// Create a resync handler which does nothing
+    pub fn noop() -> Self {
+        Self(Box::new(move |()| Box::pin(async move { Ok(()) })))
+    }
+
+    /// Create a resync handler which notifies `tx`
+    pub fn forward(tx: Sender<ResyncNotification>) -> Self {
+        Self(Box::new(move |()| {
+            let tx = tx.clone();
+            Box::pin(async move {
+                tx.send(ResyncNotification).await?;
+                Ok(())
+            })
+        }))
+    }
+}
+
 pub struct Informer<T: Object> {
     reflector: Arc<Reflector<T>>,
     eh: EventHandler<T>,
@@ -41,7 +119,7 @@ pub struct Informer<T: Object> {
 
 impl<T: Object> Informer<T> {
     pub fn new(lw: ListerWatcher<T>, eh: EventHandler<T>, rh: ResyncHandler) -> Self {
-        let store = Arc::new(RwLock::new(HashMap::new()));
+        let store = Arc::new(RwLock::new(IndexedStore::default()));
         let reflector = Reflector {
             lw,
             store,
@@ -53,7 +131,29 @@ impl<T: Object> Informer<T> {
         }
     }
 
-    pub fn get_store(&self) -> Arc<RwLock<HashMap<String, T>>> {
+    /// Register an index on the store, see [`IndexedStore::add_indexer`].
+    /// It should be called before the informer runs.
+    ///
+    /// ```ignore
+    /// let informer = Informer::new(lw, eh, rh)
+    ///     .with_indexer(NODE_NAME_INDEX, Arc::new(node_name_index));
+    /// ```
+    pub fn with_indexer(self, name: &str, index_fn: IndexFunc<T>) -> Self {
+        self.reflector
+            .store
+            .try_write()
+            .expect("Store shouldn't be locked before informer runs")
+            .add_indexer(name, index_fn);
+        self
+    }
+
+    /// Replace the resync handler, e.g. with one which needs the store.
+    pub fn with_resync_handler(mut self, rh: ResyncHandler) -> Self {
+        self.rh = rh;
+        self
+    }
+
+    pub fn get_store(&self) -> Store<T> {
         self.reflector.store.clone()
     }
 
@@ -23,7 +23,7 @@ pub(super) enum ReflectorNotification<T> {
 }
 
 #[derive(Debug)]
-pub(super) struct ResyncNotification;
+pub struct ResyncNotification;
 
 impl<T: Object> Reflector<T> {
     pub(super) async fn run(
@@ -51,9 +51,9 @@ impl<T: Object> Reflector<T> {
                     new_store.insert(object.uri(), object);
                 }
                 let mut old_store = self.store.write().await;
-                if !old_store.eq(&new_store) {
+                if **old_store != new_store {
                     should_resync = true;
-                    old_store.clone_from(&new_store);
+                    old_store.replace(new_store);
                 }
             }
             if should_resync {
@@ -0,0 +1,389 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::Notify;
+
+/// Parameters of the rate limiting applied by [`WorkQueue::add_rate_limited`].
+#[derive(Debug, Clone)]
+pub struct WorkQueueConfig {
+    /// Delay after the first failure of a key, doubled on every further failure
+    pub base_delay: Duration,
+    /// Upper bound of the per-key backoff
+    pub max_delay: Duration,
+    /// Sustained rate of the global token bucket, in keys per second
+    pub qps: f64,
+    /// Capacity of the global token bucket
+    pub burst: u32,
+}
+
+impl Default for WorkQueueConfig {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(5),
+            max_delay: Duration::from_secs(300),
+            qps: 10.0,
+            burst: 100,
+        }
+    }
+}
+
+/// A queue of object keys (usually URIs) waiting to be reconciled.
+///
+/// * A key is queued at most once, no matter how many times it's added before being processed.
+/// * A key is never handed out to more than one worker at the same time.
+///   If it's added again while being processed, it's queued after [`WorkQueue::done`].
+/// * Failed keys can be requeued with per-key exponential backoff,
+///   combined with a global token bucket, by [`WorkQueue::add_rate_limited`].
+///
+/// It's cheap to clone, all clones share the same queue.
+///
+/// ```no_run
+/// # async fn reconcile(_: &str) -> anyhow::Result<()> { Ok(()) }
+/// # async fn example() {
+/// use resources::informer::WorkQueue;
+///
+/// let queue = WorkQueue::default();
+/// queue.add("/api/v1/replicasets/nginx");
+/// while let Some(key) = queue.get().await {
+///     match reconcile(&key).await {
+///         Ok(_) => queue.forget(&key),
+///         Err(_) => queue.add_rate_limited(&key),
+///     }
+///     queue.done(&key);
+/// }
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct WorkQueue {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    config: WorkQueueConfig,
+    state: Mutex<State>,
+    notify: Notify,
+}
+
+#[derive(Default)]
+struct State {
+    /// Keys ready to be processed, in order
+    queue: VecDeque<String>,
+    /// Keys which need processing, either queued or being processed
+    dirty: HashSet<String>,
+    /// Keys handed out by `get` but not yet `done`
+    processing: HashSet<String>,
+    /// Deadlines of keys scheduled by `add_after`
+    waiting: HashMap<String, Instant>,
+    /// Number of consecutive failures of each key
+    failures: HashMap<String, u32>,
+    bucket: TokenBucket,
+    shutting_down: bool,
+}
+
+struct TokenBucket {
+    /// May go negative, which means tokens are reserved ahead of time
+    tokens: f64,
+    last: Instant,
+}
+
+impl Default for TokenBucket {
+    fn default() -> Self {
+        Self {
+            tokens: WorkQueueConfig::default().burst as f64,
+            last: Instant::now(),
+        }
+    }
+}
+
+impl TokenBucket {
+    /// Take a token, return how long the caller should wait before using it.
+    fn reserve(&mut self, config: &WorkQueueConfig) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.qps).min(config.burst as f64);
+        self.last = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 || config.qps <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / config.qps)
+        }
+    }
+}
+
+impl Default for WorkQueue {
+    fn default() -> Self {
+        Self::new(WorkQueueConfig::default())
+    }
+}
+
+impl WorkQueue {
+    pub fn new(config: WorkQueueConfig) -> Self {
+        let state = State {
+            bucket: TokenBucket {
+                tokens: config.burst as f64,
+                last: Instant::now(),
+            },
+            ..Default::default()
+        };
+        Self {
+            inner: Arc::new(Inner {
+                config,
+                state: Mutex::new(state),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// Queue `key` immediately, unless it's already queued.
+    pub fn add(&self, key: &str) {
+        let mut state = self.lock();
+        if state.shutting_down || state.dirty.contains(key) {
+            return;
+        }
+        state.dirty.insert(key.to_owned());
+        if state.processing.contains(key) {
+            // Requeued in `done`
+            return;
+        }
+        state.queue.push_back(key.to_owned());
+        drop(state);
+        self.inner.notify.notify_one();
+    }
+
+    /// Queue `key` after `delay`.
+    /// If it's already scheduled earlier, the earlier one takes effect.
+    pub fn add_after(&self, key: &str, delay: Duration) {
+        if delay.is_zero() {
+            return self.add(key);
+        }
+        let deadline = Instant::now() + delay;
+        let mut state = self.lock();
+        if state.shutting_down {
+            return;
+        }
+        if matches!(state.waiting.get(key), Some(existing) if *existing <= deadline) {
+            return;
+        }
+        state.waiting.insert(key.to_owned(), deadline);
+        drop(state);
+
+        let queue = self.to_owned();
+        let key = key.to_owned();
+        tokio::spawn(async move {
+            tokio::time::sleep_until(deadline.into()).await;
+            let mut state = queue.lock();
+            // Superseded by an earlier deadline, which has already fired
+            if state.waiting.get(&key) != Some(&deadline) {
+                return;
+            }
+            state.waiting.remove(&key);
+            drop(state);
+            queue.add(&key);
+        });
+    }
+
+    /// Queue `key` after its backoff, which grows exponentially with its failures,
+    /// and the global rate limit, whichever is longer.
+    pub fn add_rate_limited(&self, key: &str) {
+        let config = &self.inner.config;
+        let mut state = self.lock();
+        let failures = state.failures.entry(key.to_owned()).or_insert(0);
+        let backoff = config
+            .base_delay
+            .checked_mul(2u32.saturating_pow(*failures))
+            .unwrap_or(config.max_delay)
+            .min(config.max_delay);
+        *failures += 1;
+        let delay = backoff.max(state.bucket.reserve(config));
+        drop(state);
+        self.add_after(key, delay);
+    }
+
+    /// Clear the failure history of `key` and cancel its pending retry,
+    /// usually called after it's processed successfully.
+    pub fn forget(&self, key: &str) {
+        let mut state = self.lock();
+        state.failures.remove(key);
+        // Its timer finds the deadline gone and does nothing
+        state.waiting.remove(key);
+    }
+
+    /// Number of times `key` has been requeued by [`WorkQueue::add_rate_limited`].
+    pub fn num_requeues(&self, key: &str) -> u32 {
+        self.lock().failures.get(key).copied().unwrap_or(0)
+    }
+
+    /// Wait for the next key to process.
+    /// Return `None` once the queue is shut down and drained.
+    ///
+    /// The caller must call [`WorkQueue::done`] after processing it.
+    pub async fn get(&self) -> Option<String> {
+        loop {
+            let notified = self.inner.notify.notified();
+            {
+                let mut state = self.lock();
+                if let Some(key) = state.queue.pop_front() {
+                    state.dirty.remove(&key);
+                    state.processing.insert(key.to_owned());
+                    return Some(key);
+                }
+                if state.shutting_down {
+                    return None;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Mark `key` as processed. If it was added during processing,
+    /// or its scheduled deadline has passed, it's queued again.
+    pub fn done(&self, key: &str) {
+        let mut state = self.lock();
+        state.processing.remove(key);
+        // Fire an overdue deadline here rather than leaving it to a timer which may never run
+        if matches!(state.waiting.get(key), Some(deadline) if *deadline <= Instant::now()) {
+            state.waiting.remove(key);
+            if !state.shutting_down {
+                state.dirty.insert(key.to_owned());
+            }
+        }
+        if state.dirty.contains(key) {
+            state.queue.push_back(key.to_owned());
+            drop(state);
+            self.inner.notify.notify_one();
+        }
+    }
+
+    /// Stop accepting new keys. Workers drain the keys already queued,
+    /// after which [`WorkQueue::get`] returns `None`.
+    pub fn shut_down(&self) {
+        let mut state = self.lock();
+        state.shutting_down = true;
+        state.waiting.clear();
+        drop(state);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.lock().shutting_down
+    }
+
+    /// Number of keys ready to be processed.
+    pub fn len(&self) -> usize {
+        self.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> WorkQueueConfig {
+        WorkQueueConfig {
+            base_delay: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn deduplicate_keys() {
+        let queue = WorkQueue::default();
+        queue.add("a");
+        queue.add("b");
+        queue.add("a");
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.get().await.as_deref(), Some("a"));
+        assert_eq!(queue.get().await.as_deref(), Some("b"));
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn requeue_key_added_while_processing() {
+        let queue = WorkQueue::default();
+        queue.add("a");
+        let key = queue.get().await.unwrap();
+        queue.add("a");
+        assert!(queue.is_empty());
+        queue.done(&key);
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn earlier_deadline_wins() {
+        let queue = WorkQueue::default();
+        queue.add_after("a", Duration::from_secs(60));
+        queue.add_after("a", Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(queue.len(), 1);
+        assert!(queue.lock().waiting.is_empty());
+    }
+
+    #[tokio::test]
+    async fn forget_cancels_retry() {
+        let queue = WorkQueue::new(config());
+        queue.add("a");
+        let key = queue.get().await.unwrap();
+        queue.add_rate_limited(&key);
+        assert_eq!(queue.num_requeues(&key), 1);
+        queue.forget(&key);
+        queue.done(&key);
+        assert_eq!(queue.num_requeues(&key), 0);
+        assert!(queue.lock().waiting.is_empty());
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn forget_before_requeue() {
+        let queue = WorkQueue::new(config());
+        queue.add("a");
+        let key = queue.get().await.unwrap();
+        queue.forget(&key);
+        queue.add_after(&key, Duration::from_millis(10));
+        queue.done(&key);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(queue.len(), 1);
+        assert!(queue.lock().waiting.is_empty());
+    }
+
+    #[tokio::test]
+    async fn done_fires_overdue_deadline() {
+        let queue = WorkQueue::default();
+        queue.add("a");
+        let key = queue.get().await.unwrap();
+        // As if its timer never ran
+        queue.lock().waiting.insert(key.to_owned(), Instant::now());
+        queue.done(&key);
+        assert_eq!(queue.len(), 1);
+        assert!(queue.lock().waiting.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drain_after_shut_down() {
+        let queue = WorkQueue::default();
+        queue.add("a");
+        queue.add_after("b", Duration::from_secs(60));
+        queue.shut_down();
+        queue.add("c");
+        assert!(queue.lock().waiting.is_empty());
+        assert_eq!(queue.get().await.as_deref(), Some("a"));
+        assert_eq!(queue.get().await, None);
+    }
+}
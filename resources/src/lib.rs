@@ -1,4 +1,6 @@
+pub mod client;
 pub mod config;
+pub mod controller;
 pub mod informer;
 pub mod models;
 pub mod objects;
@@ -0,0 +1,60 @@
+//! Messages exchanged between the scheduler and its HTTP extenders.
+//!
+//! Each call is a POST of JSON to `<urlPrefix>/<verb>`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::objects::pod::Pod;
+
+/// Highest score an extender should give in prioritize,
+/// scaled to the range of score plugins by the scheduler.
+pub const MAX_EXTENDER_PRIORITY: i64 = 10;
+
+/// Body of filter and prioritize calls
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtenderArgs {
+    pub pod: Pod,
+    /// Nodes that passed all filter plugins and previous extenders
+    pub node_names: Vec<String>,
+}
+
+/// Response of filter calls
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExtenderFilterResult {
+    /// Nodes the pod can run on
+    pub node_names: Vec<String>,
+    /// Reason of each node filtered out, by node name
+    pub failed_nodes: HashMap<String, String>,
+    /// Set if the extender failed to filter the nodes
+    pub error: Option<String>,
+}
+
+/// Score of a node in the response of prioritize calls,
+/// in [0, MAX_EXTENDER_PRIORITY].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HostPriority {
+    pub host: String,
+    pub score: i64,
+}
+
+/// Body of bind calls
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtenderBindingArgs {
+    pub pod_name: String,
+    pub pod_uid: Option<Uuid>,
+    pub node: String,
+}
+
+/// Response of bind calls
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ExtenderBindingResult {
+    /// Set if the extender failed to bind the pod
+    pub error: Option<String>,
+}
@@ -2,7 +2,10 @@ use axum::{http::StatusCode, response::IntoResponse, Json};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
+use crate::config::ClusterConfig;
+
 pub mod etcd;
+pub mod extender;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Response<T: Serialize> {
@@ -23,6 +26,23 @@ pub struct NodeConfig {
     pub api_server_endpoint: Url,
 }
 
+impl From<&NodeConfig> for ClusterConfig {
+    /// Cluster config to reach `api_server_endpoint`, watching through the same host
+    fn from(config: &NodeConfig) -> Self {
+        let api_server_url = config
+            .api_server_endpoint
+            .as_str()
+            .trim_end_matches('/')
+            .to_owned();
+        let api_server_watch_url = api_server_url.replacen("http", "ws", 1);
+        ClusterConfig {
+            api_server_url,
+            api_server_watch_url,
+            ..Default::default()
+        }
+    }
+}
+
 impl<T> Response<T>
 where
     T: Serialize,
@@ -57,6 +77,13 @@ impl ErrResponse {
             status: StatusCode::BAD_REQUEST,
         }
     }
+    pub fn conflict(msg: String, cause: Option<String>) -> Self {
+        Self {
+            msg,
+            cause,
+            status: StatusCode::CONFLICT,
+        }
+    }
     pub fn json(&self) -> String {
         serde_json::to_string::<Self>(self).unwrap()
     }
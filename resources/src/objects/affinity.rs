@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+
+use super::Labels;
+
+/// Topology key of a single node,
+/// every node is considered to be labeled with its own name.
+pub const HOSTNAME_TOPOLOGY_KEY: &str = "kubernetes.io/hostname";
+
+/// Scheduling constraints of a pod.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Affinity {
+    /// Node affinity scheduling rules for the pod.
+    pub node_affinity: Option<NodeAffinity>,
+    /// Pod affinity scheduling rules,
+    /// e.g. co-locate this pod in the same node, zone, etc. as some other pods.
+    pub pod_affinity: Option<PodAffinity>,
+    /// Pod anti-affinity scheduling rules,
+    /// e.g. avoid putting this pod in the same node, zone, etc. as some other pods.
+    pub pod_anti_affinity: Option<PodAffinity>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeAffinity {
+    /// If the affinity requirements specified by this field are not met at scheduling time,
+    /// the pod will not be scheduled onto the node.
+    pub required_during_scheduling_ignored_during_execution: Option<NodeSelector>,
+    /// The scheduler will prefer to schedule pods to nodes
+    /// that satisfy the affinity expressions specified by this field,
+    /// but it may choose a node that violates one or more of the expressions.
+    #[serde(default)]
+    pub preferred_during_scheduling_ignored_during_execution: Vec<PreferredSchedulingTerm>,
+}
+
+/// A node selector represents the union of the results
+/// of one or more label queries over a set of nodes.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSelector {
+    /// A list of node selector terms. The terms are ORed.
+    pub node_selector_terms: Vec<NodeSelectorTerm>,
+}
+
+impl NodeSelector {
+    /// Whether any of the terms matches `labels`
+    pub fn matches(&self, labels: &Labels) -> bool {
+        self.node_selector_terms
+            .iter()
+            .any(|term| term.matches(labels))
+    }
+}
+
+/// The requirements of a node selector term are ANDed.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSelectorTerm {
+    /// A list of node selector requirements by node's labels.
+    #[serde(default)]
+    pub match_expressions: Vec<Requirement>,
+}
+
+impl NodeSelectorTerm {
+    /// Whether all the requirements are satisfied by `labels`
+    pub fn matches(&self, labels: &Labels) -> bool {
+        self.match_expressions.iter().all(|req| req.matches(labels))
+    }
+}
+
+/// An empty preferred scheduling term matches all objects with implicit weight 0.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferredSchedulingTerm {
+    /// Weight associated with matching the corresponding node selector term,
+    /// in the range 1-100.
+    pub weight: i64,
+    /// A node selector term, associated with the corresponding weight.
+    pub preference: NodeSelectorTerm,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PodAffinity {
+    /// If the requirements specified by this field are not met at scheduling time,
+    /// the pod will not be scheduled onto the node.
+    /// All terms must be satisfied.
+    #[serde(default)]
+    pub required_during_scheduling_ignored_during_execution: Vec<PodAffinityTerm>,
+    /// The scheduler will prefer to schedule pods to nodes
+    /// that satisfy the expressions specified by this field,
+    /// but it may choose a node that violates one or more of the expressions.
+    #[serde(default)]
+    pub preferred_during_scheduling_ignored_during_execution: Vec<WeightedPodAffinityTerm>,
+}
+
+/// Defines a set of pods that this pod should be co-located (affinity)
+/// or not co-located (anti-affinity) with,
+/// where co-located is defined as running on a node whose value of the label
+/// with key `topology_key` matches that of any node on which a pod of the set is running.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PodAffinityTerm {
+    /// A label query over a set of pods.
+    pub label_selector: LabelSelector,
+    /// Key of the node label which defines the topology domain,
+    /// e.g. "kubernetes.io/hostname" for a single node. Empty topology key is not allowed.
+    pub topology_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightedPodAffinityTerm {
+    /// Weight associated with matching the corresponding pod affinity term,
+    /// in the range 1-100.
+    pub weight: i64,
+    /// A pod affinity term, associated with the corresponding weight.
+    pub pod_affinity_term: PodAffinityTerm,
+}
+
+/// A label query over a set of resources.
+/// The result of `match_labels` and `match_expressions` are ANDed.
+/// An empty label selector matches all objects.
+///
+/// # Examples
+/// ```
+/// use resources::objects::{affinity::LabelSelector, Labels};
+///
+/// let selector: LabelSelector = serde_yaml::from_str(
+///     "matchLabels:\n  app: web\nmatchExpressions:\n  - key: tier\n    operator: NotIn\n    values: [cache]\n",
+/// )
+/// .unwrap();
+/// let labels = Labels::try_from(&"app=web,tier=frontend".to_string()).unwrap();
+/// assert!(selector.matches(&labels));
+/// let labels = Labels::try_from(&"app=web,tier=cache".to_string()).unwrap();
+/// assert!(!selector.matches(&labels));
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSelector {
+    /// Each entry is equivalent to an element of `match_expressions`,
+    /// whose operator is "In", and the values array contains only the value.
+    #[serde(default)]
+    pub match_labels: Labels,
+    /// A list of label selector requirements. The requirements are ANDed.
+    #[serde(default)]
+    pub match_expressions: Vec<Requirement>,
+}
+
+impl LabelSelector {
+    pub fn matches(&self, labels: &Labels) -> bool {
+        labels.matches(&self.match_labels)
+            && self.match_expressions.iter().all(|req| req.matches(labels))
+    }
+}
+
+/// A selector that contains values, a key,
+/// and an operator that relates the key and values.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Requirement {
+    /// The label key that the selector applies to.
+    pub key: String,
+    /// Represents a key's relationship to a set of values.
+    pub operator: Operator,
+    /// An array of string values.
+    /// If the operator is In or NotIn, the values array must be non-empty.
+    /// If the operator is Exists or DoesNotExist, the values array must be empty.
+    /// If the operator is Gt or Lt, the values array must have a single element,
+    /// which will be interpreted as an integer.
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    In,
+    NotIn,
+    Exists,
+    DoesNotExist,
+    /// Only supported by node selector requirements
+    Gt,
+    /// Only supported by node selector requirements
+    Lt,
+}
+
+impl Requirement {
+    /// Whether `labels` satisfies the requirement
+    ///
+    /// # Examples
+    /// ```
+    /// use resources::objects::{
+    ///     affinity::{Operator, Requirement},
+    ///     Labels,
+    /// };
+    ///
+    /// let labels = Labels::try_from(&"disktype=ssd,cores=8".to_string()).unwrap();
+    /// let requirement = |key: &str, operator, values: &[&str]| Requirement {
+    ///     key: key.to_string(),
+    ///     operator,
+    ///     values: values.iter().map(|v| v.to_string()).collect(),
+    /// };
+    /// assert!(requirement("disktype", Operator::In, &["ssd", "nvme"]).matches(&labels));
+    /// assert!(requirement("gpu", Operator::NotIn, &["true"]).matches(&labels));
+    /// assert!(!requirement("gpu", Operator::Exists, &[]).matches(&labels));
+    /// assert!(requirement("cores", Operator::Gt, &["4"]).matches(&labels));
+    /// assert!(!requirement("cores", Operator::Lt, &["4"]).matches(&labels));
+    /// ```
+    pub fn matches(&self, labels: &Labels) -> bool {
+        let value = labels.0.get(&self.key);
+        match self.operator {
+            Operator::In => value.map_or(false, |v| self.values.contains(v)),
+            Operator::NotIn => value.map_or(true, |v| !self.values.contains(v)),
+            Operator::Exists => value.is_some(),
+            Operator::DoesNotExist => value.is_none(),
+            Operator::Gt | Operator::Lt => {
+                let value = value.and_then(|v| v.parse::<i64>().ok());
+                let bound = self.values.first().and_then(|v| v.parse::<i64>().ok());
+                match (value, bound) {
+                    (Some(value), Some(bound)) if self.operator == Operator::Gt => value > bound,
+                    (Some(value), Some(bound)) => value < bound,
+                    _ => false,
+                }
+            },
+        }
+    }
+}
+
+/// Describes how a group of pods ought to spread across topology domains.
+///
+/// # Examples
+/// ```
+/// use resources::objects::affinity::{TopologySpreadConstraint, UnsatisfiableConstraintAction};
+///
+/// let constraint: TopologySpreadConstraint = serde_yaml::from_str(
+///     "maxSkew: 1\ntopologyKey: zone\nwhenUnsatisfiable: ScheduleAnyway\nlabelSelector:\n  matchLabels:\n    app: web\n",
+/// )
+/// .unwrap();
+/// assert_eq!(constraint.max_skew, 1);
+/// assert_eq!(
+///     constraint.when_unsatisfiable,
+///     UnsatisfiableConstraintAction::ScheduleAnyway
+/// );
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologySpreadConstraint {
+    /// The degree to which pods may be unevenly distributed,
+    /// i.e. the maximum permitted difference between the number of matching pods
+    /// in any two topology domains. It must be greater than zero.
+    pub max_skew: i64,
+    /// Key of node labels, nodes that have a label with this key and identical values
+    /// are considered to be in the same topology domain.
+    pub topology_key: String,
+    /// How to deal with a pod if it doesn't satisfy the spread constraint.
+    #[serde(default)]
+    pub when_unsatisfiable: UnsatisfiableConstraintAction,
+    /// Pods matching this label selector are counted
+    /// to determine the number of pods in their topology domain.
+    #[serde(default)]
+    pub label_selector: LabelSelector,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum UnsatisfiableConstraintAction {
+    /// Don't schedule the pod, it's the default
+    DoNotSchedule,
+    /// Schedule the pod anyway, but prefer nodes that reduce the skew
+    ScheduleAnyway,
+}
+
+impl Default for UnsatisfiableConstraintAction {
+    fn default() -> Self {
+        UnsatisfiableConstraintAction::DoNotSchedule
+    }
+}
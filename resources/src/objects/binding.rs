@@ -16,4 +16,12 @@ impl Object for Binding {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
 }
@@ -0,0 +1,261 @@
+use std::{fmt, marker::PhantomData};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use strum::Display;
+
+/// Status of a condition, one of True, False, Unknown.
+#[derive(Debug, Serialize, Deserialize, Display, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConditionStatus {
+    True,
+    False,
+    Unknown,
+}
+
+impl Default for ConditionStatus {
+    fn default() -> Self {
+        ConditionStatus::Unknown
+    }
+}
+
+impl From<bool> for ConditionStatus {
+    fn from(status: bool) -> Self {
+        if status {
+            ConditionStatus::True
+        } else {
+            ConditionStatus::False
+        }
+    }
+}
+
+/// Condition contains details for one aspect of the current state of an object,
+/// `T` is the kind-specific condition type, e.g. `PodConditionType`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Condition<T> {
+    /// Type of condition in CamelCase.
+    #[serde(rename = "type")]
+    pub type_: T,
+    /// Status of the condition, one of True, False, Unknown.
+    pub status: ConditionStatus,
+    /// A programmatic identifier in CamelCase
+    /// indicating the reason for the condition's last transition.
+    pub reason: Option<String>,
+    /// A human readable message indicating details about the transition.
+    pub message: Option<String>,
+    /// Last time the condition transitioned from one status to another.
+    pub last_transition_time: NaiveDateTime,
+    /// The `metadata.generation` that the condition was set based upon.
+    pub observed_generation: Option<i64>,
+}
+
+impl<T> Condition<T> {
+    pub fn new(type_: T, status: impl Into<ConditionStatus>) -> Self {
+        Condition {
+            type_,
+            status: status.into(),
+            reason: None,
+            message: None,
+            last_transition_time: Local::now().naive_utc(),
+            observed_generation: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: impl ToString, message: impl ToString) -> Self {
+        self.reason = Some(reason.to_string());
+        self.message = Some(message.to_string());
+        self
+    }
+
+    pub fn is_true(&self) -> bool {
+        self.status == ConditionStatus::True
+    }
+}
+
+/// Current conditions of an object, at most one for each type.
+///
+/// # Examples
+/// ```
+/// use resources::objects::condition::{Condition, ConditionStatus, Conditions};
+///
+/// let mut conditions = Conditions::default();
+/// assert!(conditions.set(Condition::new("Ready", false).with_reason("NotStarted", "")));
+/// let since = conditions.get(&"Ready").unwrap().last_transition_time;
+/// // Only reason changes, the transition time is kept
+/// assert!(conditions.set(Condition::new("Ready", false).with_reason("Starting", "")));
+/// assert_eq!(conditions.get(&"Ready").unwrap().last_transition_time, since);
+/// assert!(!conditions.set(Condition::new("Ready", false).with_reason("Starting", "")));
+/// assert!(conditions.set(Condition::new("Ready", ConditionStatus::True)));
+/// assert!(conditions.is_true(&"Ready"));
+/// ```
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Conditions<T>(pub Vec<Condition<T>>);
+
+impl<T> Default for Conditions<T> {
+    fn default() -> Self {
+        Conditions(Vec::new())
+    }
+}
+
+impl<T: PartialEq> Conditions<T> {
+    pub fn get(&self, type_: &T) -> Option<&Condition<T>> {
+        self.0.iter().find(|c| &c.type_ == type_)
+    }
+
+    /// Whether the condition of `type_` exists and is True
+    pub fn is_true(&self, type_: &T) -> bool {
+        self.get(type_).map_or(false, Condition::is_true)
+    }
+
+    /// Add or update a condition, return whether anything changed.
+    ///
+    /// The last transition time is only updated when the status changes.
+    pub fn set(&mut self, mut condition: Condition<T>) -> bool {
+        let existing = match self.0.iter_mut().find(|c| c.type_ == condition.type_) {
+            Some(existing) => existing,
+            None => {
+                self.0.push(condition);
+                return true;
+            },
+        };
+        if existing.status == condition.status {
+            if existing.reason == condition.reason
+                && existing.message == condition.message
+                && existing.observed_generation == condition.observed_generation
+            {
+                return false;
+            }
+            condition.last_transition_time = existing.last_transition_time;
+        }
+        *existing = condition;
+        true
+    }
+
+    pub fn remove(&mut self, type_: &T) -> Option<Condition<T>> {
+        let index = self.0.iter().position(|c| &c.type_ == type_)?;
+        Some(self.0.remove(index))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Condition<T>> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Condition stored in the legacy map form, keyed by its type
+#[derive(Deserialize)]
+struct LegacyCondition {
+    status: bool,
+}
+
+/// Accepts both a list of conditions and the legacy map form,
+/// `{"Ready": {"status": true}}`, which older objects are still stored in.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Conditions<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ConditionsVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for ConditionsVisitor<T> {
+            type Value = Conditions<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a list of conditions or a map from type to status")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut conditions = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+                while let Some(condition) = seq.next_element()? {
+                    conditions.push(condition);
+                }
+                Ok(Conditions(conditions))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut conditions = Vec::with_capacity(map.size_hint().unwrap_or_default());
+                while let Some((type_, legacy)) = map.next_entry::<T, LegacyCondition>()? {
+                    conditions.push(Condition::new(type_, legacy.status));
+                }
+                Ok(Conditions(conditions))
+            }
+        }
+
+        deserializer.deserialize_any(ConditionsVisitor(PhantomData))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Conditions<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:<8} {:<20} {:<24} {}",
+            "Type", "Status", "Last Transition", "Reason", "Message"
+        )?;
+        for condition in self.0.iter() {
+            writeln!(
+                f,
+                "{:<20} {:<8} {:<20} {:<24} {}",
+                condition.type_.to_string(),
+                condition.status.to_string(),
+                Local
+                    .from_utc_datetime(&condition.last_transition_time)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+                condition.reason.as_deref().unwrap_or_default(),
+                condition.message.as_deref().unwrap_or_default()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_list() {
+        let json = r#"[{"type": "Ready", "status": "False", "reason": "Starting",
+            "lastTransitionTime": "2022-05-01T08:00:00"}]"#;
+        let conditions: Conditions<String> = serde_json::from_str(json).unwrap();
+        let ready = conditions.get(&"Ready".to_string()).unwrap();
+        assert_eq!(ready.status, ConditionStatus::False);
+        assert_eq!(ready.reason.as_deref(), Some("Starting"));
+    }
+
+    #[test]
+    fn deserialize_legacy_map() {
+        let json = r#"{"Ready": {"status": true}, "PodScheduled": {"status": false}}"#;
+        let conditions: Conditions<String> = serde_json::from_str(json).unwrap();
+        assert_eq!(conditions.0.len(), 2);
+        assert!(conditions.is_true(&"Ready".to_string()));
+        assert_eq!(
+            conditions.get(&"PodScheduled".to_string()).unwrap().status,
+            ConditionStatus::False
+        );
+    }
+
+    #[test]
+    fn serialize_as_list() {
+        let mut conditions = Conditions::default();
+        conditions.set(Condition::new("Ready".to_string(), true));
+        let value = serde_json::to_value(&conditions).unwrap();
+        assert!(value.is_array());
+        let back: Conditions<String> = serde_json::from_value(value).unwrap();
+        assert_eq!(back, conditions);
+    }
+}
@@ -1,8 +1,11 @@
 use std::default::Default;
 
+use indenter::indented;
 use serde::{Deserialize, Serialize};
+use strum::Display;
 
 use super::{
+    condition::Conditions,
     hpa::{HorizontalPodAutoscalerBehavior, MetricSource},
     Labels, Metadata, Object,
 };
@@ -50,6 +53,20 @@ pub struct FunctionStatus {
     pub host: String,
     /// the name of image which wraps this function
     pub image: Option<String>,
+    /// The most recent generation observed by the Function controller.
+    #[serde(default)]
+    pub observed_generation: i64,
+    /// The latest available observations of the function's current state.
+    #[serde(default)]
+    pub conditions: Conditions<FunctionConditionType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, PartialEq, Eq, Hash)]
+pub enum FunctionConditionType {
+    /// The image wrapping the function has been built and pushed.
+    ImageBuilt,
+    /// The ReplicaSet and autoscaler serving the function have been created.
+    Ready,
 }
 
 impl Object for Function {
@@ -60,6 +77,37 @@ impl Object for Function {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl std::fmt::Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16} {}", "Name:", self.metadata.name)?;
+        writeln!(f, "{:<16} {}", "Labels:", self.metadata.labels.to_string())?;
+        let status = match &self.status {
+            Some(status) => status,
+            None => return Ok(()),
+        };
+        writeln!(f, "{:<16} {}", "Host:", status.host)?;
+        writeln!(
+            f,
+            "{:<16} {}",
+            "Image:",
+            status.image.as_deref().unwrap_or("<none>")
+        )?;
+        if !status.conditions.is_empty() {
+            writeln!(f, "Conditions:")?;
+            write!(indented(f), "{}", status.conditions)?;
+        }
+        Ok(())
+    }
 }
 
 impl Function {
@@ -75,6 +123,8 @@ impl Function {
             filename,
             host,
             image: None,
+            observed_generation: 0,
+            conditions: Conditions::default(),
         });
     }
 }
@@ -1,6 +1,8 @@
+use indenter::indented;
 use serde::{Deserialize, Serialize};
+use strum::Display;
 
-use super::{pod::PodTemplateSpec, Metadata, Object};
+use super::{condition::Conditions, pod::PodTemplateSpec, Metadata, Object};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct GpuJob {
@@ -20,6 +22,40 @@ impl Object for GpuJob {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl std::fmt::Display for GpuJob {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16} {}", "Name:", self.metadata.name)?;
+        writeln!(f, "{:<16} {}", "Labels:", self.metadata.labels.to_string())?;
+        writeln!(
+            f,
+            "{:<16} {} completions / {} parallelism",
+            "Spec:", self.spec.completions, self.spec.parallelism
+        )?;
+        let status = match &self.status {
+            Some(status) => status,
+            None => return Ok(()),
+        };
+        writeln!(
+            f,
+            "{:<16} {} active / {} succeeded / {} failed",
+            "Pods:", status.active, status.succeeded, status.failed
+        )?;
+        if !status.conditions.is_empty() {
+            writeln!(f, "Conditions:")?;
+            write!(indented(f), "{}", status.conditions)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -41,6 +77,9 @@ pub struct GpuJobSpec {
     /// Specifies the number of retries before marking this job failed. Defaults to 6
     #[serde(default = "back_off_limit_default")]
     pub back_off_limit: u32,
+    /// Name of the scheduler for the pods of the job,
+    /// defaults to the default scheduler.
+    pub scheduler_name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -73,6 +112,20 @@ pub struct GpuJobStatus {
     pub filename: Option<String>,
     /// Describes the pod that will be created when executing a job.
     pub template: Option<PodTemplateSpec>,
+    /// The most recent generation observed by the GpuJob controller.
+    #[serde(default)]
+    pub observed_generation: i64,
+    /// The latest available observations of the job's current state.
+    #[serde(default)]
+    pub conditions: Conditions<GpuJobConditionType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, PartialEq, Eq, Hash)]
+pub enum GpuJobConditionType {
+    /// The job has completed its execution.
+    Complete,
+    /// The job has failed its execution.
+    Failed,
 }
 
 fn completions_default() -> u32 {
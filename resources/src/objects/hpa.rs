@@ -1,8 +1,15 @@
 use chrono::NaiveDateTime;
+use indenter::indented;
 use serde::{Deserialize, Serialize};
+use strum::Display;
 
 use super::{
-    function::Function, metrics::Resource, object_reference::ObjectReference, Metadata, Object,
+    condition::Conditions,
+    function::Function,
+    metrics::Resource,
+    object_reference::ObjectReference,
+    quantity::{LegacyQuantity, Quantity},
+    Metadata, Object,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -20,6 +27,42 @@ impl Object for HorizontalPodAutoscaler {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl std::fmt::Display for HorizontalPodAutoscaler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16} {}", "Name:", self.metadata.name)?;
+        writeln!(f, "{:<16} {}", "Labels:", self.metadata.labels.to_string())?;
+        let target = &self.spec.scale_target_ref;
+        writeln!(f, "{:<16} {}/{}", "Target:", target.kind, target.name)?;
+        writeln!(
+            f,
+            "{:<16} {} - {}",
+            "Replicas Range:", self.spec.min_replicas, self.spec.max_replicas
+        )?;
+        let status = match &self.status {
+            Some(status) => status,
+            None => return Ok(()),
+        };
+        writeln!(
+            f,
+            "{:<16} {} current / {} desired",
+            "Replicas:", status.current_replicas, status.desired_replicas
+        )?;
+        if !status.conditions.is_empty() {
+            writeln!(f, "Conditions:")?;
+            write!(indented(f), "{}", status.conditions)?;
+        }
+        Ok(())
+    }
 }
 
 impl HorizontalPodAutoscaler {
@@ -33,6 +76,7 @@ impl HorizontalPodAutoscaler {
                 kind: "function".to_string(),
                 name: func_name.to_owned(),
             }],
+            ..Default::default()
         };
         let spec = HorizontalPodAutoscalerSpec {
             scale_target_ref: ObjectReference {
@@ -245,6 +289,7 @@ pub enum MetricSource {
 /// describing each pod in the current scale target (e.g. CPU or memory).
 /// The values will be averaged together before being compared to the target.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(from = "LegacyResourceMetricSource")]
 pub struct ResourceMetricSource {
     /// Name of the resource.
     pub name: Resource,
@@ -252,6 +297,44 @@ pub struct ResourceMetricSource {
     pub target: MetricTarget,
 }
 
+/// Resource metric source written before quantities were introduced,
+/// whose average value is a plain integer in milli-CPU or bytes.
+#[derive(Deserialize)]
+struct LegacyResourceMetricSource {
+    name: Resource,
+    target: LegacyMetricTarget,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum LegacyMetricTarget {
+    AverageUtilization(u32),
+    AverageValue(LegacyQuantity),
+}
+
+impl From<LegacyResourceMetricSource> for ResourceMetricSource {
+    fn from(source: LegacyResourceMetricSource) -> Self {
+        let target = match source.target {
+            LegacyMetricTarget::AverageUtilization(utilization) => {
+                MetricTarget::AverageUtilization(utilization)
+            },
+            LegacyMetricTarget::AverageValue(LegacyQuantity::Quantity(value)) => {
+                MetricTarget::AverageValue(value)
+            },
+            LegacyMetricTarget::AverageValue(LegacyQuantity::Integer(value)) => {
+                MetricTarget::AverageValue(match source.name {
+                    Resource::CPU => Quantity::from_milli(value),
+                    Resource::Memory => Quantity::from_bytes(value),
+                })
+            },
+        };
+        ResourceMetricSource {
+            name: source.name,
+            target,
+        }
+    }
+}
+
 impl Default for ResourceMetricSource {
     fn default() -> Self {
         ResourceMetricSource {
@@ -280,9 +363,8 @@ pub enum MetricTarget {
     /// of the resource for the pods.
     AverageUtilization(u32),
     /// Target value of the average of the resource metric
-    /// across all relevant pods.
-    /// Percentage for CPU, megabytes for memory.
-    AverageValue(u64),
+    /// across all relevant pods, e.g. "200m" for CPU, "64Mi" for memory.
+    AverageValue(Quantity),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -297,4 +379,66 @@ pub struct HorizontalPodAutoscalerStatus {
     /// Last time the HorizontalPodAutoscaler scaled the number of pods,
     /// used by the autoscaler to control how often the number of pods is changed.
     pub last_scale_time: Option<NaiveDateTime>,
+    /// The most recent generation observed by this autoscaler.
+    #[serde(default)]
+    pub observed_generation: i64,
+    /// Conditions needed for this autoscaler to scale its target,
+    /// and whether or not those conditions are met.
+    #[serde(default)]
+    pub conditions: Conditions<HorizontalPodAutoscalerConditionType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, PartialEq, Eq, Hash)]
+pub enum HorizontalPodAutoscalerConditionType {
+    /// Whether the autoscaler is able to fetch and update scales,
+    /// and whether backoff-related conditions prevent scaling.
+    AbleToScale,
+    /// Whether the autoscaler is enabled and able to calculate the desired scales.
+    ScalingActive,
+    /// Whether the desired scale was capped
+    /// by the maximum or minimum of the autoscaler.
+    ScalingLimited,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_average_value() {
+        let source: ResourceMetricSource =
+            serde_json::from_str(r#"{"name": "Memory", "target": {"averageValue": "64Mi"}}"#)
+                .unwrap();
+        assert_eq!(
+            source.target,
+            MetricTarget::AverageValue("64Mi".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn deserialize_legacy_average_value() {
+        let cpu: ResourceMetricSource =
+            serde_json::from_str(r#"{"name": "CPU", "target": {"averageValue": 200}}"#).unwrap();
+        assert_eq!(
+            cpu.target,
+            MetricTarget::AverageValue(Quantity::from_milli(200))
+        );
+        let memory: ResourceMetricSource =
+            serde_json::from_str(r#"{"name": "Memory", "target": {"averageValue": 1048576}}"#)
+                .unwrap();
+        assert_eq!(
+            memory.target,
+            MetricTarget::AverageValue(Quantity::from_bytes(1024 * 1024))
+        );
+    }
+
+    #[test]
+    fn round_trip_average_utilization() {
+        let source = ResourceMetricSource::default();
+        let json = serde_json::to_string(&source).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ResourceMetricSource>(&json).unwrap(),
+            source
+        );
+    }
 }
@@ -1,13 +1,16 @@
 use std::{collections::HashMap, fmt::Debug};
 
 use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
 use enum_dispatch::enum_dispatch;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use uuid::Uuid;
 
 use self::object_reference::ObjectReference;
 
+pub mod affinity;
 pub mod binding;
+pub mod condition;
 pub mod function;
 pub mod gpu_job;
 pub mod hpa;
@@ -16,8 +19,12 @@ pub mod metrics;
 pub mod node;
 pub mod object_reference;
 pub mod pod;
+pub mod pod_group;
+pub mod priority_class;
+pub mod quantity;
 pub mod replica_set;
 pub mod service;
+pub mod taint;
 pub mod workflow;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -34,6 +41,8 @@ pub enum KubeObject {
     GpuJob(gpu_job::GpuJob),
     Function(function::Function),
     Workflow(workflow::Workflow),
+    PriorityClass(priority_class::PriorityClass),
+    PodGroup(pod_group::PodGroup),
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
@@ -45,7 +54,15 @@ pub struct Metadata {
     /// to request the generation of an appropriate name automatically.
     /// Name is primarily intended for creation idempotence
     /// and configuration definition. Cannot be updated.
+    #[serde(default)]
     pub name: String,
+    /// Optional prefix used by the server to generate a unique name
+    /// ONLY IF the name field has not been provided.
+    /// The generated name consists of this prefix and a random suffix,
+    /// e.g. "nginx-" -> "nginx-x7k2p".
+    /// If this field is specified and the generated name exists,
+    /// the server will retry a few times before giving up.
+    pub generate_name: Option<String>,
     /// UID is the unique in time and space value for this object.
     /// It is typically generated by the server
     /// on successful creation of a resource
@@ -57,6 +74,18 @@ pub struct Metadata {
     /// May match selectors of replication controllers and services.
     #[serde(default)]
     pub labels: Labels,
+    /// Unstructured key value map stored with a resource
+    /// that may be set by external tools to store and retrieve arbitrary metadata.
+    /// They are not queryable and should be preserved when modifying objects.
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+    /// A timestamp representing the server time when this object was created.
+    /// Populated by the system. Read-only.
+    pub creation_timestamp: Option<NaiveDateTime>,
+    /// A sequence number representing a specific generation of the desired state.
+    /// Populated by the system. Read-only.
+    #[serde(default)]
+    pub generation: i64,
     /// List of objects depended by this object.
     /// If ALL objects in the list have been deleted,
     /// this object will be garbage collected.
@@ -85,6 +114,12 @@ pub trait Object:
     /// Return the name of the object
     fn name(&self) -> &String;
 
+    /// Return the metadata of the object
+    fn metadata(&self) -> &Metadata;
+
+    /// Return the mutable metadata of the object
+    fn metadata_mut(&mut self) -> &mut Metadata;
+
     /// Return the prefix of the object in Etcd,
     /// e.g. Pod -> "/api/v1/pods"
     fn prefix(&self) -> String {
@@ -6,16 +6,43 @@ use indenter::indented;
 use serde::{Deserialize, Serialize};
 use strum::Display;
 
-use super::{Metadata, Object};
+use super::{
+    condition::Conditions,
+    quantity::{deserialize_legacy_kibi, Quantity},
+    taint::Taint,
+    Metadata, Object,
+};
 use crate::config::kubelet::KubeletConfig;
 
+/// Taint added by the node lifecycle controller when the node misses its status reports
+pub const TAINT_NODE_NOT_READY: &str = "node.kubernetes.io/not-ready";
+/// Taint added by the node lifecycle controller
+/// when the node stops reporting status for longer than the grace period
+pub const TAINT_NODE_UNREACHABLE: &str = "node.kubernetes.io/unreachable";
+/// Taint a pod tolerates to be scheduled onto an unschedulable node
+pub const TAINT_NODE_UNSCHEDULABLE: &str = "node.kubernetes.io/unschedulable";
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Node {
     pub metadata: Metadata,
     #[serde(default)]
+    pub spec: NodeSpec,
+    #[serde(default)]
     pub status: NodeStatus,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSpec {
+    /// If specified, the node's taints.
+    #[serde(default)]
+    pub taints: Vec<Taint>,
+    /// Unschedulable controls node schedulability of new pods,
+    /// set by cordoning the node.
+    #[serde(default)]
+    pub unschedulable: bool,
+}
+
 impl Object for Node {
     fn kind(&self) -> &'static str {
         "Node"
@@ -24,6 +51,14 @@ impl Object for Node {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
 }
 
 impl Node {
@@ -40,6 +75,23 @@ impl std::fmt::Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{:<16} {}", "Name:", self.metadata.name)?;
         writeln!(f, "{:<16} {}", "Labels:", self.metadata.labels.to_string())?;
+        let taints = self
+            .spec
+            .taints
+            .iter()
+            .map(Taint::to_string)
+            .collect::<Vec<_>>();
+        writeln!(
+            f,
+            "{:<16} {}",
+            "Taints:",
+            if taints.is_empty() {
+                "<none>".to_string()
+            } else {
+                taints.join(", ")
+            }
+        )?;
+        writeln!(f, "{:<16} {}", "Unschedulable:", self.spec.unschedulable)?;
         let status = &self.status;
         writeln!(
             f,
@@ -59,23 +111,48 @@ impl std::fmt::Display for Node {
         write!(indented(f), "{}", status.allocatable)?;
 
         writeln!(f, "System Info:")?;
-        write!(indented(f), "{}", status.node_info)
+        write!(indented(f), "{}", status.node_info)?;
+
+        if !status.conditions.is_empty() {
+            writeln!(f, "Conditions:")?;
+            write!(indented(f), "{}", status.conditions)?;
+        }
+        Ok(())
     }
 }
 
 impl Node {
+    /// Whether the Ready condition is True,
+    /// judged from the last heartbeat if the condition isn't set yet.
     pub fn is_ready(&self) -> bool {
-        let now = Local::now().naive_utc();
-        let config = KubeletConfig::default();
-        self.status.last_heartbeat
-            > now
-                - (Duration::seconds(
-                    config.node_status_report_frequency as i64
-                        + config.node_status_update_frequency as i64,
-                ))
+        match self.status.conditions.get(&NodeConditionType::Ready) {
+            Some(condition) => condition.is_true(),
+            None => self.heartbeat_age() < status_report_window(),
+        }
+    }
+
+    /// Pressure conditions that are True
+    pub fn pressures(&self) -> Vec<NodeConditionType> {
+        NodeConditionType::PRESSURES
+            .into_iter()
+            .filter(|pressure| self.status.conditions.is_true(pressure))
+            .collect()
+    }
+
+    /// Time since the last heartbeat of rKubelet
+    pub fn heartbeat_age(&self) -> Duration {
+        Local::now().naive_utc() - self.status.last_heartbeat
     }
 }
 
+/// Longest expected interval between two status reports of rKubelet
+fn status_report_window() -> Duration {
+    let config = KubeletConfig::default();
+    Duration::seconds(
+        config.node_status_report_frequency as i64 + config.node_status_update_frequency as i64,
+    )
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Derivative, Eq)]
 #[derivative(PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -95,6 +172,30 @@ pub struct NodeStatus {
     /// Last heartbeat time, set by rKubelet.
     #[derivative(PartialEq = "ignore")]
     pub last_heartbeat: NaiveDateTime,
+    /// Current service state of the node.
+    #[serde(default)]
+    pub conditions: Conditions<NodeConditionType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, Copy, PartialEq, Eq)]
+pub enum NodeConditionType {
+    /// The node is healthy and ready to accept pods,
+    /// set by the node lifecycle controller from heartbeats.
+    Ready,
+    /// Available memory on the node is low, set by rKubelet.
+    MemoryPressure,
+    /// Available disk space on the node is low, set by rKubelet.
+    DiskPressure,
+    /// Too many processes are running on the node, set by rKubelet.
+    PIDPressure,
+}
+
+impl NodeConditionType {
+    pub const PRESSURES: [NodeConditionType; 3] = [
+        NodeConditionType::MemoryPressure,
+        NodeConditionType::DiskPressure,
+        NodeConditionType::PIDPressure,
+    ];
 }
 
 impl Default for NodeStatus {
@@ -106,6 +207,7 @@ impl Default for NodeStatus {
             kubelet_port: 10250,
             node_info: NodeInfo::default(),
             last_heartbeat: NaiveDateTime::from_timestamp(0, 0),
+            conditions: Conditions::default(),
         }
     }
 }
@@ -142,15 +244,17 @@ impl std::fmt::Display for NodeInfo {
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
 pub struct Capacity {
-    /// Number of cpu cores on the node.
-    pub cpu: u16,
-    /// Amount of memory in kilobytes.
-    pub memory: u64,
+    /// CPU cores on the node.
+    pub cpu: Quantity,
+    /// Amount of memory, e.g. "16Gi".
+    /// A plain integer is in kibibytes, as posted by older rKubelet.
+    #[serde(deserialize_with = "deserialize_legacy_kibi")]
+    pub memory: Quantity,
 }
 
 impl std::fmt::Display for Capacity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "CPU: {}", self.cpu)?;
-        writeln!(f, "Memory: {}KB", self.memory)
+        writeln!(f, "Memory: {}", self.memory)
     }
 }
@@ -5,9 +5,18 @@ use bollard::models::{ContainerInspectResponse, ContainerStateStatusEnum, Restar
 use chrono::{Local, NaiveDateTime, TimeZone};
 use indenter::indented;
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumIter, IntoEnumIterator};
-
-use super::{function::Function, metrics, Labels, Metadata, Object};
+use strum::Display;
+
+use super::{
+    affinity::{Affinity, TopologySpreadConstraint},
+    condition::Conditions,
+    function::Function,
+    metrics,
+    priority_class::PreemptionPolicy,
+    quantity::{deserialize_legacy_milli, Quantity},
+    taint::Toleration,
+    Labels, Metadata, Object,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Pod {
@@ -24,15 +33,20 @@ impl Object for Pod {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
 }
 
 impl Pod {
     pub fn is_ready(&self) -> bool {
         match &self.status {
-            Some(status) => status
-                .conditions
-                .get(&PodConditionType::Ready)
-                .map_or(false, |condition| condition.status),
+            Some(status) => status.conditions.is_true(&PodConditionType::Ready),
             None => false,
         }
     }
@@ -60,6 +74,16 @@ impl Pod {
             .map_or(false, |name| name == node_name)
     }
 
+    /// Priority of the pod, 0 if not resolved from a priority class
+    pub fn priority(&self) -> i32 {
+        self.spec.priority.unwrap_or_default()
+    }
+
+    /// Node the pod is expected to be scheduled to after preemption
+    pub fn nominated_node_name(&self) -> Option<&String> {
+        self.status.as_ref()?.nominated_node_name.as_ref()
+    }
+
     pub fn requests(&self, resource: &metrics::Resource) -> i64 {
         self.spec
             .containers
@@ -108,7 +132,15 @@ impl std::fmt::Display for Pod {
             Local.from_utc_datetime(&status.start_time)
         )?;
         writeln!(f, "{:<16} {}", "Labels:", self.metadata.labels.to_string())?;
+        writeln!(f, "{:<16} {}", "Priority:", self.priority())?;
+        if let Some(class) = &self.spec.priority_class_name {
+            writeln!(f, "{:<16} {}", "Priority Class:", class)?;
+        }
+        writeln!(f, "{:<16} {}", "Scheduler:", self.spec.scheduler_name())?;
         writeln!(f, "{:<16} {}", "Phase:", status.phase)?;
+        if let Some(node) = &status.nominated_node_name {
+            writeln!(f, "{:<16} {}", "Nominated Node:", node)?;
+        }
         writeln!(
             f,
             "{:<16} {}",
@@ -126,15 +158,7 @@ impl std::fmt::Display for Pod {
         }
 
         writeln!(f, "Conditions:")?;
-        writeln!(indented(f), "{:<16} {:<8}", "Type", "Status")?;
-        for (condition_type, condition) in status.conditions.iter() {
-            writeln!(
-                indented(f),
-                "{:<16} {:<8}",
-                condition_type,
-                condition.status
-            )?;
-        }
+        write!(indented(f), "{}", status.conditions)?;
 
         if !self.spec.volumes.is_empty() {
             writeln!(f, "Volumes:")?;
@@ -172,13 +196,53 @@ pub struct PodSpec {
     /// for the pod to be scheduledon that node.
     #[serde(default)]
     pub node_selector: Labels,
+    /// If specified, the pod's scheduling constraints,
+    /// including node affinity and pod (anti-)affinity.
+    pub affinity: Option<Affinity>,
+    /// Describes how a group of pods ought to spread across topology domains.
+    /// All constraints are ANDed.
+    #[serde(default)]
+    pub topology_spread_constraints: Vec<TopologySpreadConstraint>,
+    /// If specified, the pod's tolerations.
+    #[serde(default)]
+    pub tolerations: Vec<Toleration>,
+    /// If specified, indicates the pod's priority.
+    /// If not specified, the pod priority will be default
+    /// or zero if there is no default.
+    pub priority_class_name: Option<String>,
+    /// The priority value, populated from `priority_class_name` by API server.
+    /// The higher the value, the higher the priority.
+    pub priority: Option<i32>,
+    /// Policy for preempting pods with lower priority,
+    /// populated from `priority_class_name` by API server.
+    pub preemption_policy: Option<PreemptionPolicy>,
+    /// Duration in seconds the pod needs to terminate gracefully,
+    /// after which its containers are killed. Defaults to 30 seconds.
+    pub termination_grace_period_seconds: Option<i64>,
+    /// Name of the scheduler responsible for the pod,
+    /// defaults to the default scheduler.
+    pub scheduler_name: Option<String>,
     /// NodeName is a request to schedule this pod onto a specific node.
     /// If it is non-empty, the scheduler simply schedules this pod onto that node,
     /// assuming that it fits resource requirements.
     pub node_name: Option<String>,
 }
 
+pub const DEFAULT_TERMINATION_GRACE_PERIOD_SECONDS: i64 = 30;
+pub const DEFAULT_SCHEDULER_NAME: &str = "default-scheduler";
+
 impl PodSpec {
+    pub fn termination_grace_period_seconds(&self) -> i64 {
+        self.termination_grace_period_seconds
+            .unwrap_or(DEFAULT_TERMINATION_GRACE_PERIOD_SECONDS)
+    }
+
+    pub fn scheduler_name(&self) -> &str {
+        self.scheduler_name
+            .as_deref()
+            .unwrap_or(DEFAULT_SCHEDULER_NAME)
+    }
+
     pub fn network_mode(&self) -> String {
         if self.host_network {
             "host".to_string()
@@ -230,8 +294,8 @@ pub struct Container {
 impl Container {
     pub fn requests(&self, resource: &metrics::Resource) -> i64 {
         match resource {
-            metrics::Resource::CPU => self.resources.requests.cpu,
-            metrics::Resource::Memory => self.resources.requests.memory,
+            metrics::Resource::CPU => self.resources.requests.cpu.milli_value(),
+            metrics::Resource::Memory => self.resources.requests.memory.value(),
         }
     }
 
@@ -353,10 +417,10 @@ impl std::fmt::Display for ResourceRequirements {
 impl ResourceRequirements {
     pub fn cpu_shares(&self) -> i64 {
         ResourceRequirements::milli_cpu_to_shares(
-            if self.requests.cpu == 0 && self.limits.cpu != 0 {
-                self.limits.cpu
+            if self.requests.cpu.is_zero() && !self.limits.cpu.is_zero() {
+                self.limits.cpu.milli_value()
             } else {
-                self.requests.cpu
+                self.requests.cpu.milli_value()
             },
         )
     }
@@ -376,10 +440,12 @@ impl ResourceRequirements {
 #[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
 #[serde(default)]
 pub struct Resource {
-    /// CPU unit in milli CPU.
-    pub cpu: i64,
-    /// Memory in bytes. Defaults to 0.
-    pub memory: i64,
+    /// CPU in cores, e.g. "500m" or "2".
+    /// A plain integer is in milli CPU, as in older manifests.
+    #[serde(deserialize_with = "deserialize_legacy_milli")]
+    pub cpu: Quantity,
+    /// Memory in bytes, e.g. "256Mi" or 268435456. Defaults to 0.
+    pub memory: Quantity,
 }
 
 impl std::fmt::Display for Resource {
@@ -466,25 +532,26 @@ pub struct PodStatus {
     #[serde(rename = "podIP")]
     pub pod_ip: Option<Ipv4Addr>,
     /// Current service state of pod.
-    pub conditions: HashMap<PodConditionType, PodCondition>,
+    #[serde(default)]
+    pub conditions: Conditions<PodConditionType>,
     /// The list has one entry per container in the manifest.
     /// Each entry is currently the output of docker inspect.
     pub container_statuses: Vec<ContainerStatus>,
+    /// Set when the pod preempts pods on the node,
+    /// it may still be scheduled elsewhere once the victims are gone.
+    pub nominated_node_name: Option<String>,
 }
 
 impl Default for PodStatus {
     fn default() -> Self {
-        let mut conditions = HashMap::new();
-        PodConditionType::iter().for_each(|c| {
-            conditions.insert(c, PodCondition::default());
-        });
         PodStatus {
             host_ip: None,
             start_time: Local::now().naive_utc(),
             phase: PodPhase::Pending,
             pod_ip: None,
-            conditions,
+            conditions: Conditions::default(),
             container_statuses: vec![],
+            nominated_node_name: None,
         }
     }
 }
@@ -512,7 +579,7 @@ pub enum PodPhase {
     Succeeded,
 }
 
-#[derive(Debug, Serialize, Deserialize, Display, PartialEq, Eq, Hash, EnumIter, Clone)]
+#[derive(Debug, Serialize, Deserialize, Display, PartialEq, Eq, Hash, Clone)]
 pub enum PodConditionType {
     /// All containers in the pod are ready.
     ContainersReady,
@@ -523,13 +590,6 @@ pub enum PodConditionType {
     Ready,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone, Eq, PartialEq)]
-pub struct PodCondition {
-    /// Status is the status of the condition.
-    /// Can be True, False, Unknown.
-    pub status: bool,
-}
-
 #[derive(Debug, Serialize, Deserialize, Display, Clone, Eq, PartialEq)]
 pub enum ContainerState {
     Running,
@@ -0,0 +1,126 @@
+use indenter::indented;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+
+use super::{condition::Conditions, pod::Pod, Metadata, Object};
+
+/// Label of pods belonging to a pod group, whose value is the name of the group
+pub const POD_GROUP_LABEL: &str = "scheduling.minik8s.io/pod-group";
+
+/// Seconds to wait for all members of a group to be placed by default
+pub const DEFAULT_SCHEDULE_TIMEOUT_SECONDS: u64 = 60;
+
+/// A collection of pods which are scheduled as a whole,
+/// i.e. none of them is bound until `min_member` of them can run at the same time.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PodGroup {
+    pub metadata: Metadata,
+    /// Defines the specification of the desired behavior of the pod group.
+    pub spec: PodGroupSpec,
+    /// The most recently observed status of the pod group.
+    /// Populated by the scheduler. Read-only.
+    pub status: Option<PodGroupStatus>,
+}
+
+impl Object for PodGroup {
+    fn kind(&self) -> &'static str {
+        "PodGroup"
+    }
+
+    fn name(&self) -> &String {
+        &self.metadata.name
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl std::fmt::Display for PodGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:<16} {}", "Name:", self.metadata.name)?;
+        writeln!(f, "{:<16} {}", "Labels:", self.metadata.labels.to_string())?;
+        writeln!(f, "{:<16} {}", "Min Member:", self.spec.min_member)?;
+        writeln!(
+            f,
+            "{:<16} {}s",
+            "Timeout:",
+            self.spec.schedule_timeout_seconds()
+        )?;
+        let status = match &self.status {
+            Some(status) => status,
+            None => return Ok(()),
+        };
+        writeln!(f, "{:<16} {}", "Phase:", status.phase)?;
+        writeln!(f, "{:<16} {}", "Scheduled:", status.scheduled)?;
+        if !status.conditions.is_empty() {
+            writeln!(f, "Conditions:")?;
+            write!(indented(f), "{}", status.conditions)?;
+        }
+        Ok(())
+    }
+}
+
+/// Name of the pod group the pod belongs to
+pub fn pod_group_name(pod: &Pod) -> Option<&String> {
+    pod.metadata.labels.0.get(POD_GROUP_LABEL)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PodGroupSpec {
+    /// Minimal number of members to run the group,
+    /// the scheduler won't bind any of them until this many can be placed.
+    pub min_member: u32,
+    /// Seconds to wait for all members to be placed
+    /// after the first one fits, before releasing them. Defaults to 60.
+    pub schedule_timeout_seconds: Option<u64>,
+}
+
+impl PodGroupSpec {
+    pub fn schedule_timeout_seconds(&self) -> u64 {
+        self.schedule_timeout_seconds
+            .unwrap_or(DEFAULT_SCHEDULE_TIMEOUT_SECONDS)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PodGroupStatus {
+    /// Current phase of the group.
+    #[serde(default)]
+    pub phase: PodGroupPhase,
+    /// Number of members bound to nodes.
+    #[serde(default)]
+    pub scheduled: u32,
+    /// The latest available observations of the group's current state.
+    #[serde(default)]
+    pub conditions: Conditions<PodGroupConditionType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, Copy, PartialEq, Eq)]
+pub enum PodGroupPhase {
+    /// Not enough members have been placed yet
+    Pending,
+    /// Some members fit and are waiting for the rest of the group
+    Scheduling,
+    /// At least `min_member` members are bound to nodes
+    Scheduled,
+}
+
+impl Default for PodGroupPhase {
+    fn default() -> Self {
+        PodGroupPhase::Pending
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, PartialEq, Eq, Hash)]
+pub enum PodGroupConditionType {
+    /// Whether the group has been scheduled as a whole,
+    /// the reason tells why not if it's false.
+    Scheduled,
+}
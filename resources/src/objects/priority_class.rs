@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Metadata, Object};
+
+/// Mapping from a priority class name to the priority integer value.
+/// The value can be any valid integer.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityClass {
+    pub metadata: Metadata,
+    /// The integer value of this priority class.
+    /// This is the actual priority that pods receive
+    /// when they have the name of this class in their pod spec.
+    pub value: i32,
+    /// Whether this priority class should be considered as the default priority
+    /// for pods that do not have any priority class.
+    /// Only one priority class can be marked as global default.
+    #[serde(default)]
+    pub global_default: bool,
+    /// The policy for preempting pods with lower priority.
+    /// One of Never, PreemptLowerPriority.
+    /// Defaults to PreemptLowerPriority if unset.
+    #[serde(default)]
+    pub preemption_policy: PreemptionPolicy,
+    /// An arbitrary string that usually provides guidelines
+    /// on when this priority class should be used.
+    pub description: Option<String>,
+}
+
+impl Object for PriorityClass {
+    fn kind(&self) -> &'static str {
+        "PriorityClass"
+    }
+
+    fn kind_plural(&self) -> String {
+        "PriorityClasses".to_string()
+    }
+
+    fn name(&self) -> &String {
+        &self.metadata.name
+    }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PreemptionPolicy {
+    /// Pods can preempt other pods with lower priority
+    PreemptLowerPriority,
+    /// Pods never preempt other pods with lower priority
+    Never,
+}
+
+impl Default for PreemptionPolicy {
+    fn default() -> Self {
+        PreemptionPolicy::PreemptLowerPriority
+    }
+}
@@ -0,0 +1,514 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Sub, SubAssign},
+    str::FromStr,
+};
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Quantity is a fixed-point representation of a number,
+/// in the same format as Kubernetes, e.g. "500m", "256Mi", "2Gi", "1.5".
+///
+/// The value is kept in milli-units,
+/// so it's precise to 0.001 and can't exceed about 9.2e15.
+///
+/// Accepted suffixes:
+/// * decimal SI: `m` (milli), `k`, `M`, `G`, `T`, `P`, `E`
+/// * binary SI: `Ki`, `Mi`, `Gi`, `Ti`, `Pi`, `Ei`
+/// * decimal exponent: `e3`, `E-2`
+///
+/// # Examples
+/// ```
+/// use resources::objects::quantity::Quantity;
+///
+/// let cpu: Quantity = "500m".parse().unwrap();
+/// assert_eq!(cpu.milli_value(), 500);
+/// let memory: Quantity = "256Mi".parse().unwrap();
+/// assert_eq!(memory.value(), 256 * 1024 * 1024);
+/// assert_eq!(memory.to_string(), "256Mi");
+/// assert_eq!("1.5".parse::<Quantity>().unwrap(), "1500m".parse().unwrap());
+/// assert_eq!((cpu + "1".parse().unwrap()).to_string(), "1500m");
+/// assert!("1.5Ki".parse::<Quantity>().unwrap() > "1k".parse().unwrap());
+/// assert!("12x".parse::<Quantity>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quantity {
+    milli: i64,
+    format: Format,
+}
+
+/// How a quantity is printed, kept from the parsed string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// e.g. "1500m", "2k"
+    DecimalSI,
+    /// e.g. "256Mi"
+    BinarySI,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::DecimalSI
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseQuantityError(String);
+
+impl fmt::Display for ParseQuantityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid quantity \"{}\"", self.0)
+    }
+}
+
+impl std::error::Error for ParseQuantityError {}
+
+const DECIMAL_SUFFIXES: [(&str, u32); 6] =
+    [("k", 1), ("M", 2), ("G", 3), ("T", 4), ("P", 5), ("E", 6)];
+const BINARY_SUFFIXES: [(&str, u32); 6] = [
+    ("Ki", 1),
+    ("Mi", 2),
+    ("Gi", 3),
+    ("Ti", 4),
+    ("Pi", 5),
+    ("Ei", 6),
+];
+
+impl Quantity {
+    /// Create a quantity of `milli` thousandths, e.g. milli-CPU.
+    pub fn from_milli(milli: i64) -> Self {
+        Self {
+            milli,
+            format: Format::DecimalSI,
+        }
+    }
+
+    /// Create a quantity of whole units, e.g. CPU cores.
+    pub fn from_value(value: i64) -> Self {
+        Self::from_milli(value.saturating_mul(1000))
+    }
+
+    /// Create a quantity of bytes, printed with binary suffixes.
+    pub fn from_bytes(bytes: i64) -> Self {
+        Self {
+            milli: bytes.saturating_mul(1000),
+            format: Format::BinarySI,
+        }
+    }
+
+    /// Value in milli-units, e.g. milli-CPU
+    pub fn milli_value(&self) -> i64 {
+        self.milli
+    }
+
+    /// Value in whole units, rounded up, e.g. bytes
+    pub fn value(&self) -> i64 {
+        let value = self.milli / 1000;
+        if self.milli % 1000 > 0 {
+            value + 1
+        } else {
+            value
+        }
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        self.milli as f64 / 1000.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.milli == 0
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = ParseQuantityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseQuantityError(s.to_owned());
+        let trimmed = s.trim();
+        let (negative, unsigned) = match trimmed.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, trimmed.strip_prefix('+').unwrap_or(trimmed)),
+        };
+        let number_len = unsigned
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(unsigned.len());
+        let (number, suffix) = unsigned.split_at(number_len);
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(err());
+        }
+
+        // value = mantissa * numerator / denominator
+        let mut mantissa: i128 = 0;
+        for digit in integer.chars().chain(fraction.chars()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(digit.to_digit(10)? as i128))
+                .ok_or_else(err)?;
+        }
+        let mut numerator: i128 = 1000;
+        let mut denominator: i128 = 10i128.checked_pow(fraction.len() as u32).ok_or_else(err)?;
+        let mut format = Format::DecimalSI;
+        match suffix {
+            "" => {},
+            "m" => denominator *= 1000,
+            _ => {
+                if let Some((_, power)) = BINARY_SUFFIXES.iter().find(|(s, _)| *s == suffix) {
+                    numerator *= 1024i128.pow(*power);
+                    format = Format::BinarySI;
+                } else if let Some((_, power)) = DECIMAL_SUFFIXES.iter().find(|(s, _)| *s == suffix)
+                {
+                    numerator *= 1000i128.pow(*power);
+                } else {
+                    // Decimal exponent, e.g. "1e3"
+                    let exponent = suffix
+                        .strip_prefix(|c: char| c == 'e' || c == 'E')
+                        .and_then(|e| e.parse::<i32>().ok())
+                        .filter(|e| e.abs() <= 18)
+                        .ok_or_else(err)?;
+                    let scale = 10i128.pow(exponent.unsigned_abs());
+                    if exponent >= 0 {
+                        numerator *= scale;
+                    } else {
+                        denominator *= scale;
+                    }
+                }
+            },
+        }
+
+        let scaled = mantissa.checked_mul(numerator).ok_or_else(err)?;
+        // Round up to milli-units, same as Kubernetes
+        let mut milli = scaled / denominator;
+        if scaled % denominator != 0 {
+            milli += 1;
+        }
+        if negative {
+            milli = -milli;
+        }
+        let milli = i64::try_from(milli).map_err(|_| err())?;
+        Ok(Self {
+            milli,
+            format,
+        })
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.milli % 1000 != 0 {
+            return write!(f, "{}m", self.milli);
+        }
+        let value = self.milli / 1000;
+        if value == 0 {
+            return write!(f, "0");
+        }
+        let (base, suffixes) = match self.format {
+            Format::BinarySI => (1024i64, &BINARY_SUFFIXES),
+            Format::DecimalSI => (1000i64, &DECIMAL_SUFFIXES),
+        };
+        // Use the largest suffix which represents the value exactly
+        let mut suffix = "";
+        let mut mantissa = value;
+        for (s, _) in suffixes.iter() {
+            if mantissa % base != 0 {
+                break;
+            }
+            mantissa /= base;
+            suffix = s;
+        }
+        write!(f, "{}{}", mantissa, suffix)
+    }
+}
+
+impl PartialEq for Quantity {
+    fn eq(&self, other: &Self) -> bool {
+        self.milli == other.milli
+    }
+}
+
+impl Eq for Quantity {}
+
+impl PartialOrd for Quantity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Quantity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.milli.cmp(&other.milli)
+    }
+}
+
+impl Hash for Quantity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.milli.hash(state);
+    }
+}
+
+impl Add for Quantity {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            milli: self.milli.saturating_add(rhs.milli),
+            format: self.format,
+        }
+    }
+}
+
+impl AddAssign for Quantity {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Quantity {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            milli: self.milli.saturating_sub(rhs.milli),
+            format: self.format,
+        }
+    }
+}
+
+impl SubAssign for Quantity {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i64> for Quantity {
+    type Output = Self;
+
+    fn mul(self, rhs: i64) -> Self {
+        Self {
+            milli: self.milli.saturating_mul(rhs),
+            format: self.format,
+        }
+    }
+}
+
+impl Sum for Quantity {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Quantity::default(), |acc, q| Self {
+            milli: acc.milli.saturating_add(q.milli),
+            format: q.format,
+        })
+    }
+}
+
+impl Serialize for Quantity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    /// Accept strings like "256Mi", and plain numbers in whole units.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(QuantityVisitor {
+            integer_unit: 1000,
+        })
+    }
+}
+
+/// Deserialize a quantity where a plain integer means milli-units,
+/// for manifests written before quantities were introduced, e.g. `cpu: 100`.
+pub fn deserialize_legacy_milli<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Quantity, D::Error> {
+    deserializer.deserialize_any(QuantityVisitor {
+        integer_unit: 1,
+    })
+}
+
+/// Deserialize a quantity where a plain integer means kibibytes,
+/// for node capacity posted before quantities were introduced.
+pub fn deserialize_legacy_kibi<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Quantity, D::Error> {
+    let quantity = deserializer.deserialize_any(QuantityVisitor {
+        integer_unit: 1024 * 1000,
+    })?;
+    Ok(Quantity {
+        format: Format::BinarySI,
+        ..quantity
+    })
+}
+
+/// A quantity, or a plain integer kept as is,
+/// for fields whose legacy unit depends on other fields, e.g. the resource of a metric.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+pub enum LegacyQuantity {
+    Integer(i64),
+    Quantity(Quantity),
+}
+
+struct QuantityVisitor {
+    /// Milli-units a plain integer stands for
+    integer_unit: i64,
+}
+
+impl<'de> Visitor<'de> for QuantityVisitor {
+    type Value = Quantity;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a quantity like \"500m\" or \"256Mi\", or a number")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Quantity, E> {
+        v.checked_mul(self.integer_unit)
+            .map(Quantity::from_milli)
+            .ok_or_else(|| E::custom(format!("Quantity {} is too large", v)))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Quantity, E> {
+        let v = i64::try_from(v).map_err(|_| E::custom(format!("Quantity {} is too large", v)))?;
+        self.visit_i64(v)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Quantity, E> {
+        let milli = (v * self.integer_unit as f64).ceil();
+        if !milli.is_finite() || milli.abs() >= i64::MAX as f64 {
+            return Err(E::custom(format!("Quantity {} is too large", v)));
+        }
+        Ok(Quantity::from_milli(milli as i64))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Quantity, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Quantity {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_suffixes() {
+        assert_eq!(parse("500m").milli_value(), 500);
+        assert_eq!(parse("2").milli_value(), 2000);
+        assert_eq!(parse("1.5").milli_value(), 1500);
+        assert_eq!(parse("+.5").milli_value(), 500);
+        assert_eq!(parse("-1.5").milli_value(), -1500);
+        assert_eq!(parse("2k").value(), 2000);
+        assert_eq!(parse("3M").value(), 3_000_000);
+        assert_eq!(parse("1Ki").value(), 1024);
+        assert_eq!(parse("100Mi").value(), 100 * 1024 * 1024);
+        assert_eq!(parse("1.5Gi").value(), 3 * 512 * 1024 * 1024);
+        assert_eq!(parse("1e3").value(), 1000);
+        assert_eq!(parse("1E-2").milli_value(), 10);
+        assert_eq!(parse("1k").format(), Format::DecimalSI);
+        assert_eq!(parse("1Ki").format(), Format::BinarySI);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in [
+            "", "m", "Ki", ".", "1x", "1.2.3", "1Kib", "1e19", "10E", "1e",
+        ] {
+            assert!(s.parse::<Quantity>().is_err(), "{:?} should be invalid", s);
+        }
+    }
+
+    #[test]
+    fn round_up_to_milli() {
+        assert_eq!(parse("0.0001").milli_value(), 1);
+        assert_eq!(parse("1.0001").milli_value(), 1001);
+        assert_eq!(parse("-0.0001").milli_value(), -1);
+        assert_eq!(parse("0.5m").milli_value(), 1);
+        // Whole units are rounded up as well
+        assert_eq!(Quantity::from_milli(1001).value(), 2);
+        assert_eq!(Quantity::from_milli(-1500).value(), -1);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(parse("500m").to_string(), "500m");
+        assert_eq!(parse("1.5").to_string(), "1500m");
+        assert_eq!(parse("0").to_string(), "0");
+        assert_eq!(parse("1000").to_string(), "1k");
+        assert_eq!(parse("1500").to_string(), "1500");
+        assert_eq!(parse("2000000").to_string(), "2M");
+        assert_eq!(parse("1024Mi").to_string(), "1Gi");
+        assert_eq!(parse("1536Mi").to_string(), "1536Mi");
+        assert_eq!(parse("-2Ki").to_string(), "-2Ki");
+        assert_eq!(Quantity::from_bytes(3 * 1024 * 1024).to_string(), "3Mi");
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let quantity = parse("256Mi");
+        let json = serde_json::to_string(&quantity).unwrap();
+        assert_eq!(json, "\"256Mi\"");
+        let back: Quantity = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, quantity);
+        assert_eq!(back.format(), Format::BinarySI);
+    }
+
+    #[test]
+    fn deserialize_numbers_as_whole_units() {
+        assert_eq!(serde_json::from_str::<Quantity>("2").unwrap(), parse("2"));
+        assert_eq!(
+            serde_json::from_str::<Quantity>("0.25").unwrap(),
+            parse("250m")
+        );
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Legacy {
+        #[serde(deserialize_with = "deserialize_legacy_milli")]
+        cpu: Quantity,
+        #[serde(deserialize_with = "deserialize_legacy_kibi")]
+        memory: Quantity,
+    }
+
+    #[test]
+    fn deserialize_legacy_integers() {
+        let legacy: Legacy = serde_json::from_str(r#"{"cpu": 100, "memory": 1024}"#).unwrap();
+        assert_eq!(legacy.cpu.milli_value(), 100);
+        assert_eq!(legacy.memory.value(), 1024 * 1024);
+        assert_eq!(legacy.memory.to_string(), "1Mi");
+    }
+
+    #[test]
+    fn deserialize_legacy_floats() {
+        let legacy: Legacy = serde_json::from_str(r#"{"cpu": 0.5, "memory": 1.5}"#).unwrap();
+        assert_eq!(legacy.cpu.milli_value(), 1);
+        assert_eq!(legacy.memory.value(), 1536);
+    }
+
+    #[test]
+    fn deserialize_legacy_strings() {
+        let legacy: Legacy = serde_json::from_str(r#"{"cpu": "1", "memory": "1Gi"}"#).unwrap();
+        assert_eq!(legacy.cpu.milli_value(), 1000);
+        assert_eq!(legacy.memory.value(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn deserialize_legacy_quantity() {
+        let integer: LegacyQuantity = serde_json::from_str("200").unwrap();
+        assert!(matches!(integer, LegacyQuantity::Integer(200)));
+        let quantity: LegacyQuantity = serde_json::from_str("\"200m\"").unwrap();
+        assert!(matches!(quantity, LegacyQuantity::Quantity(q) if q.milli_value() == 200));
+    }
+}
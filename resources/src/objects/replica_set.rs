@@ -1,8 +1,10 @@
+use indenter::indented;
 use serde::{Deserialize, Serialize};
+use strum::Display;
 
 use super::{
-    function::Function, object_reference::ObjectReference, pod::PodTemplateSpec, Labels, Metadata,
-    Object,
+    condition::Conditions, function::Function, object_reference::ObjectReference,
+    pod::PodTemplateSpec, Labels, Metadata, Object,
 };
 
 /// ReplicaSet ensures that a specified number of pod replicas are running
@@ -26,6 +28,14 @@ impl Object for ReplicaSet {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
 }
 
 impl std::fmt::Display for ReplicaSet {
@@ -41,7 +51,12 @@ impl std::fmt::Display for ReplicaSet {
             f,
             "{:<16} {} ready / {} current / {} desired",
             "Replicas:", status.ready_replicas, status.replicas, self.spec.replicas
-        )
+        )?;
+        if !status.conditions.is_empty() {
+            writeln!(f, "Conditions:")?;
+            write!(indented(f), "{}", status.conditions)?;
+        }
+        Ok(())
     }
 }
 
@@ -56,6 +71,7 @@ impl ReplicaSet {
                 kind: "function".to_string(),
                 name: func_name,
             }],
+            ..Default::default()
         };
         let spec = ReplicaSetSpec {
             selector: func.metadata.labels.clone(),
@@ -99,4 +115,16 @@ pub struct ReplicaSetStatus {
     pub replicas: u32,
     /// The number of pods targeted by this ReplicaSet with a Ready Condition.
     pub ready_replicas: u32,
+    /// The most recent generation observed by the ReplicaSet controller.
+    #[serde(default)]
+    pub observed_generation: i64,
+    /// Represents the latest available observations of a replica set's current state.
+    #[serde(default)]
+    pub conditions: Conditions<ReplicaSetConditionType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, Clone, PartialEq, Eq, Hash)]
+pub enum ReplicaSetConditionType {
+    /// Added when one of its pods fails to be created or deleted.
+    ReplicaFailure,
 }
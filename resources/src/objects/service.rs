@@ -23,6 +23,14 @@ impl Object for Service {
     fn name(&self) -> &String {
         &self.metadata.name
     }
+
+    fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -58,6 +66,7 @@ impl Service {
                 kind: "function".to_string(),
                 name: func_name.to_string(),
             }],
+            ..Default::default()
         };
         let spec = ServiceSpec {
             selector: Labels(HashMap::from([(
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// A taint is attached to a node, so that it repels pods
+/// which don't tolerate the taint.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Taint {
+    /// The taint key to be applied to a node.
+    pub key: String,
+    /// The taint value corresponding to the taint key.
+    pub value: Option<String>,
+    /// The effect of the taint on pods that do not tolerate the taint.
+    pub effect: TaintEffect,
+    /// The time at which the taint was added.
+    /// It is only written for NoExecute taints.
+    pub time_added: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Display, EnumString, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaintEffect {
+    /// Do not allow new pods to schedule onto the node unless they tolerate the taint,
+    /// but allow all pods already running on the node to continue running.
+    NoSchedule,
+    /// Like NoSchedule, but the scheduler tries not to schedule new pods onto the node,
+    /// rather than prohibiting it entirely.
+    PreferNoSchedule,
+    /// Evict any already-running pods that do not tolerate the taint.
+    NoExecute,
+}
+
+impl fmt::Display for Taint {
+    /// Format as "key=value:Effect", or "key:Effect" without value.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}={}:{}", self.key, value, self.effect),
+            None => write!(f, "{}:{}", self.key, self.effect),
+        }
+    }
+}
+
+impl std::str::FromStr for Taint {
+    type Err = anyhow::Error;
+
+    /// Parse a taint of the form "key=value:Effect" or "key:Effect".
+    ///
+    /// # Examples
+    /// ```
+    /// use resources::objects::taint::{Taint, TaintEffect};
+    ///
+    /// let taint = "dedicated=gpu:NoSchedule".parse::<Taint>().unwrap();
+    /// assert_eq!(taint.key, "dedicated");
+    /// assert_eq!(taint.value.as_deref(), Some("gpu"));
+    /// assert_eq!(taint.effect, TaintEffect::NoSchedule);
+    /// assert_eq!(taint.to_string(), "dedicated=gpu:NoSchedule");
+    ///
+    /// let taint = "master:PreferNoSchedule".parse::<Taint>().unwrap();
+    /// assert_eq!(taint.value, None);
+    /// assert!("master".parse::<Taint>().is_err());
+    /// assert!("master:Sometimes".parse::<Taint>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self> {
+        let (key_value, effect) = s
+            .rsplit_once(':')
+            .ok_or_else(|| anyhow!("Missing taint effect in {}", s))?;
+        let effect = effect
+            .parse::<TaintEffect>()
+            .with_context(|| format!("Invalid taint effect {}", effect))?;
+        let (key, value) = match key_value.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_owned())),
+            None => (key_value, None),
+        };
+        if key.is_empty() {
+            return Err(anyhow!("Missing taint key in {}", s));
+        }
+        Ok(Taint {
+            key: key.to_owned(),
+            value,
+            effect,
+            time_added: None,
+        })
+    }
+}
+
+/// The pod this toleration is attached to tolerates any taint that matches
+/// the triple <key,value,effect> using the matching operator.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Toleration {
+    /// The taint key that the toleration applies to.
+    /// Empty means match all taint keys.
+    /// If the key is empty, operator must be Exists;
+    /// this combination means to match all values and all keys.
+    pub key: Option<String>,
+    /// Represents a key's relationship to the value.
+    /// Defaults to Equal.
+    #[serde(default)]
+    pub operator: TolerationOperator,
+    /// The taint value the toleration matches to.
+    /// If the operator is Exists, the value should be empty.
+    pub value: Option<String>,
+    /// Indicates the taint effect to match.
+    /// Empty means match all taint effects.
+    pub effect: Option<TaintEffect>,
+    /// The period of time the toleration tolerates a NoExecute taint,
+    /// after which the pod is evicted. In seconds.
+    /// Empty means tolerate the taint forever.
+    pub toleration_seconds: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TolerationOperator {
+    /// The value must be equal to that of the taint
+    Equal,
+    /// Any value of the taint matches
+    Exists,
+}
+
+impl Default for TolerationOperator {
+    fn default() -> Self {
+        TolerationOperator::Equal
+    }
+}
+
+impl Toleration {
+    /// Whether the toleration tolerates the taint
+    ///
+    /// # Examples
+    /// ```
+    /// use resources::objects::taint::{Taint, TaintEffect, Toleration, TolerationOperator};
+    ///
+    /// let taint = "dedicated=gpu:NoSchedule".parse::<Taint>().unwrap();
+    /// let toleration = Toleration {
+    ///     key: Some("dedicated".to_string()),
+    ///     value: Some("gpu".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert!(toleration.tolerates(&taint));
+    ///
+    /// let toleration = Toleration {
+    ///     key: Some("dedicated".to_string()),
+    ///     effect: Some(TaintEffect::NoExecute),
+    ///     operator: TolerationOperator::Exists,
+    ///     ..Default::default()
+    /// };
+    /// assert!(!toleration.tolerates(&taint));
+    ///
+    /// let tolerate_all = Toleration {
+    ///     operator: TolerationOperator::Exists,
+    ///     ..Default::default()
+    /// };
+    /// assert!(tolerate_all.tolerates(&taint));
+    /// ```
+    pub fn tolerates(&self, taint: &Taint) -> bool {
+        if self.effect.map_or(false, |effect| effect != taint.effect) {
+            return false;
+        }
+        match self.key.as_deref() {
+            None | Some("") => return self.operator == TolerationOperator::Exists,
+            Some(key) if key != taint.key => return false,
+            _ => {},
+        }
+        match self.operator {
+            TolerationOperator::Exists => true,
+            TolerationOperator::Equal => {
+                self.value.as_deref().unwrap_or_default()
+                    == taint.value.as_deref().unwrap_or_default()
+            },
+        }
+    }
+}
+
+/// Whether any of `tolerations` tolerates the taint
+pub fn tolerated(tolerations: &[Toleration], taint: &Taint) -> bool {
+    tolerations
+        .iter()
+        .any(|toleration| toleration.tolerates(taint))
+}
@@ -0,0 +1,76 @@
+use std::{fs::File, path::PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use reqwest::{Client, StatusCode};
+
+use crate::{
+    create::{create, create_with_file},
+    objects::KubeObject,
+    patch::{patch_fields, patch_with_file},
+    utils::{client_dry_run_msg, gen_url_from_object},
+    DryRun,
+};
+
+#[derive(Args)]
+pub struct Arg {
+    /// The definition YAML file of the resource to apply
+    #[clap(short, long, parse(from_os_str), value_name = "FILE")]
+    file: PathBuf,
+    /// ZIP code file to upload, required when applying jobs and functions
+    #[clap(short, long, parse(from_os_str), value_name = "ZIP")]
+    code_file: Option<PathBuf>,
+    /// Preview the object without persisting it
+    #[clap(long, arg_enum, value_name = "STRATEGY")]
+    dry_run: Option<DryRun>,
+}
+
+impl Arg {
+    pub async fn handle(&self) -> Result<()> {
+        let path = &self.file.as_path();
+        let file =
+            File::open(path).with_context(|| format!("Failed to open file {}", path.display()))?;
+        let value: serde_json::Value = serde_yaml::from_reader(file)
+            .with_context(|| format!("Failed to parse file {}", path.display()))?;
+        let object: KubeObject = serde_json::from_value(value.clone())
+            .with_context(|| format!("Failed to parse file {}", path.display()))?;
+
+        let exists = exists(&object).await?;
+        if self.dry_run == Some(DryRun::Client) {
+            let action = if exists { "patched" } else { "created" };
+            println!("{}", client_dry_run_msg(&object, action));
+            return Ok(());
+        }
+
+        let msg = match (&object, exists) {
+            (KubeObject::GpuJob(..), false) | (KubeObject::Function(..), false) => {
+                create_with_file(&object, self.code_path()?, self.dry_run).await
+            },
+            (KubeObject::Function(..), true) => {
+                patch_with_file(&object, self.code_path()?, self.dry_run).await
+            },
+            (_, false) => create(&object, self.dry_run).await,
+            (_, true) => patch_fields(&object, &value, self.dry_run).await,
+        }
+        .with_context(|| format!("Failed to apply using file {}", path.display()))?;
+
+        println!("{}", msg);
+        Ok(())
+    }
+
+    fn code_path(&self) -> Result<PathBuf> {
+        self.code_file
+            .to_owned()
+            .ok_or_else(|| anyhow!("Code file is not provided"))
+    }
+}
+
+async fn exists(object: &KubeObject) -> Result<bool> {
+    let client = Client::new();
+    let res = client.get(gen_url_from_object(object)?).send().await?;
+    match res.status() {
+        StatusCode::NOT_FOUND => Ok(false),
+        status if status.is_success() => Ok(true),
+        status => Err(anyhow!("Failed to get current object: {}", status)),
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use reqwest::Client;
+use resources::{
+    models::Response,
+    objects::{node::Node, KubeObject},
+};
+
+use crate::{
+    patch::patch,
+    utils::{client_dry_run_msg, gen_url},
+    DryRun,
+};
+
+#[derive(Args)]
+pub struct Arg {
+    /// Name of node
+    name: String,
+    /// Preview the node without persisting it
+    #[clap(long, arg_enum, value_name = "STRATEGY")]
+    dry_run: Option<DryRun>,
+}
+
+impl Arg {
+    /// Mark the node as unschedulable or schedulable
+    pub async fn handle(&self, unschedulable: bool) -> Result<()> {
+        let msg = set_unschedulable(&self.name, unschedulable, self.dry_run).await?;
+        println!("{}", msg);
+        Ok(())
+    }
+}
+
+pub async fn get_node(name: &str) -> Result<Node> {
+    let client = Client::new();
+    let url = gen_url("nodes".to_string(), Some(&name.to_owned()))?;
+    let res = client
+        .get(url)
+        .send()
+        .await?
+        .json::<Response<KubeObject>>()
+        .await?;
+    match res.data {
+        Some(KubeObject::Node(node)) => Ok(node),
+        _ => Err(anyhow!("Node {} not found", name)),
+    }
+}
+
+pub async fn set_unschedulable(
+    name: &str,
+    unschedulable: bool,
+    dry_run: Option<DryRun>,
+) -> Result<String> {
+    let mut node = get_node(name).await?;
+    let action = if unschedulable {
+        "cordoned"
+    } else {
+        "uncordoned"
+    };
+    if node.spec.unschedulable == unschedulable {
+        return Ok(format!("node/{} already {}", name, action));
+    }
+    node.spec.unschedulable = unschedulable;
+
+    let object = KubeObject::Node(node);
+    if dry_run == Some(DryRun::Client) {
+        return Ok(client_dry_run_msg(&object, action));
+    }
+    patch(&object, dry_run).await
+}
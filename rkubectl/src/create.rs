@@ -9,7 +9,11 @@ use reqwest::{
 use resources::objects::Object;
 use serde::Deserialize;
 
-use crate::{objects::KubeObject, utils::gen_url};
+use crate::{
+    objects::KubeObject,
+    utils::{client_dry_run_msg, gen_url, with_dry_run},
+    DryRun,
+};
 
 #[derive(Args)]
 pub struct Arg {
@@ -19,6 +23,9 @@ pub struct Arg {
     /// ZIP code file to upload, required when creating jobs and functions
     #[clap(short, long, parse(from_os_str), value_name = "ZIP")]
     code_file: Option<PathBuf>,
+    /// Preview the object without persisting it
+    #[clap(long, arg_enum, value_name = "STRATEGY")]
+    dry_run: Option<DryRun>,
 }
 
 impl Arg {
@@ -28,17 +35,21 @@ impl Arg {
             File::open(path).with_context(|| format!("Failed to open file {}", path.display()))?;
         let object: KubeObject = serde_yaml::from_reader(file)
             .with_context(|| format!("Failed to parse file {}", path.display()))?;
+        if self.dry_run == Some(DryRun::Client) {
+            println!("{}", client_dry_run_msg(&object, "created"));
+            return Ok(());
+        }
         let msg: String = match object {
             KubeObject::GpuJob(..) | KubeObject::Function(..) => {
                 let code_path = self
                     .code_file
                     .to_owned()
                     .ok_or_else(|| anyhow!("Code file is not provided"))?;
-                create_with_file(&object, code_path)
+                create_with_file(&object, code_path, self.dry_run)
                     .await
                     .with_context(|| format!("Failed to create using file {}", path.display()))?
             },
-            _ => create(&object)
+            _ => create(&object, self.dry_run)
                 .await
                 .with_context(|| format!("Failed to create using file {}", path.display()))?,
         };
@@ -48,9 +59,9 @@ impl Arg {
     }
 }
 
-async fn create(object: &KubeObject) -> Result<String> {
+pub async fn create(object: &KubeObject, dry_run: Option<DryRun>) -> Result<String> {
     let client = Client::new();
-    let url = gen_url(object.kind_plural(), None)?;
+    let url = with_dry_run(gen_url(object.kind_plural(), None)?, dry_run);
     let res = client
         .post(url)
         .json(&object)
@@ -64,9 +75,13 @@ async fn create(object: &KubeObject) -> Result<String> {
     }
 }
 
-async fn create_with_file(object: &KubeObject, path: PathBuf) -> Result<String> {
+pub async fn create_with_file(
+    object: &KubeObject,
+    path: PathBuf,
+    dry_run: Option<DryRun>,
+) -> Result<String> {
     let client = Client::builder().pool_idle_timeout(None).build()?;
-    let url = gen_url(object.kind_plural(), None)?;
+    let url = with_dry_run(gen_url(object.kind_plural(), None)?, dry_run);
 
     // Load file as a part
     let bytes = std::fs::read(&path)?;
@@ -3,7 +3,10 @@ use clap::Args;
 use reqwest::Client;
 use serde::Deserialize;
 
-use crate::{utils::gen_url, ResourceKind};
+use crate::{
+    utils::{gen_url, with_dry_run},
+    DryRun, ResourceKind,
+};
 
 #[derive(Args)]
 pub struct Arg {
@@ -12,12 +15,22 @@ pub struct Arg {
     kind: ResourceKind,
     /// Name of resource
     name: String,
+    /// Preview the deletion without performing it
+    #[clap(long, arg_enum, value_name = "STRATEGY")]
+    dry_run: Option<DryRun>,
 }
 
 impl Arg {
     pub async fn handle(&self) -> Result<()> {
+        if self.dry_run == Some(DryRun::Client) {
+            println!("{}/{} deleted (dry run)", self.kind, self.name);
+            return Ok(());
+        }
         let client = Client::new();
-        let url = gen_url(self.kind.to_string(), Some(&self.name))?;
+        let url = with_dry_run(
+            gen_url(self.kind.to_string(), Some(&self.name))?,
+            self.dry_run,
+        );
         let res = client.delete(url).send().await?.json::<DeleteRes>().await?;
         println!("{}", res.msg);
         Ok(())
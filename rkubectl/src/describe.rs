@@ -49,6 +49,18 @@ impl Arg {
                 KubeObject::Node(node) => {
                     println!("{}", node);
                 },
+                KubeObject::HorizontalPodAutoscaler(hpa) => {
+                    println!("{}", hpa);
+                },
+                KubeObject::GpuJob(job) => {
+                    println!("{}", job);
+                },
+                KubeObject::Function(func) => {
+                    println!("{}", func);
+                },
+                KubeObject::PodGroup(group) => {
+                    println!("{}", group);
+                },
                 _ => {
                     println!("{:#?}", object);
                 },
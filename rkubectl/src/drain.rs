@@ -0,0 +1,164 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use reqwest::Client;
+use resources::{
+    models::Response,
+    objects::{pod::Pod, KubeObject, Object},
+};
+use serde::Deserialize;
+use tokio::time::{self, Instant};
+
+use crate::{cordon::set_unschedulable, utils::gen_url};
+
+#[derive(Args)]
+pub struct Arg {
+    /// Name of node
+    name: String,
+    /// Also evict pods without a controller, which are skipped by default and won't be recreated
+    #[clap(long)]
+    force: bool,
+    /// Seconds to wait for evicted pods to be replaced on other nodes, 0 to not wait
+    #[clap(long, default_value_t = 300, value_name = "SECONDS")]
+    timeout: u64,
+}
+
+impl Arg {
+    /// Cordon the node, evict its pods and wait for their ReplicaSets to recover
+    pub async fn handle(&self) -> Result<()> {
+        println!("{}", set_unschedulable(&self.name, true, None).await?);
+
+        let client = Client::new();
+        let pods = list_pods(&client)
+            .await?
+            .into_iter()
+            .filter(|pod| pod.spec.node_name.as_ref() == Some(&self.name) && pod.is_active())
+            .collect::<Vec<_>>();
+        let (unmanaged, pods): (Vec<_>, Vec<_>) = pods
+            .into_iter()
+            .partition(|pod| pod.metadata.owner_references.is_empty() && !self.force);
+        if !unmanaged.is_empty() {
+            eprintln!(
+                "Warning: skipping pods not managed by a controller (use --force to evict them): {}",
+                unmanaged
+                    .iter()
+                    .map(|pod| pod.name().to_owned())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        // Pods are stopped gracefully by rKubelet once deleted
+        let mut replica_sets = Vec::<String>::new();
+        for pod in pods.iter() {
+            let url = gen_url("pods".to_string(), Some(pod.name()))?;
+            let res = client.delete(url).send().await?.json::<DeleteRes>().await?;
+            if let Some(cause) = res.cause {
+                return Err(anyhow!("Failed to evict pod {}: {}", pod.name(), cause));
+            }
+            println!("pod/{} evicted", pod.name());
+            for owner in pod.metadata.owner_references.iter() {
+                if owner.kind == "ReplicaSet" && !replica_sets.contains(&owner.name) {
+                    replica_sets.push(owner.name.to_owned());
+                }
+            }
+        }
+
+        if self.timeout > 0 && !replica_sets.is_empty() {
+            self.wait_for_replacements(&client, &replica_sets).await?;
+        }
+        println!("node/{} drained", self.name);
+        Ok(())
+    }
+
+    /// Wait until every ReplicaSet has all replicas ready on other nodes
+    async fn wait_for_replacements(&self, client: &Client, replica_sets: &[String]) -> Result<()> {
+        let deadline = Instant::now() + Duration::from_secs(self.timeout);
+        let mut last_progress = String::new();
+        loop {
+            let pods = list_pods(client).await?;
+            let mut pending = BTreeMap::new();
+            for name in replica_sets {
+                let desired = match get_replica_set_replicas(client, name).await? {
+                    Some(desired) => desired,
+                    // Deleted meanwhile, nothing to replace
+                    None => continue,
+                };
+                let ready = pods
+                    .iter()
+                    .filter(|pod| {
+                        pod.spec.node_name.as_ref() != Some(&self.name)
+                            && pod.is_ready()
+                            && pod
+                                .metadata
+                                .owner_references
+                                .iter()
+                                .any(|owner| owner.kind == "ReplicaSet" && &owner.name == name)
+                    })
+                    .count() as u32;
+                if ready < desired {
+                    pending.insert(name, (ready, desired));
+                }
+            }
+            if pending.is_empty() {
+                return Ok(());
+            }
+
+            let progress = pending
+                .iter()
+                .map(|(name, (ready, desired))| {
+                    format!("replicaset/{} {}/{}", name, ready, desired)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            if Instant::now() >= deadline {
+                return Err(anyhow!("Timed out waiting for replacements: {}", progress));
+            }
+            if progress != last_progress {
+                println!("Waiting for replacements: {}", progress);
+                last_progress = progress;
+            }
+            time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+}
+
+async fn list_pods(client: &Client) -> Result<Vec<Pod>> {
+    let url = gen_url("pods".to_string(), None)?;
+    let res = client
+        .get(url)
+        .send()
+        .await?
+        .json::<Response<Vec<KubeObject>>>()
+        .await?;
+    Ok(res
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|object| match object {
+            KubeObject::Pod(pod) => Some(pod),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Desired replicas of the ReplicaSet, `None` if it doesn't exist
+async fn get_replica_set_replicas(client: &Client, name: &str) -> Result<Option<u32>> {
+    let url = gen_url("replicasets".to_string(), Some(&name.to_owned()))?;
+    let res = client
+        .get(url)
+        .send()
+        .await?
+        .json::<Response<KubeObject>>()
+        .await?;
+    match res.data {
+        Some(KubeObject::ReplicaSet(rs)) => Ok(Some(rs.spec.replicas)),
+        _ => Ok(None),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeleteRes {
+    cause: Option<String>,
+}
@@ -6,16 +6,17 @@ use reqwest::Client;
 use resources::{
     models::Response,
     objects::{
-        node::NodeAddressType,
+        condition::ConditionStatus,
+        node::{NodeAddressType, NodeConditionType},
         KubeObject::{
-            self, Function, GpuJob, HorizontalPodAutoscaler, Ingress, Node, Pod, ReplicaSet,
-            Service, Workflow,
+            self, Function, GpuJob, HorizontalPodAutoscaler, Ingress, Node, Pod, PodGroup,
+            PriorityClass, ReplicaSet, Service, Workflow,
         },
     },
 };
 
 use crate::{
-    utils::{calc_age, gen_url},
+    utils::{calc_age, calc_object_age, gen_url},
     ResourceKind,
 };
 
@@ -64,38 +65,42 @@ impl Arg {
                             .iter()
                             .map(|c| c.restart_count)
                             .sum::<u32>();
+                        // Fall back to start time for pods created by older API servers
+                        let created = pod.metadata.creation_timestamp.unwrap_or(status.start_time);
                         println!(
                             "{:<20} {:<10} {:<8} {:<10}",
                             pod.metadata.name,
                             status.phase,
                             restarts,
-                            calc_age(status.start_time)
+                            calc_age(created)
                         );
                     }
                 }
             },
             ResourceKind::ReplicaSets => {
                 println!(
-                    "{:<20} {:<8} {:<8} {:<8}",
-                    "NAME", "DESIRED", "CURRENT", "READY"
+                    "{:<20} {:<8} {:<8} {:<8} {:<}",
+                    "NAME", "DESIRED", "CURRENT", "READY", "AGE"
                 );
                 for object in data {
                     if let ReplicaSet(rs) = object {
+                        let age = calc_object_age(&rs);
                         let status = rs.status.unwrap_or_default();
                         println!(
-                            "{:<20} {:<8} {:<8} {:<8}",
+                            "{:<20} {:<8} {:<8} {:<8} {:<}",
                             rs.metadata.name,
                             rs.spec.replicas,
                             status.replicas,
                             status.ready_replicas,
+                            age
                         );
                     }
                 }
             },
             ResourceKind::Services => {
                 println!(
-                    "{:<20} {:<16} {:<20} {:<}",
-                    "NAME", "CLUSTER-IP", "PORTS", "ENDPOINTS"
+                    "{:<20} {:<16} {:<20} {:<16} {:<}",
+                    "NAME", "CLUSTER-IP", "PORTS", "AGE", "ENDPOINTS"
                 );
                 for object in data {
                     if let Service(svc) = object {
@@ -120,21 +125,26 @@ impl Arg {
                             .collect::<Vec<_>>()
                             .join(",");
                         println!(
-                            "{: <20} {: <16} {: <20} {:<}",
+                            "{: <20} {: <16} {: <20} {: <16} {:<}",
                             svc.metadata.name,
                             svc.spec.cluster_ip.ok_or_else(|| anyhow!(
                                 "Service should always have a cluster IP"
                             ))?,
                             ports,
+                            calc_object_age(&svc),
                             eps
                         );
                     }
                 }
             },
             ResourceKind::Ingresses => {
-                println!("{:<20} {:<30} PATH:SERVICE:PORT", "NAME", "HOST");
+                println!(
+                    "{:<20} {:<30} {:<16} PATH:SERVICE:PORT",
+                    "NAME", "HOST", "AGE"
+                );
                 for object in data {
                     if let Ingress(ingress) = object {
+                        let age = calc_object_age(&ingress);
                         let name = ingress.metadata.name;
                         for rule in ingress.spec.rules {
                             let paths = rule
@@ -149,18 +159,25 @@ impl Arg {
                                 .collect::<Vec<_>>()
                                 .join(",");
 
-                            println!("{:<20} {:<30} {}", name, rule.host.unwrap(), paths);
+                            println!(
+                                "{:<20} {:<30} {:<16} {}",
+                                name,
+                                rule.host.unwrap(),
+                                age,
+                                paths
+                            );
                         }
                     }
                 }
             },
             ResourceKind::HorizontalPodAutoscalers => {
                 println!(
-                    "{:<16} {:<24} {:<8} {:<8} {:<}",
-                    "NAME", "REFERENCE", "CURRENT", "DESIRED", "LAST-SCALE"
+                    "{:<16} {:<24} {:<8} {:<8} {:<16} {:<}",
+                    "NAME", "REFERENCE", "CURRENT", "DESIRED", "LAST-SCALE", "AGE"
                 );
                 for object in data {
                     if let HorizontalPodAutoscaler(hpa) = object {
+                        let age = calc_object_age(&hpa);
                         let status = hpa
                             .status
                             .with_context(|| anyhow!("HorizontalPodAutoscaler has no status"))?;
@@ -171,46 +188,61 @@ impl Arg {
                         let scale_target = hpa.spec.scale_target_ref;
                         let reference = format!("{}/{}", scale_target.kind, scale_target.name);
                         println!(
-                            "{:<16} {:<24} {:<8} {:<8} {:<}",
+                            "{:<16} {:<24} {:<8} {:<8} {:<16} {:<}",
                             hpa.metadata.name,
                             reference,
                             status.current_replicas,
                             status.desired_replicas,
-                            last_scale
+                            last_scale,
+                            age
                         );
                     }
                 }
             },
             ResourceKind::GpuJobs => {
                 println!(
-                    "{:<20} {:<10} {:<8} {:<8} {:<8}",
-                    "NAME", "DESIRED", "ACTIVE", "FAILED", "SUCCEEDED"
+                    "{:<20} {:<10} {:<8} {:<8} {:<10} {:<}",
+                    "NAME", "DESIRED", "ACTIVE", "FAILED", "SUCCEEDED", "AGE"
                 );
                 for object in data {
                     if let GpuJob(job) = object {
+                        let age = calc_object_age(&job);
                         let status = job.status.unwrap_or_default();
                         println!(
-                            "{:<20} {:<10} {:<8} {:<8} {:<8}",
+                            "{:<20} {:<10} {:<8} {:<8} {:<10} {:<}",
                             job.metadata.name,
                             job.spec.completions,
                             status.active,
                             status.failed,
-                            status.succeeded
+                            status.succeeded,
+                            age
                         );
                     }
                 }
             },
             ResourceKind::Nodes => {
                 println!(
-                    "{:<16} {:<16} {:<}",
-                    "NAME", "LAST HEARTBEAT", "INTERNAL-IP"
+                    "{:<16} {:<10} {:<16} {:<16} {:<}",
+                    "NAME", "STATUS", "LAST HEARTBEAT", "AGE", "INTERNAL-IP"
                 );
                 for object in data {
                     if let Node(node) = object {
+                        let status = match node
+                            .status
+                            .conditions
+                            .get(&NodeConditionType::Ready)
+                            .map(|condition| condition.status)
+                        {
+                            Some(ConditionStatus::True) => "Ready",
+                            Some(ConditionStatus::False) => "NotReady",
+                            Some(ConditionStatus::Unknown) | None => "Unknown",
+                        };
                         println!(
-                            "{:<16} {:<16} {:<}",
+                            "{:<16} {:<10} {:<16} {:<16} {:<}",
                             node.metadata.name,
+                            status,
                             calc_age(node.status.last_heartbeat),
+                            calc_object_age(&node),
                             node.status
                                 .addresses
                                 .get(&NodeAddressType::InternalIP)
@@ -221,11 +253,12 @@ impl Arg {
             },
             ResourceKind::Functions => {
                 println!(
-                    "{:<16} {:<10} {:<8} {:<8} {:<16} HOST",
-                    "NAME", "STATUS", "CURRENT", "DESIRED", "LAST-SCALE"
+                    "{:<16} {:<10} {:<8} {:<8} {:<16} {:<16} HOST",
+                    "NAME", "STATUS", "CURRENT", "DESIRED", "LAST-SCALE", "AGE"
                 );
                 for object in data {
                     if let Function(func) = object {
+                        let age = calc_object_age(&func);
                         let status = func.status.expect("Function has no status");
 
                         // Query HPA
@@ -256,7 +289,7 @@ impl Arg {
                         };
 
                         println!(
-                            "{:<16} {:<10} {:<8} {:<8} {:<16} {}",
+                            "{:<16} {:<10} {:<8} {:<8} {:<16} {:<16} {}",
                             func.metadata.name,
                             state,
                             hpa_status.current_replicas,
@@ -265,18 +298,57 @@ impl Arg {
                                 .last_scale_time
                                 .map(calc_age)
                                 .unwrap_or_else(|| "Never".to_string()),
+                            age,
                             status.host
                         );
                     }
                 }
             },
             ResourceKind::Workflows => {
-                println!("{:<10} HOST", "Name");
+                println!("{:<10} {:<16} HOST", "Name", "AGE");
                 for object in data {
                     if let Workflow(workflow) = object {
                         println!(
-                            "{:<10} {}.workflow.func.minik8s.com",
-                            workflow.metadata.name, workflow.metadata.name,
+                            "{:<10} {:<16} {}.workflow.func.minik8s.com",
+                            workflow.metadata.name,
+                            calc_object_age(&workflow),
+                            workflow.metadata.name,
+                        );
+                    }
+                }
+            },
+            ResourceKind::PriorityClasses => {
+                println!(
+                    "{:<24} {:<12} {:<16} {:<}",
+                    "NAME", "VALUE", "GLOBAL-DEFAULT", "AGE"
+                );
+                for object in data {
+                    if let PriorityClass(class) = object {
+                        println!(
+                            "{:<24} {:<12} {:<16} {:<}",
+                            class.metadata.name,
+                            class.value,
+                            class.global_default,
+                            calc_object_age(&class)
+                        );
+                    }
+                }
+            },
+            ResourceKind::PodGroups => {
+                println!(
+                    "{:<20} {:<12} {:<10} {:<12} {:<}",
+                    "NAME", "MIN-MEMBER", "SCHEDULED", "PHASE", "AGE"
+                );
+                for object in data {
+                    if let PodGroup(group) = object {
+                        let status = group.status.to_owned().unwrap_or_default();
+                        println!(
+                            "{:<20} {:<12} {:<10} {:<12} {:<}",
+                            group.metadata.name,
+                            group.spec.min_member,
+                            status.scheduled,
+                            status.phase.to_string(),
+                            calc_object_age(&group)
                         );
                     }
                 }
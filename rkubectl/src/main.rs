@@ -9,14 +9,18 @@ use reqwest::Url;
 use resources::objects;
 use strum::Display;
 
+mod apply;
 mod completion;
+mod cordon;
 mod create;
 mod delete;
 mod describe;
+mod drain;
 mod exec;
 mod get;
 mod logs;
 mod patch;
+mod taint;
 mod utils;
 
 struct AppConfig {
@@ -44,6 +48,8 @@ struct Cli {
 enum Commands {
     /// Create a resource using configuration file.
     Create(create::Arg),
+    /// Create a resource or patch it if it already exists.
+    Apply(apply::Arg),
     /// Delete a resource by name.
     Delete(delete::Arg),
     /// Get resources list.
@@ -52,6 +58,14 @@ enum Commands {
     Patch(patch::Arg),
     /// Describe a resource.
     Describe(describe::Arg),
+    /// Add or remove taints of a node.
+    Taint(taint::Arg),
+    /// Mark a node as unschedulable.
+    Cordon(cordon::Arg),
+    /// Mark a node as schedulable.
+    Uncordon(cordon::Arg),
+    /// Cordon a node and evict its pods in preparation for maintenance.
+    Drain(drain::Arg),
     /// Print pod container logs.
     Logs(logs::Arg),
     /// Execute commands in a pod container.
@@ -74,6 +88,16 @@ enum ResourceKind {
     Nodes,
     Functions,
     Workflows,
+    PriorityClasses,
+    PodGroups,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ArgEnum)]
+enum DryRun {
+    /// Send the request with `dryRun=All`, nothing will be persisted by the server
+    Server,
+    /// Only validate the resource locally without sending it
+    Client,
 }
 
 #[tokio::main]
@@ -82,10 +106,15 @@ async fn main() -> Result<()> {
 
     match &cli.command {
         Commands::Create(arg) => arg.handle().await?,
+        Commands::Apply(arg) => arg.handle().await?,
         Commands::Delete(arg) => arg.handle().await?,
         Commands::Get(arg) => arg.handle().await?,
         Commands::Patch(arg) => arg.handle().await?,
         Commands::Describe(arg) => arg.handle().await?,
+        Commands::Taint(arg) => arg.handle().await?,
+        Commands::Cordon(arg) => arg.handle(true).await?,
+        Commands::Uncordon(arg) => arg.handle(false).await?,
+        Commands::Drain(arg) => arg.handle().await?,
         Commands::Logs(arg) => arg.handle().await?,
         Commands::Exec(arg) => arg.handle().await?,
         Commands::Completion(arg) => arg.handle(&mut Cli::command()).await?,
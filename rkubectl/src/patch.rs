@@ -11,7 +11,8 @@ use serde::Deserialize;
 
 use crate::{
     objects::KubeObject,
-    utils::{gen_url, gen_url_from_object},
+    utils::{client_dry_run_msg, gen_url, gen_url_from_object, with_dry_run},
+    DryRun,
 };
 
 #[derive(Args)]
@@ -20,6 +21,9 @@ pub struct Arg {
     file: PathBuf,
     #[clap(short, long, parse(from_os_str), value_name = "ZIP")]
     code_file: Option<PathBuf>,
+    /// Preview the object without persisting it
+    #[clap(long, arg_enum, value_name = "STRATEGY")]
+    dry_run: Option<DryRun>,
 }
 
 impl Arg {
@@ -27,8 +31,14 @@ impl Arg {
         let path = &self.file.as_path();
         let file =
             File::open(path).with_context(|| format!("Failed to open file {}", path.display()))?;
-        let object: KubeObject = serde_yaml::from_reader(file)
+        let value: serde_json::Value = serde_yaml::from_reader(file)
             .with_context(|| format!("Failed to parse file {}", path.display()))?;
+        let object: KubeObject = serde_json::from_value(value.clone())
+            .with_context(|| format!("Failed to parse file {}", path.display()))?;
+        if self.dry_run == Some(DryRun::Client) {
+            println!("{}", client_dry_run_msg(&object, "patched"));
+            return Ok(());
+        }
 
         let msg: String = match object {
             KubeObject::Function(..) => {
@@ -36,11 +46,11 @@ impl Arg {
                     .code_file
                     .to_owned()
                     .ok_or_else(|| anyhow!("Code file is not provided"))?;
-                patch_with_file(&object, code_path)
+                patch_with_file(&object, code_path, self.dry_run)
                     .await
                     .with_context(|| format!("Failed to update using file {}", path.display()))?
             },
-            _ => patch(&object)
+            _ => patch_fields(&object, &value, self.dry_run)
                 .await
                 .with_context(|| format!("Failed to patch using file {}", path.display()))?,
         };
@@ -50,12 +60,22 @@ impl Arg {
     }
 }
 
-async fn patch(object: &KubeObject) -> Result<String> {
+pub async fn patch(object: &KubeObject, dry_run: Option<DryRun>) -> Result<String> {
+    patch_fields(object, &serde_json::to_value(object)?, dry_run).await
+}
+
+/// Patch an object with only the fields given in `fields`,
+/// so that the server leaves the omitted ones untouched.
+pub async fn patch_fields(
+    object: &KubeObject,
+    fields: &serde_json::Value,
+    dry_run: Option<DryRun>,
+) -> Result<String> {
     let client = Client::new();
-    let url = gen_url_from_object(object)?;
+    let url = with_dry_run(gen_url_from_object(object)?, dry_run);
     let res = client
         .patch(url)
-        .json(&object)
+        .json(fields)
         .send()
         .await?
         .json::<PatchRes>()
@@ -63,8 +83,29 @@ async fn patch(object: &KubeObject) -> Result<String> {
     Ok(res.msg)
 }
 
-async fn patch_with_file(object: &KubeObject, path: PathBuf) -> Result<String> {
+pub async fn patch_with_file(
+    object: &KubeObject,
+    path: PathBuf,
+    dry_run: Option<DryRun>,
+) -> Result<String> {
     let client = Client::builder().pool_idle_timeout(None).build()?;
+    if dry_run == Some(DryRun::Server) {
+        // Nothing is deleted in a dry run, so recreating the object would conflict with itself
+        let res = client
+            .put(with_dry_run(
+                gen_url(object.kind_plural(), Some(object.name()))?,
+                dry_run,
+            ))
+            .json(&object)
+            .send()
+            .await?
+            .json::<PatchRes>()
+            .await?;
+        return match res.cause {
+            Some(cause) => Err(anyhow::anyhow!("{}: {}", res.msg, cause)),
+            None => Ok(res.msg),
+        };
+    }
     let url = gen_url(object.kind_plural(), None)?;
 
     let res = client
@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use resources::objects::{
+    taint::{Taint, TaintEffect},
+    KubeObject,
+};
+
+use crate::{cordon::get_node, patch::patch, utils::client_dry_run_msg, DryRun};
+
+#[derive(Args)]
+pub struct Arg {
+    /// Name of node
+    name: String,
+    /// Taints to add as KEY[=VALUE]:EFFECT, or to remove as KEY[:EFFECT]-
+    #[clap(required = true)]
+    taints: Vec<String>,
+    /// Preview the node without persisting it
+    #[clap(long, arg_enum, value_name = "STRATEGY")]
+    dry_run: Option<DryRun>,
+}
+
+impl Arg {
+    pub async fn handle(&self) -> Result<()> {
+        let mut node = get_node(&self.name).await?;
+
+        for taint in self.taints.iter() {
+            let taints = &mut node.spec.taints;
+            match taint.strip_suffix('-') {
+                Some(removal) => {
+                    let (key, effect) = match removal.split_once(':') {
+                        Some((key, effect)) => (
+                            key,
+                            Some(
+                                effect
+                                    .parse::<TaintEffect>()
+                                    .with_context(|| format!("Invalid taint effect {}", effect))?,
+                            ),
+                        ),
+                        None => (removal, None),
+                    };
+                    let len = taints.len();
+                    taints.retain(|taint| {
+                        taint.key != key || effect.map_or(false, |effect| taint.effect != effect)
+                    });
+                    if taints.len() == len {
+                        return Err(anyhow!("Taint {} not found", removal));
+                    }
+                },
+                None => {
+                    let taint = taint.parse::<Taint>()?;
+                    // A taint with the same key and effect is overwritten
+                    taints.retain(|old| old.key != taint.key || old.effect != taint.effect);
+                    taints.push(taint);
+                },
+            }
+        }
+
+        let object = KubeObject::Node(node);
+        if self.dry_run == Some(DryRun::Client) {
+            println!("{}", client_dry_run_msg(&object, "patched"));
+            return Ok(());
+        }
+        println!("{}", patch(&object, self.dry_run).await?);
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use chrono::{Local, NaiveDateTime};
 use chrono_humanize::{Accuracy, HumanTime, Tense};
 use resources::objects::Object;
 
-use crate::{objects::KubeObject, Url, CONFIG};
+use crate::{objects::KubeObject, DryRun, Url, CONFIG};
 
 pub fn gen_url_from_object(object: &KubeObject) -> Result<Url> {
     let url = CONFIG.base_url.to_owned();
@@ -22,7 +22,32 @@ pub fn gen_url(mut kind_plural: String, name: Option<&String>) -> Result<Url> {
     Ok(url.join(path.as_str())?)
 }
 
+/// Ask the server to process the request without persisting anything
+pub fn with_dry_run(mut url: Url, dry_run: Option<DryRun>) -> Url {
+    if dry_run == Some(DryRun::Server) {
+        url.query_pairs_mut().append_pair("dryRun", "All");
+    }
+    url
+}
+
+pub fn client_dry_run_msg(object: &KubeObject, action: &str) -> String {
+    format!(
+        "{}/{} {} (dry run)",
+        object.kind().to_lowercase(),
+        object.name(),
+        action
+    )
+}
+
 pub fn calc_age(time: NaiveDateTime) -> String {
     let d = HumanTime::from(Local::now().naive_utc() - time);
     d.to_text_en(Accuracy::Rough, Tense::Present)
 }
+
+/// Age of an object since it's created, unknown if created by an older API server
+pub fn calc_object_age<T: Object>(object: &T) -> String {
+    object
+        .metadata()
+        .creation_timestamp
+        .map_or_else(|| "<unknown>".to_string(), calc_age)
+}
@@ -1,8 +1,11 @@
 use std::{cmp::Eq, collections::HashMap, default::Default, hash::Hash, pin::Pin};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use bollard::{
-    container::{Config, CreateContainerOptions, LogsOptions, StartContainerOptions},
+    container::{
+        Config, CreateContainerOptions, InspectContainerOptions, LogsOptions,
+        StartContainerOptions, Stats, StatsOptions, StopContainerOptions,
+    },
     errors::Error::DockerResponseServerError,
     exec::{CreateExecOptions, StartExecResults},
     image::{CreateImageOptions, ListImagesOptions},
@@ -155,9 +158,16 @@ impl Container {
         }
     }
 
-    pub async fn stop(&self) -> Result<()> {
+    /// Stop a container, kill it if it's still running after `timeout` seconds,
+    /// which defaults to 10 seconds
+    pub async fn stop(&self, timeout: Option<i64>) -> Result<()> {
         DOCKER
-            .stop_container(self.id.as_str(), None)
+            .stop_container(
+                self.id.as_str(),
+                timeout.map(|t| StopContainerOptions {
+                    t,
+                }),
+            )
             .await
             .with_context(|| format!("Failed to stop container {}", self.id))
     }
@@ -181,6 +191,36 @@ impl Container {
         }
     }
 
+    /// Current resource usage of a running container
+    pub async fn stats(&self) -> Result<Stats> {
+        let mut stream = DOCKER.stats(
+            self.id.as_str(),
+            Some(StatsOptions {
+                stream: false,
+                one_shot: true,
+            }),
+        );
+        stream
+            .next()
+            .await
+            .ok_or_else(|| anyhow!("No stats of container {}", self.id))?
+            .with_context(|| format!("Failed to get stats of container {}", self.id))
+    }
+
+    /// Size of files created or changed by a container in bytes
+    pub async fn writable_layer_size(&self) -> Result<i64> {
+        let response = DOCKER
+            .inspect_container(
+                self.id.as_str(),
+                Some(InspectContainerOptions {
+                    size: true,
+                }),
+            )
+            .await
+            .with_context(|| format!("Failed to inspect container {}", self.id))?;
+        Ok(response.size_rw.unwrap_or_default())
+    }
+
     pub async fn logs(&self, tail: &Option<String>) -> Result<String> {
         let tail = tail.to_owned().unwrap_or_else(|| "all".to_string());
         let mut stream = DOCKER.logs(
@@ -273,8 +313,11 @@ pub async fn start_containers(containers: &[Container]) -> Vec<Result<()>> {
 }
 
 /// Stop docker containers concurrently
-pub async fn stop_containers(containers: &[Container]) -> Vec<Result<()>> {
-    let tasks = containers.iter().map(|c| c.stop()).collect::<Vec<_>>();
+pub async fn stop_containers(containers: &[Container], timeout: Option<i64>) -> Vec<Result<()>> {
+    let tasks = containers
+        .iter()
+        .map(|c| c.stop(timeout))
+        .collect::<Vec<_>>();
     let results = join_all(tasks).await;
     results.iter().for_each(|r| {
         if let Err(e) = r {
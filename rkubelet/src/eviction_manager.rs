@@ -0,0 +1,170 @@
+use std::{cmp::Reverse, time::Duration};
+
+use anyhow::{anyhow, Result};
+use resources::{
+    client::Api,
+    informer::Store,
+    objects::{node::NodeConditionType, pod, Object},
+};
+use tokio::time::{sleep, Instant};
+
+use crate::{config::CONFIG, docker::Container, stats::NodeStats};
+
+/// Evict pods when the node is short of memory, disk or process IDs,
+/// so that the rest of pods keep running.
+///
+/// One pod is evicted at a time,
+/// the one of the lowest priority, using the most of the starved resource among them.
+/// The next one is evicted only after it has terminated, since the resource isn't released before.
+pub struct EvictionManager {
+    node_name: String,
+    pod_store: Store<pod::Pod>,
+    pod_api: Api<pod::Pod>,
+    evicting: Option<Eviction>,
+}
+
+/// A pod evicted but maybe not terminated yet
+struct Eviction {
+    pod_name: String,
+    containers: Vec<Container>,
+    /// Stop waiting for it after this, e.g. if its containers can't be removed
+    deadline: Instant,
+}
+
+/// Time allowed for removing containers beyond the termination grace period
+const EVICTION_TIMEOUT_MARGIN: Duration = Duration::from_secs(30);
+
+impl EvictionManager {
+    pub fn new(node_name: String, pod_store: Store<pod::Pod>, pod_api: Api<pod::Pod>) -> Self {
+        Self {
+            node_name,
+            pod_store,
+            pod_api,
+            evicting: None,
+        }
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        tracing::info!("Eviction manager started");
+        loop {
+            sleep(Duration::from_secs(CONFIG.eviction_monitoring_period)).await;
+            let pressures = NodeStats::collect().pressures(&CONFIG.eviction_hard);
+            if let Some(pressure) = pressures.first() {
+                tracing::warn!("Hard eviction threshold crossed: {}", pressure);
+                if self.is_terminating().await {
+                    continue;
+                }
+                match self.evict_pod(*pressure).await {
+                    Ok(eviction) => self.evicting = Some(eviction),
+                    Err(err) => {
+                        tracing::error!("Failed to evict pod for {}: {:#}", pressure, err)
+                    },
+                }
+            }
+        }
+    }
+
+    /// Whether the pod evicted last is still terminating
+    async fn is_terminating(&mut self) -> bool {
+        let eviction = match self.evicting {
+            Some(ref eviction) => eviction,
+            None => return false,
+        };
+        if Instant::now() >= eviction.deadline {
+            tracing::warn!(
+                "Timed out waiting for evicted pod {} to terminate",
+                eviction.pod_name
+            );
+        } else {
+            for container in eviction.containers.iter() {
+                // Containers are removed once stopped
+                if !matches!(container.inspect().await, Ok(None)) {
+                    tracing::info!("Waiting for evicted pod {} to terminate", eviction.pod_name);
+                    return true;
+                }
+            }
+        }
+        self.evicting = None;
+        false
+    }
+
+    async fn evict_pod(&self, pressure: NodeConditionType) -> Result<Eviction> {
+        let pods = self
+            .pod_store
+            .read()
+            .await
+            .values()
+            .filter(|pod| pod.is_on_node(&self.node_name) && pod.is_active())
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut candidates = Vec::with_capacity(pods.len());
+        for pod in pods {
+            let usage = pod_usage(&pod, pressure).await;
+            candidates.push((pod, usage));
+        }
+        let (pod, usage) = candidates
+            .into_iter()
+            .min_by_key(|(pod, usage)| (pod.priority(), Reverse(*usage)))
+            .ok_or_else(|| anyhow!("No pod to evict"))?;
+
+        tracing::info!(
+            "Evicting pod {} for {}, priority: {}, usage: {}",
+            pod.name(),
+            pressure,
+            pod.priority(),
+            usage
+        );
+        // Pod worker stops it gracefully once deleted, and its owner recreates it elsewhere
+        match self.pod_api.delete(pod.name()).await {
+            Ok(()) => tracing::info!("Pod {} evicted", pod.name()),
+            // Deleted meanwhile, nothing left to evict
+            Err(e) if e.is_not_found() => tracing::info!("Pod {} already evicted", pod.name()),
+            Err(e) => return Err(e.into()),
+        }
+
+        let grace_period =
+            Duration::from_secs(pod.spec.termination_grace_period_seconds().max(0) as u64);
+        Ok(Eviction {
+            pod_name: pod.name().to_owned(),
+            containers: pod
+                .status
+                .iter()
+                .flat_map(|status| status.container_statuses.iter())
+                .filter(|status| !status.container_id.is_empty())
+                .map(Container::from)
+                .collect(),
+            deadline: Instant::now() + grace_period + EVICTION_TIMEOUT_MARGIN,
+        })
+    }
+}
+
+/// Usage of the starved resource by the pod,
+/// memory in bytes, disk in bytes or number of processes.
+async fn pod_usage(pod: &pod::Pod, pressure: NodeConditionType) -> u64 {
+    let container_statuses = match &pod.status {
+        Some(status) => &status.container_statuses,
+        None => return 0,
+    };
+    let mut usage = 0;
+    for container in container_statuses.iter().map(Container::from) {
+        let result = match pressure {
+            NodeConditionType::DiskPressure => container
+                .writable_layer_size()
+                .await
+                .map(|size| size.max(0) as u64),
+            NodeConditionType::PIDPressure => container
+                .stats()
+                .await
+                .map(|stats| stats.pids_stats.current.unwrap_or_default()),
+            _ => container
+                .stats()
+                .await
+                .map(|stats| stats.memory_stats.usage.unwrap_or_default()),
+        };
+        match result {
+            Ok(container_usage) => usage += container_usage,
+            Err(err) => tracing::warn!("{:#}", err),
+        }
+    }
+    usage
+}
@@ -1,28 +1,28 @@
 use std::{collections::HashSet, sync::Arc};
 
-use anyhow::{anyhow, Error, Result};
+use anyhow::Result;
 use axum::{routing::get, Extension, Router};
-use reqwest::Url;
 use resources::{
-    informer::{EventHandler, Informer, ListerWatcher, ResyncHandler, Store, WsStream},
-    models::Response,
+    client::{Api, Client},
+    informer::{EventHandler, Informer, ResyncHandler, Store},
     objects::pod::Pod,
 };
 use tokio::sync::{mpsc, RwLock};
-use tokio_tungstenite::connect_async;
 
 use crate::{
-    config::CONFIG, models::PodUpdate, node_status_manager::NodeStatusManager,
-    pod_worker::PodWorker, status_manager::StatusManager,
+    config::CONFIG, eviction_manager::EvictionManager, models::PodUpdate,
+    node_status_manager::NodeStatusManager, pod_worker::PodWorker, status_manager::StatusManager,
 };
 
 mod api;
 mod config;
 mod docker;
+mod eviction_manager;
 mod models;
 mod node_status_manager;
 mod pod;
 mod pod_worker;
+mod stats;
 mod status_manager;
 mod volume;
 
@@ -41,27 +41,9 @@ async fn main() -> Result<()> {
     tracing::info!("rKubelet started");
     tracing::debug!("API Server: {}", CONFIG.cluster.api_server_url);
 
-    let lw = ListerWatcher {
-        lister: Box::new(move |_| {
-            Box::pin(async {
-                let res = reqwest::get(format!("{}/api/v1/pods", CONFIG.cluster.api_server_url))
-                    .await?
-                    .json::<Response<Vec<Pod>>>()
-                    .await?;
-                let res = res.data.ok_or_else(|| anyhow!("Lister failed"))?;
-                Ok::<Vec<Pod>, Error>(res)
-            })
-        }),
-        watcher: Box::new(|_| {
-            Box::pin(async {
-                let url = Url::parse(
-                    format!("{}/api/v1/watch/pods", CONFIG.cluster.api_server_watch_url).as_str(),
-                )?;
-                let (stream, _) = connect_async(url).await?;
-                Ok::<WsStream, Error>(stream)
-            })
-        }),
-    };
+    let client = Client::new(CONFIG.cluster.to_owned())?;
+    let pod_api = Api::<Pod>::new(client, "pods");
+    let lw = pod_api.lister_watcher();
 
     // Create work queue and register event handler closures
     let (tx, rx) = mpsc::channel::<PodUpdate>(16);
@@ -115,12 +97,21 @@ async fn main() -> Result<()> {
 
     let pods: PodList = Arc::new(RwLock::new(HashSet::new()));
     // Start pod worker
-    let mut pod_worker = PodWorker::new(node_name, pods.clone(), pod_store.clone(), tx, resync_rx);
+    let mut pod_worker = PodWorker::new(
+        node_name.to_owned(),
+        pods.clone(),
+        pod_store.clone(),
+        tx,
+        resync_rx,
+    );
     let pod_worker_handle = tokio::spawn(async move { pod_worker.run(rx).await });
 
     let mut status_manager = StatusManager::new(pods, pod_store.clone());
     let status_manager_handle = tokio::spawn(async move { status_manager.run().await });
 
+    let mut eviction_manager = EvictionManager::new(node_name, pod_store.clone(), pod_api);
+    let eviction_manager_handle = tokio::spawn(async move { eviction_manager.run().await });
+
     // Configure and start rkubelet API server
     let app_state = Arc::new(AppState {
         pod_store,
@@ -147,6 +138,7 @@ async fn main() -> Result<()> {
 
     node_status_manager_handle.await??;
     status_manager_handle.await??;
+    eviction_manager_handle.await??;
     pod_worker_handle.await??;
     informer_handle.await?
     // TODO: Gracefully shutdown
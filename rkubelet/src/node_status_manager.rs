@@ -6,14 +6,16 @@ use interfaces::Interface;
 use resources::{
     models::Response,
     objects::{
-        node::{Capacity, Node, NodeAddressType, NodeInfo, NodeStatus},
+        condition::{Condition, Conditions},
+        node::{Capacity, Node, NodeAddressType, NodeConditionType, NodeInfo, NodeStatus},
+        quantity::Quantity,
         KubeObject, Metadata, Object,
     },
 };
 use sysinfo::{RefreshKind, System, SystemExt};
 use tokio::time::sleep;
 
-use crate::config::CONFIG;
+use crate::{config::CONFIG, stats::NodeStats};
 
 pub struct NodeStatusManager {
     metadata: Metadata,
@@ -117,9 +119,33 @@ impl NodeStatusManager {
             kubelet_port: CONFIG.port,
             last_heartbeat: Local::now().naive_utc(),
             node_info: self.get_info(),
+            conditions: self.get_conditions(),
         }
     }
 
+    /// Pressure conditions of node resources,
+    /// Ready is maintained by the node lifecycle controller
+    fn get_conditions(&self) -> Conditions<NodeConditionType> {
+        let stats = NodeStats::collect();
+        let mut pressures = stats.pressures(&CONFIG.eviction_soft);
+        pressures.extend(stats.pressures(&CONFIG.eviction_hard));
+        // Keep transition time of unchanged conditions
+        let mut conditions = self.status.conditions.clone();
+        for type_ in NodeConditionType::PRESSURES {
+            let under_pressure = pressures.contains(&type_);
+            let reason = match (type_, under_pressure) {
+                (NodeConditionType::MemoryPressure, true) => "KubeletHasInsufficientMemory",
+                (NodeConditionType::MemoryPressure, false) => "KubeletHasSufficientMemory",
+                (NodeConditionType::DiskPressure, true) => "KubeletHasDiskPressure",
+                (NodeConditionType::DiskPressure, false) => "KubeletHasNoDiskPressure",
+                (NodeConditionType::PIDPressure, true) => "KubeletHasInsufficientPID",
+                (..) => "KubeletHasSufficientPID",
+            };
+            conditions.set(Condition::new(type_, under_pressure).with_reason(reason, ""));
+        }
+        conditions
+    }
+
     fn get_addresses(&self) -> HashMap<NodeAddressType, String> {
         let mut addresses = HashMap::new();
         let system = System::new();
@@ -170,13 +196,14 @@ impl NodeStatusManager {
     /// Return allocatable resources and total capacity
     fn get_capacity(&self) -> (Capacity, Capacity) {
         let system = System::new_with_specifics(RefreshKind::new().with_cpu().with_memory());
+        // sysinfo reports memory in kibibytes
         let total = Capacity {
-            cpu: system.processors().len() as u16,
-            memory: system.total_memory(),
+            cpu: Quantity::from_value(system.processors().len() as i64),
+            memory: Quantity::from_bytes(system.total_memory() as i64 * 1024),
         };
         let allocatable = Capacity {
-            cpu: system.processors().len() as u16,
-            memory: system.available_memory(),
+            cpu: Quantity::from_value(system.processors().len() as i64),
+            memory: Quantity::from_bytes(system.available_memory() as i64 * 1024),
         };
         (allocatable, total)
     }
@@ -184,6 +211,7 @@ impl NodeStatusManager {
     fn object(&self) -> KubeObject {
         KubeObject::Node(Node {
             metadata: self.metadata.clone(),
+            spec: Default::default(),
             status: self.status.clone(),
         })
     }
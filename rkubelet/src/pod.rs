@@ -10,10 +10,11 @@ use bollard::{
 use futures::future::join_all;
 use resources::{
     objects::{
+        condition::{Condition, Conditions},
         pod,
         pod::{
-            ContainerState, ContainerStatus, ImagePullPolicy, PodCondition, PodConditionType,
-            PodPhase, PodSpec, PodStatus, RestartPolicy, VolumeMount,
+            ContainerState, ContainerStatus, ImagePullPolicy, PodConditionType, PodPhase, PodSpec,
+            PodStatus, RestartPolicy, VolumeMount,
         },
         KubeObject, Metadata,
     },
@@ -120,7 +121,7 @@ impl Pod {
 
         let host_config = Some(HostConfig {
             cpu_shares: Some(container.resources.cpu_shares()),
-            memory: Some(container.resources.limits.memory),
+            memory: Some(container.resources.limits.memory.value()),
             network_mode: mode.to_owned(),
             ipc_mode: mode.to_owned(),
             pid_mode: mode.to_owned(),
@@ -243,8 +244,9 @@ impl Pod {
     /// Stop all pod containers
     async fn stop_containers(&self) -> Result<()> {
         let containers = self.containers();
-        let results = docker::stop_containers(&containers).await;
-        self.sandbox().stop().await?;
+        let grace_period = self.spec.termination_grace_period_seconds();
+        let results = docker::stop_containers(&containers, Some(grace_period)).await;
+        self.sandbox().stop(None).await?;
         first_error_or_ok(results)
     }
 
@@ -279,7 +281,6 @@ impl Pod {
             .map(ContainerStatus::from)
             .collect::<Vec<_>>();
         let phase = self.compute_phase(&container_statuses);
-        // TODO: determine pod conditions
         let new_status = PodStatus {
             phase,
             pod_ip,
@@ -410,31 +411,37 @@ impl Pod {
         PodPhase::Pending
     }
 
-    fn determine_conditions(
-        &self,
-        containers: &[ContainerStatus],
-    ) -> HashMap<PodConditionType, PodCondition> {
+    fn determine_conditions(&self, containers: &[ContainerStatus]) -> Conditions<PodConditionType> {
         let mut conditions = self.status.conditions.to_owned();
-        // Make sure running container count equals that in spec,
-        // in case no pod container remains
-        let ready_count = containers
+        // Check against containers in spec, in case no pod container remains
+        let unready = self
+            .spec
+            .containers
             .iter()
-            .map(|status| (status.state == ContainerState::Running) as u32)
-            .sum::<u32>();
-        let containers_ready = ready_count == self.spec.containers.len() as u32;
-        conditions.insert(
-            PodConditionType::ContainersReady,
-            PodCondition {
-                status: containers_ready,
-            },
-        );
+            .filter(|container| {
+                !containers.iter().any(|status| {
+                    status.name == container.name && status.state == ContainerState::Running
+                })
+            })
+            .map(|container| container.name.as_str())
+            .collect::<Vec<_>>();
+        let (containers_ready, ready) = if unready.is_empty() {
+            (
+                Condition::new(PodConditionType::ContainersReady, true),
+                Condition::new(PodConditionType::Ready, true),
+            )
+        } else {
+            let message = format!("containers with unready status: [{}]", unready.join(", "));
+            (
+                Condition::new(PodConditionType::ContainersReady, false)
+                    .with_reason("ContainersNotReady", &message),
+                Condition::new(PodConditionType::Ready, false)
+                    .with_reason("ContainersNotReady", &message),
+            )
+        };
+        conditions.set(containers_ready);
         // TODO: Since we don't have readiness gates, this is equivalent to ContainersReady
-        conditions.insert(
-            PodConditionType::Ready,
-            PodCondition {
-                status: containers_ready,
-            },
-        );
+        conditions.set(ready);
         conditions
     }
 
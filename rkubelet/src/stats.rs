@@ -0,0 +1,84 @@
+use std::{fs, path::Path};
+
+use resources::{config::kubelet::EvictionThresholds, objects::node::NodeConditionType};
+use sysinfo::{DiskExt, ProcessRefreshKind, RefreshKind, System, SystemExt};
+
+use crate::config::CONFIG;
+
+/// Resource usage of the node, the signals of eviction
+#[derive(Debug, Clone)]
+pub struct NodeStats {
+    /// Available memory in kibibytes
+    pub memory_available: u64,
+    pub memory_total: u64,
+    /// Available space on the filesystem of `eviction_fs_path` in bytes
+    pub disk_available: u64,
+    pub disk_total: u64,
+    /// Number of running processes
+    pub pids_used: u64,
+    /// Maximum process ID of the kernel
+    pub pid_max: u64,
+}
+
+impl NodeStats {
+    pub fn collect() -> Self {
+        let system = System::new_with_specifics(
+            RefreshKind::new()
+                .with_memory()
+                .with_disks_list()
+                .with_processes(ProcessRefreshKind::new()),
+        );
+        // The disk mounted at the longest prefix of the path holds it
+        let path = Path::new(&CONFIG.eviction_fs_path);
+        let disk = system
+            .disks()
+            .iter()
+            .filter(|disk| path.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len());
+        let pid_max = fs::read_to_string("/proc/sys/kernel/pid_max")
+            .ok()
+            .and_then(|content| content.trim().parse().ok())
+            .unwrap_or_default();
+
+        NodeStats {
+            memory_available: system.available_memory(),
+            memory_total: system.total_memory(),
+            disk_available: disk.map_or(0, DiskExt::available_space),
+            disk_total: disk.map_or(0, DiskExt::total_space),
+            pids_used: system.processes().len() as u64,
+            pid_max,
+        }
+    }
+
+    /// Pressure conditions whose resource is available below the thresholds
+    pub fn pressures(&self, thresholds: &EvictionThresholds) -> Vec<NodeConditionType> {
+        let mut pressures = Vec::new();
+        if below(
+            self.memory_available,
+            self.memory_total,
+            thresholds.memory_available_percent,
+        ) {
+            pressures.push(NodeConditionType::MemoryPressure);
+        }
+        if below(
+            self.disk_available,
+            self.disk_total,
+            thresholds.disk_available_percent,
+        ) {
+            pressures.push(NodeConditionType::DiskPressure);
+        }
+        if below(
+            self.pid_max.saturating_sub(self.pids_used),
+            self.pid_max,
+            thresholds.pid_available_percent,
+        ) {
+            pressures.push(NodeConditionType::PIDPressure);
+        }
+        pressures
+    }
+}
+
+/// Whether `available` is below `percent` of `total`, unknown totals never are
+fn below(available: u64, total: u64, percent: u64) -> bool {
+    total > 0 && available * 100 < total * percent
+}
@@ -1,22 +1,97 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use resources::{
-    informer::Store,
-    objects::{node::Node, pod::Pod, Labels},
+    informer::{Store, NODE_NAME_INDEX},
+    objects::{
+        affinity::HOSTNAME_TOPOLOGY_KEY,
+        metrics::Resource,
+        node::{Node, NodeConditionType},
+        pod::Pod,
+        taint::Taint,
+        Labels, Object,
+    },
 };
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct NodeState {
     pub name: String,
     pub labels: Labels,
     pub is_ready: bool,
-    pub pod_count: u32,
+    /// Whether the node is cordoned
+    pub unschedulable: bool,
+    /// Resources the node is short of
+    pub pressures: Vec<NodeConditionType>,
+    pub taints: Vec<Taint>,
+    /// Pods bound to the node, by name
+    pub pods: HashMap<String, Pod>,
+    /// Allocatable CPU in milli cores
+    pub allocatable_cpu: i64,
+    /// Allocatable memory in bytes
+    pub allocatable_memory: i64,
+    /// CPU requested by pods bound to the node, in milli cores
+    pub requested_cpu: i64,
+    /// Memory requested by pods bound to the node, in bytes
+    pub requested_memory: i64,
+}
+
+impl NodeState {
+    pub fn pod_count(&self) -> usize {
+        self.pods.len()
+    }
+
+    /// Value of the node label `key` which defines the topology domain of the node,
+    /// the hostname topology key defaults to the node name if not labeled.
+    pub fn topology_value(&self, key: &str) -> Option<&str> {
+        match self.labels.0.get(key) {
+            Some(value) => Some(value),
+            None if key == HOSTNAME_TOPOLOGY_KEY => Some(&self.name),
+            None => None,
+        }
+    }
+
+    pub fn add_pod(&mut self, pod: &Pod) {
+        if self
+            .pods
+            .insert(pod.name().to_owned(), pod.to_owned())
+            .is_none()
+        {
+            self.requested_cpu += pod.requests(&Resource::CPU);
+            self.requested_memory += pod.requests(&Resource::Memory);
+        }
+    }
+
+    pub fn remove_pod(&mut self, pod: &Pod) {
+        if let Some(pod) = self.pods.remove(pod.name()) {
+            self.requested_cpu = (self.requested_cpu - pod.requests(&Resource::CPU)).max(0);
+            self.requested_memory =
+                (self.requested_memory - pod.requests(&Resource::Memory)).max(0);
+        }
+    }
+}
+
+/// Assumed pods are forgotten if their binding isn't seen for this long after it's sent
+const ASSUMED_POD_TTL: Duration = Duration::from_secs(30);
+
+/// A pod counted on a node before its binding is seen from the API server
+struct AssumedPod {
+    pod: Pod,
+    node_name: String,
+    /// Set when binding finishes, the pod expires if its binding isn't seen by then
+    deadline: Option<Instant>,
 }
 
 pub struct Cache {
     pub pod_cache: Store<Pod>,
     pub node_cache: Store<Node>,
     pub node_states: HashMap<String, NodeState>,
+    /// Pending pods nominated to run on each node after preemption,
+    /// by node name and then pod name
+    pub nominated_pods: HashMap<String, HashMap<String, Pod>>,
+    /// Pods scheduled but not yet seen bound, by pod name
+    assumed_pods: HashMap<String, AssumedPod>,
 }
 
 impl Cache {
@@ -27,10 +102,12 @@ impl Cache {
             pod_cache,
             node_cache,
             node_states: HashMap::new(),
+            nominated_pods: HashMap::new(),
+            assumed_pods: HashMap::new(),
         }
     }
 
-    /// Re-calculate node states.
+    /// Re-calculate node states, keeping assumed pods.
     pub async fn refresh(&mut self) {
         self.node_states.clear();
         let store = self.node_cache.read().await;
@@ -41,11 +118,17 @@ impl Cache {
         }
     }
 
+    /// Count the pod bound to the node,
+    /// confirming it if it's assumed.
     pub async fn handle_pod_add(&mut self, pod: Pod, node_name: &String) {
+        if let Some(assumed) = self.assumed_pods.remove(pod.name()) {
+            // Bound to another node by someone else
+            if &assumed.node_name != node_name {
+                self.forget_on_node(&pod, &assumed.node_name);
+            }
+        }
         match self.node_states.get_mut(node_name) {
-            Some(node_state) => {
-                node_state.pod_count += 1;
-            },
+            Some(node_state) => node_state.add_pod(&pod),
             None => {
                 tracing::warn!(
                     "Pod {} is scheduled to node {} which is not in the node cache",
@@ -57,11 +140,12 @@ impl Cache {
     }
 
     pub async fn handle_pod_delete(&mut self, pod: Pod) {
+        if let Some(assumed) = self.assumed_pods.remove(pod.name()) {
+            self.forget_on_node(&pod, &assumed.node_name);
+        }
         if let Some(node_name) = &pod.spec.node_name {
             match self.node_states.get_mut(node_name) {
-                Some(node_state) => {
-                    node_state.pod_count -= 1;
-                },
+                Some(node_state) => node_state.remove_pod(&pod),
                 None => {
                     tracing::warn!(
                         "Pod {} is scheduled to node {} which is not in the node cache",
@@ -73,19 +157,91 @@ impl Cache {
         }
     }
 
-    pub async fn handle_node_add(&mut self, node: Node) {
-        let is_ready = node.is_ready();
-        self.node_states.insert(
-            node.metadata.name.to_owned(),
-            NodeState {
-                name: node.metadata.name,
-                labels: node.metadata.labels,
-                is_ready,
-                ..Default::default()
+    /// Count the pod on the node as soon as it's scheduled,
+    /// so that pods scheduled before its binding is seen don't land on the same room.
+    pub fn assume_pod(&mut self, pod: &Pod, node_name: &str) {
+        if let Some(node_state) = self.node_states.get_mut(node_name) {
+            node_state.add_pod(pod);
+        }
+        self.assumed_pods.insert(
+            pod.name().to_owned(),
+            AssumedPod {
+                pod: pod.to_owned(),
+                node_name: node_name.to_owned(),
+                deadline: None,
             },
         );
     }
 
+    /// Start the TTL of the assumed pod once it's bound
+    pub fn finish_binding(&mut self, pod: &Pod) {
+        if let Some(assumed) = self.assumed_pods.get_mut(pod.name()) {
+            assumed.deadline = Some(Instant::now() + ASSUMED_POD_TTL);
+        }
+    }
+
+    pub fn is_assumed(&self, pod: &Pod) -> bool {
+        self.assumed_pods.contains_key(pod.name())
+    }
+
+    /// Remove an assumed pod which is never bound
+    pub fn forget_pod(&mut self, pod: &Pod) {
+        if let Some(assumed) = self.assumed_pods.remove(pod.name()) {
+            self.forget_on_node(pod, &assumed.node_name);
+        }
+    }
+
+    /// Forget assumed pods whose binding isn't seen within the TTL,
+    /// e.g. the binding is lost or the pod is bound by someone else.
+    pub fn cleanup_assumed_pods(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .assumed_pods
+            .iter()
+            .filter(|(_, assumed)| assumed.deadline.map_or(false, |deadline| deadline <= now))
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<_>>();
+        for name in expired {
+            if let Some(assumed) = self.assumed_pods.remove(&name) {
+                tracing::warn!("Assumed pod {} on node {} expired", name, assumed.node_name);
+                self.forget_on_node(&assumed.pod, &assumed.node_name);
+            }
+        }
+    }
+
+    fn forget_on_node(&mut self, pod: &Pod, node_name: &str) {
+        if let Some(node_state) = self.node_states.get_mut(node_name) {
+            node_state.remove_pod(pod);
+        }
+    }
+
+    /// Track the pod on its nominated node until it's scheduled or deleted
+    pub fn update_nominated_pod(&mut self, pod: &Pod) {
+        self.remove_nominated_pod(pod);
+        if pod.spec.node_name.is_some() {
+            return;
+        }
+        if let Some(node_name) = pod.nominated_node_name() {
+            self.nominated_pods
+                .entry(node_name.to_owned())
+                .or_default()
+                .insert(pod.name().to_owned(), pod.to_owned());
+        }
+    }
+
+    pub fn remove_nominated_pod(&mut self, pod: &Pod) {
+        self.nominated_pods.retain(|_, pods| {
+            pods.remove(pod.name());
+            !pods.is_empty()
+        });
+    }
+
+    pub async fn handle_node_add(&mut self, node: Node) {
+        // Pods may have been bound to the node before it's seen
+        let state = self.calculate_node_state(&node).await;
+        self.node_states.insert(node.metadata.name, state);
+    }
+
     pub async fn handle_node_update(&mut self, new_node: Node) {
         let new_state = self.calculate_node_state(&new_node).await;
         self.node_states.insert(new_node.metadata.name, new_state);
@@ -96,17 +252,29 @@ impl Cache {
     }
 
     pub async fn calculate_node_state(&self, node: &Node) -> NodeState {
+        let allocatable = &node.status.allocatable;
         let mut node_state = NodeState {
             name: node.metadata.name.to_owned(),
             labels: node.metadata.labels.to_owned(),
             is_ready: node.is_ready(),
+            unschedulable: node.spec.unschedulable,
+            pressures: node.pressures(),
+            taints: node.spec.taints.to_owned(),
+            allocatable_cpu: allocatable.cpu.milli_value(),
+            allocatable_memory: allocatable.memory.value(),
             ..Default::default()
         };
         let pods = self.pod_cache.read().await;
-        for pod in pods.values() {
-            if pod.spec.node_name == Some(node.metadata.name.to_owned()) {
-                node_state.pod_count += 1;
-            }
+        for pod in pods.by_index(NODE_NAME_INDEX, &node.metadata.name) {
+            node_state.add_pod(pod);
+        }
+        // The informer may not have seen their binding yet
+        for assumed in self
+            .assumed_pods
+            .values()
+            .filter(|assumed| assumed.node_name == node.metadata.name)
+        {
+            node_state.add_pod(&assumed.pod);
         }
         node_state
     }
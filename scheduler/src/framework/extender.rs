@@ -0,0 +1,145 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use resources::{
+    config::scheduler,
+    models::extender::{
+        ExtenderArgs, ExtenderBindingArgs, ExtenderBindingResult, ExtenderFilterResult,
+        HostPriority, MAX_EXTENDER_PRIORITY,
+    },
+    objects::{pod::Pod, Object},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::MAX_NODE_SCORE;
+
+/// Client of an HTTP extender configured in the scheduler profile
+pub struct Extender {
+    config: scheduler::Extender,
+    client: reqwest::Client,
+}
+
+impl Extender {
+    pub fn new(config: &scheduler::Extender) -> Result<Extender> {
+        if config.weight <= 0 {
+            return Err(anyhow!(
+                "Weight of extender {} must be positive",
+                config.url_prefix
+            ));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()?;
+        Ok(Extender {
+            config: config.to_owned(),
+            client,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.url_prefix
+    }
+
+    /// Whether errors of the extender should be skipped
+    pub fn is_ignorable(&self) -> bool {
+        self.config.ignorable
+    }
+
+    pub fn is_binder(&self) -> bool {
+        self.config.bind_verb.is_some()
+    }
+
+    /// Return the nodes the pod can run on,
+    /// and the reason of each node filtered out.
+    pub async fn filter(
+        &self,
+        pod: &Pod,
+        node_names: Vec<String>,
+    ) -> Result<(Vec<String>, HashMap<String, String>)> {
+        let verb = match &self.config.filter_verb {
+            Some(verb) => verb,
+            None => return Ok((node_names, HashMap::new())),
+        };
+        let args = ExtenderArgs {
+            pod: pod.to_owned(),
+            node_names,
+        };
+        let result: ExtenderFilterResult = self.send(verb, &args).await?;
+        if let Some(error) = result.error.filter(|error| !error.is_empty()) {
+            return Err(anyhow!(error));
+        }
+        // Nodes the extender doesn't know of are dropped
+        let node_names = result
+            .node_names
+            .into_iter()
+            .filter(|name| args.node_names.contains(name))
+            .collect();
+        Ok((node_names, result.failed_nodes))
+    }
+
+    /// Return the weighted score of each node,
+    /// scaled to the range of score plugins.
+    pub async fn prioritize(
+        &self,
+        pod: &Pod,
+        node_names: &[String],
+    ) -> Result<HashMap<String, i64>> {
+        let verb = match &self.config.prioritize_verb {
+            Some(verb) => verb,
+            None => return Ok(HashMap::new()),
+        };
+        let args = ExtenderArgs {
+            pod: pod.to_owned(),
+            node_names: node_names.to_vec(),
+        };
+        let priorities: Vec<HostPriority> = self.send(verb, &args).await?;
+        let scale = MAX_NODE_SCORE / MAX_EXTENDER_PRIORITY;
+        Ok(priorities
+            .into_iter()
+            .map(|priority| {
+                let score = priority.score.clamp(0, MAX_EXTENDER_PRIORITY);
+                (priority.host, score * scale * self.config.weight)
+            })
+            .collect())
+    }
+
+    pub async fn bind(&self, pod: &Pod, node_name: &str) -> Result<()> {
+        let verb = self
+            .config
+            .bind_verb
+            .as_ref()
+            .ok_or_else(|| anyhow!("Extender {} is not a binder", self.name()))?;
+        let args = ExtenderBindingArgs {
+            pod_name: pod.name().to_owned(),
+            pod_uid: pod.metadata().uid,
+            node: node_name.to_owned(),
+        };
+        let result: ExtenderBindingResult = self.send(verb, &args).await?;
+        match result.error.filter(|error| !error.is_empty()) {
+            Some(error) => Err(anyhow!(error)),
+            None => Ok(()),
+        }
+    }
+
+    async fn send<A: Serialize, R: DeserializeOwned>(&self, verb: &str, args: &A) -> Result<R> {
+        let url = format!("{}/{}", self.config.url_prefix.trim_end_matches('/'), verb);
+        let response = self
+            .client
+            .post(&url)
+            .json(args)
+            .send()
+            .await
+            .with_context(|| format!("Failed to call extender {}", url))?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Extender {} responded with {}",
+                url,
+                response.status()
+            ));
+        }
+        response
+            .json::<R>()
+            .await
+            .with_context(|| format!("Failed to parse response of extender {}", url))
+    }
+}
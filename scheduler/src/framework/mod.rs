@@ -0,0 +1,460 @@
+use std::{
+    any::Any,
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use rayon::prelude::*;
+use resources::{
+    client::Client,
+    config::scheduler::SchedulerProfile,
+    objects::{pod::Pod, Object},
+};
+use tokio::task;
+
+use self::extender::Extender;
+use crate::{
+    cache::{Cache, NodeState},
+    plugins,
+};
+
+mod extender;
+mod preemption;
+
+/// Highest score a score plugin should give after normalization
+pub const MAX_NODE_SCORE: i64 = 100;
+/// Lowest score a score plugin should give after normalization
+pub const MIN_NODE_SCORE: i64 = 0;
+
+/// Data shared between plugins within the scheduling cycle of a pod,
+/// e.g. something computed once in PreFilter and read in Filter for every node.
+#[derive(Default)]
+pub struct CycleState(HashMap<&'static str, Box<dyn Any + Send + Sync>>);
+
+impl CycleState {
+    pub fn write<T: Any + Send + Sync>(&mut self, key: &'static str, value: T) {
+        self.0.insert(key, Box::new(value));
+    }
+
+    pub fn read<T: Any>(&self, key: &'static str) -> Option<&T> {
+        self.0.get(key)?.downcast_ref::<T>()
+    }
+}
+
+pub trait Plugin: Send + Sync {
+    /// Name used to enable the plugin in the scheduler profile
+    fn name(&self) -> &'static str;
+}
+
+pub trait PreFilterPlugin: Plugin {
+    /// Check the pod and precompute what's needed by Filter and Score
+    /// with all the nodes in the cache,
+    /// return the reason if it can't be scheduled to any node.
+    fn pre_filter(
+        &self,
+        state: &mut CycleState,
+        pod: &Pod,
+        nodes: &[&NodeState],
+    ) -> Result<(), String>;
+}
+
+pub trait FilterPlugin: Plugin {
+    /// Return the reason if the pod can't run on the node,
+    /// e.g. "Insufficient cpu".
+    fn filter(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String>;
+}
+
+pub trait ScorePlugin: Plugin {
+    /// Rank a node that passed all filters, higher is better.
+    fn score(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> i64;
+
+    /// Map the scores of all feasible nodes into [MIN_NODE_SCORE, MAX_NODE_SCORE].
+    /// Scores are expected in that range already if not overridden.
+    fn normalize_score(&self, _state: &CycleState, _pod: &Pod, _scores: &mut [i64]) {}
+}
+
+pub trait ReservePlugin: Plugin {
+    /// Reserve resources on the chosen node before the pod is bound
+    fn reserve(&self, state: &CycleState, pod: &Pod, node_name: &str) -> Result<()>;
+
+    /// Release what's reserved if reserving or binding fails
+    fn unreserve(&self, state: &CycleState, pod: &Pod, node_name: &str);
+}
+
+#[async_trait]
+pub trait BindPlugin: Plugin {
+    /// Bind the pod to the node, return `Ok(false)` to leave it to the next bind plugin.
+    async fn bind(&self, state: &CycleState, pod: &Pod, node_name: &str) -> Result<bool>;
+}
+
+/// Reasons why a pod doesn't fit on any node
+#[derive(Debug, Default)]
+pub struct FitError {
+    /// Number of nodes considered
+    pub num_nodes: usize,
+    /// Number of nodes filtered out for each reason
+    pub reasons: BTreeMap<String, usize>,
+    /// Rejected by a PreFilter plugin, preempting other pods won't help
+    pub unresolvable: bool,
+}
+
+impl FitError {
+    pub fn new(num_nodes: usize) -> Self {
+        FitError {
+            num_nodes,
+            ..Default::default()
+        }
+    }
+
+    /// None of the nodes fits the pod for the same reason,
+    /// which preempting other pods won't help
+    pub fn unresolvable(num_nodes: usize, reason: impl ToString) -> Self {
+        FitError {
+            num_nodes,
+            reasons: BTreeMap::from([(reason.to_string(), num_nodes)]),
+            unresolvable: true,
+        }
+    }
+
+    pub fn add_reason(&mut self, reason: impl ToString) {
+        *self.reasons.entry(reason.to_string()).or_default() += 1;
+    }
+}
+
+impl fmt::Display for FitError {
+    /// Format like "0/3 nodes are available: 2 Insufficient cpu, 1 node(s) were not ready."
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0/{} nodes are available", self.num_nodes)?;
+        if self.reasons.is_empty() {
+            return write!(f, ".");
+        }
+        let reasons = self
+            .reasons
+            .iter()
+            .map(|(reason, count)| format!("{} {}", count, reason))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, ": {}.", reasons)
+    }
+}
+
+/// Why the pod isn't scheduled in a scheduling cycle
+#[derive(Debug)]
+pub enum ScheduleError {
+    /// The pod doesn't fit on any node
+    Unschedulable(FitError),
+    /// A required extender failed, the pod may fit when it's retried
+    Extender(anyhow::Error),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::Unschedulable(fit_error) => fit_error.fmt(f),
+            ScheduleError::Extender(e) => write!(f, "{:#}", e),
+        }
+    }
+}
+
+/// Plugins enabled by the scheduler profile, run at each extension point.
+pub struct Framework {
+    pre_filter_plugins: Vec<Box<dyn PreFilterPlugin>>,
+    filter_plugins: Vec<Box<dyn FilterPlugin>>,
+    score_plugins: Vec<(Box<dyn ScorePlugin>, i64)>,
+    reserve_plugins: Vec<Box<dyn ReservePlugin>>,
+    bind_plugins: Vec<Box<dyn BindPlugin>>,
+    extenders: Vec<Extender>,
+}
+
+impl Framework {
+    /// Build the plugins enabled by `profile`, bind plugins talk to API server through `client`.
+    pub fn new(profile: &SchedulerProfile, client: &Client) -> Result<Framework> {
+        let score_plugins = profile
+            .score
+            .iter()
+            .map(|plugin| {
+                if plugin.weight <= 0 {
+                    return Err(anyhow!(
+                        "Weight of score plugin {} must be positive",
+                        plugin.name
+                    ));
+                }
+                let score_plugin = plugins::new_score_plugin(&plugin.name)
+                    .ok_or_else(|| anyhow!("Unknown score plugin {}", plugin.name))?;
+                Ok((score_plugin, plugin.weight))
+            })
+            .collect::<Result<_>>()?;
+        if profile.bind.is_empty() {
+            return Err(anyhow!("At least one bind plugin must be enabled"));
+        }
+        Ok(Framework {
+            pre_filter_plugins: build_plugins(
+                &profile.pre_filter,
+                "pre-filter",
+                plugins::new_pre_filter_plugin,
+            )?,
+            filter_plugins: build_plugins(&profile.filter, "filter", plugins::new_filter_plugin)?,
+            score_plugins,
+            reserve_plugins: build_plugins(
+                &profile.reserve,
+                "reserve",
+                plugins::new_reserve_plugin,
+            )?,
+            bind_plugins: build_plugins(&profile.bind, "bind", |name| {
+                plugins::new_bind_plugin(name, client)
+            })?,
+            extenders: profile
+                .extenders
+                .iter()
+                .map(Extender::new)
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Find the best node for the pod, running PreFilter, Filter and Score plugins,
+    /// then filter and prioritize of extenders.
+    ///
+    /// Filter and Score plugins run across nodes in parallel,
+    /// the worker thread is handed over meanwhile so that other tasks keep running.
+    pub async fn schedule(
+        &self,
+        state: &mut CycleState,
+        pod: &Pod,
+        cache: &Cache,
+    ) -> Result<String, ScheduleError> {
+        let nodes = cache.node_states.values().collect::<Vec<_>>();
+        for plugin in self.pre_filter_plugins.iter() {
+            if let Err(reason) = plugin.pre_filter(state, pod, &nodes) {
+                return Err(ScheduleError::Unschedulable(FitError::unresolvable(
+                    nodes.len(),
+                    reason,
+                )));
+            }
+        }
+
+        let mut fit_error = FitError::new(nodes.len());
+        let state = &*state;
+        let nominated_pods = &cache.nominated_pods;
+        let results = task::block_in_place(|| {
+            nodes
+                .par_iter()
+                .map(|node| match nominated_pods.get(&node.name) {
+                    Some(nominated) => {
+                        self.run_filter_plugins_with_nominated(state, pod, node, nominated)
+                    },
+                    None => self.run_filter_plugins(state, pod, node),
+                })
+                .collect::<Vec<_>>()
+        });
+        let mut feasible_nodes = Vec::new();
+        for (node, result) in nodes.into_iter().zip(results) {
+            match result {
+                Ok(()) => feasible_nodes.push(node),
+                Err(reason) => fit_error.add_reason(reason),
+            }
+        }
+        let feasible_nodes = self
+            .run_extender_filters(pod, feasible_nodes, &mut fit_error)
+            .await
+            .map_err(ScheduleError::Extender)?;
+
+        match feasible_nodes.len() {
+            0 => Err(ScheduleError::Unschedulable(fit_error)),
+            1 => Ok(feasible_nodes[0].name.to_owned()),
+            _ => {
+                let mut scores =
+                    task::block_in_place(|| self.run_score_plugins(state, pod, &feasible_nodes));
+                self.run_extender_prioritizes(pod, &feasible_nodes, &mut scores)
+                    .await;
+                // Break ties by name, so that the result is deterministic
+                let (node, _) = feasible_nodes
+                    .into_iter()
+                    .zip(scores)
+                    .max_by_key(|&(node, score)| (score, Reverse(&node.name)))
+                    .expect("Feasible nodes shouldn't be empty");
+                Ok(node.name.to_owned())
+            },
+        }
+    }
+
+    /// Run filter of extenders in order on the nodes passing all filter plugins.
+    ///
+    /// Nodes an extender filters out are counted in the fit error with its reason.
+    async fn run_extender_filters<'a>(
+        &self,
+        pod: &Pod,
+        nodes: Vec<&'a NodeState>,
+        fit_error: &mut FitError,
+    ) -> Result<Vec<&'a NodeState>> {
+        let mut node_names = nodes
+            .iter()
+            .map(|node| node.name.to_owned())
+            .collect::<Vec<_>>();
+        for extender in self.extenders.iter() {
+            if node_names.is_empty() {
+                break;
+            }
+            match extender.filter(pod, node_names.to_owned()).await {
+                Ok((passed, failed)) => {
+                    for name in node_names.iter().filter(|name| !passed.contains(name)) {
+                        match failed.get(name) {
+                            Some(reason) => fit_error.add_reason(reason),
+                            None => fit_error.add_reason(format!(
+                                "node(s) were filtered out by extender {}",
+                                extender.name()
+                            )),
+                        }
+                    }
+                    node_names = passed;
+                },
+                Err(e) if extender.is_ignorable() => {
+                    tracing::warn!("Skipping extender {}: {:#}", extender.name(), e);
+                },
+                Err(e) => {
+                    return Err(e.context(format!("Extender {} failed to filter", extender.name())));
+                },
+            }
+        }
+        Ok(nodes
+            .into_iter()
+            .filter(|node| node_names.contains(&node.name))
+            .collect())
+    }
+
+    /// Add the weighted scores from prioritize of extenders to the scores of plugins.
+    ///
+    /// Extenders failing to prioritize are skipped, as the scores are only preferences.
+    async fn run_extender_prioritizes(&self, pod: &Pod, nodes: &[&NodeState], scores: &mut [i64]) {
+        let node_names = nodes
+            .iter()
+            .map(|node| node.name.to_owned())
+            .collect::<Vec<_>>();
+        for extender in self.extenders.iter() {
+            match extender.prioritize(pod, &node_names).await {
+                Ok(extender_scores) => {
+                    for (name, total) in node_names.iter().zip(scores.iter_mut()) {
+                        *total += extender_scores.get(name).copied().unwrap_or_default();
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!("Extender {} failed to prioritize: {:#}", extender.name(), e);
+                },
+            }
+        }
+    }
+
+    /// Run filter plugins in order, stop at the first one that rejects the node
+    fn run_filter_plugins(
+        &self,
+        state: &CycleState,
+        pod: &Pod,
+        node: &NodeState,
+    ) -> Result<(), String> {
+        self.filter_plugins
+            .iter()
+            .try_for_each(|plugin| plugin.filter(state, pod, node))
+    }
+
+    /// Run filter plugins as if pods nominated to the node with no lower priority
+    /// were running on it, so that the room made by preemption is kept for them.
+    fn run_filter_plugins_with_nominated(
+        &self,
+        state: &CycleState,
+        pod: &Pod,
+        node: &NodeState,
+        nominated: &HashMap<String, Pod>,
+    ) -> Result<(), String> {
+        let nominated = nominated
+            .values()
+            .filter(|other| other.name() != pod.name() && other.priority() >= pod.priority())
+            .collect::<Vec<_>>();
+        if nominated.is_empty() {
+            return self.run_filter_plugins(state, pod, node);
+        }
+        let mut node = node.to_owned();
+        for other in nominated {
+            node.add_pod(other);
+        }
+        self.run_filter_plugins(state, pod, &node)
+    }
+
+    /// Return the weighted sum of normalized scores of each node
+    fn run_score_plugins(&self, state: &CycleState, pod: &Pod, nodes: &[&NodeState]) -> Vec<i64> {
+        let mut total_scores = vec![0; nodes.len()];
+        for (plugin, weight) in self.score_plugins.iter() {
+            let mut scores = nodes
+                .par_iter()
+                .map(|node| plugin.score(state, pod, node))
+                .collect::<Vec<_>>();
+            plugin.normalize_score(state, pod, &mut scores);
+            for (total, score) in total_scores.iter_mut().zip(scores) {
+                *total += score.clamp(MIN_NODE_SCORE, MAX_NODE_SCORE) * weight;
+            }
+        }
+        total_scores
+    }
+
+    /// Run reserve plugins in order,
+    /// if one of them fails, those already run are unreserved.
+    pub fn run_reserve_plugins(
+        &self,
+        state: &CycleState,
+        pod: &Pod,
+        node_name: &str,
+    ) -> Result<()> {
+        for (index, plugin) in self.reserve_plugins.iter().enumerate() {
+            if let Err(e) = plugin.reserve(state, pod, node_name) {
+                for plugin in self.reserve_plugins[..index].iter().rev() {
+                    plugin.unreserve(state, pod, node_name);
+                }
+                return Err(e.context(format!("Plugin {} failed to reserve", plugin.name())));
+            }
+        }
+        Ok(())
+    }
+
+    /// Run unreserve of all reserve plugins in reverse order
+    pub fn run_unreserve_plugins(&self, state: &CycleState, pod: &Pod, node_name: &str) {
+        for plugin in self.reserve_plugins.iter().rev() {
+            plugin.unreserve(state, pod, node_name);
+        }
+    }
+
+    /// Let the first extender that's a binder bind the pod,
+    /// or run bind plugins in order until one of them handles the binding.
+    pub async fn run_bind_plugins(
+        &self,
+        state: &CycleState,
+        pod: &Pod,
+        node_name: &str,
+    ) -> Result<()> {
+        if let Some(extender) = self.extenders.iter().find(|extender| extender.is_binder()) {
+            return extender
+                .bind(pod, node_name)
+                .await
+                .with_context(|| format!("Extender {} failed to bind", extender.name()));
+        }
+        for plugin in self.bind_plugins.iter() {
+            if plugin.bind(state, pod, node_name).await? {
+                return Ok(());
+            }
+        }
+        Err(anyhow!("All bind plugins skipped the pod"))
+    }
+}
+
+fn build_plugins<P: ?Sized>(
+    names: &[String],
+    extension_point: &str,
+    new_plugin: impl Fn(&str) -> Option<Box<P>>,
+) -> Result<Vec<Box<P>>> {
+    names
+        .iter()
+        .map(|name| {
+            new_plugin(name).ok_or_else(|| anyhow!("Unknown {} plugin {}", extension_point, name))
+        })
+        .collect()
+}
@@ -0,0 +1,200 @@
+use std::cmp::Reverse;
+
+use rayon::prelude::*;
+use resources::objects::{pod::Pod, priority_class::PreemptionPolicy};
+use tokio::task;
+
+use super::{CycleState, Framework};
+use crate::cache::{Cache, NodeState};
+
+/// Node chosen to run the preemptor, and the pods to evict from it
+#[derive(Debug)]
+pub struct Candidate {
+    pub node_name: String,
+    pub victims: Vec<Pod>,
+}
+
+impl Framework {
+    /// Find a node where the pod fits after evicting some pods of lower priority.
+    ///
+    /// The candidate whose most important victim has the lowest priority wins,
+    /// ties are broken by the sum of victim priorities, then the number of victims.
+    ///
+    /// PreFilter results are reused as is,
+    /// so e.g. anti-affinity of a victim still counts when it's removed.
+    ///
+    /// Nodes are checked in parallel, it must be called on a multi-threaded runtime
+    /// as the worker thread is handed over meanwhile.
+    pub fn preempt(&self, state: &CycleState, pod: &Pod, cache: &Cache) -> Option<Candidate> {
+        if pod.spec.preemption_policy == Some(PreemptionPolicy::Never) {
+            return None;
+        }
+        let nodes = cache.node_states.values().collect::<Vec<_>>();
+        let candidates = task::block_in_place(|| {
+            nodes
+                .par_iter()
+                .filter_map(|node| self.select_victims(state, pod, node))
+                .collect::<Vec<_>>()
+        });
+        candidates.into_iter().min_by_key(|candidate| {
+            let priorities = candidate.victims.iter().map(Pod::priority);
+            (
+                priorities.clone().max(),
+                priorities.map(i64::from).sum::<i64>(),
+                candidate.victims.len(),
+                candidate.node_name.to_owned(),
+            )
+        })
+    }
+
+    /// Evict all pods of lower priority from the node,
+    /// then add back as many of them as possible, the most important first.
+    fn select_victims(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> Option<Candidate> {
+        let mut potential_victims = node
+            .pods
+            .values()
+            .filter(|other| other.priority() < pod.priority())
+            .collect::<Vec<_>>();
+        if potential_victims.is_empty() {
+            return None;
+        }
+
+        let mut node = node.to_owned();
+        for victim in potential_victims.iter() {
+            node.remove_pod(victim);
+        }
+        if self.run_filter_plugins(state, pod, &node).is_err() {
+            return None;
+        }
+
+        potential_victims.sort_by_key(|victim| Reverse(victim.priority()));
+        let mut victims = Vec::new();
+        for victim in potential_victims {
+            node.add_pod(victim);
+            if self.run_filter_plugins(state, pod, &node).is_err() {
+                node.remove_pod(victim);
+                victims.push(victim.to_owned());
+            }
+        }
+        // The pod fits without evicting anything, nothing to preempt
+        if victims.is_empty() {
+            return None;
+        }
+        Some(Candidate {
+            node_name: node.name,
+            victims,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::{
+        client::Client,
+        config::{scheduler::SchedulerProfile, ClusterConfig},
+        objects::{
+            pod::{Container, PodSpec, Resource, ResourceRequirements},
+            quantity::Quantity,
+            Metadata, Object,
+        },
+    };
+
+    use super::*;
+    use crate::plugins::node_resources::NodeResourcesFit;
+
+    fn framework() -> Framework {
+        let profile = SchedulerProfile {
+            filter: vec![NodeResourcesFit::NAME.to_string()],
+            ..Default::default()
+        };
+        Framework::new(&profile, &Client::new(ClusterConfig::default()).unwrap()).unwrap()
+    }
+
+    fn pod(name: &str, priority: i32, cpu_milli: i64) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: name.to_owned(),
+                ..Default::default()
+            },
+            spec: PodSpec {
+                priority: Some(priority),
+                containers: vec![Container {
+                    name: "main".to_string(),
+                    resources: ResourceRequirements {
+                        requests: Resource {
+                            cpu: Quantity::from_milli(cpu_milli),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            status: None,
+        }
+    }
+
+    /// A node with 1 CPU running `pods`
+    fn node(pods: &[Pod]) -> NodeState {
+        let mut node = NodeState {
+            name: "node".to_string(),
+            allocatable_cpu: 1000,
+            allocatable_memory: 1 << 30,
+            ..Default::default()
+        };
+        for pod in pods {
+            node.add_pod(pod);
+        }
+        node
+    }
+
+    fn victim_names(candidate: Option<Candidate>) -> Option<Vec<String>> {
+        candidate.map(|candidate| {
+            candidate
+                .victims
+                .iter()
+                .map(|victim| victim.name().to_owned())
+                .collect()
+        })
+    }
+
+    #[test]
+    fn evicts_the_least_important_pods_needed() {
+        let node = node(&[pod("low", 1, 300), pod("mid", 2, 300), pod("high", 20, 200)]);
+        let candidate =
+            framework().select_victims(&CycleState::default(), &pod("new", 10, 500), &node);
+        assert_eq!(victim_names(candidate), Some(vec!["low".to_string()]));
+    }
+
+    #[test]
+    fn evicts_every_pod_needed() {
+        let node = node(&[pod("low", 1, 400), pod("mid", 2, 400), pod("high", 20, 200)]);
+        let candidate =
+            framework().select_victims(&CycleState::default(), &pod("new", 10, 500), &node);
+        assert_eq!(
+            victim_names(candidate),
+            Some(vec!["mid".to_string(), "low".to_string()])
+        );
+    }
+
+    #[test]
+    fn pods_of_higher_or_equal_priority_are_never_evicted() {
+        let node = node(&[
+            pod("low", 1, 100),
+            pod("same", 10, 400),
+            pod("high", 20, 400),
+        ]);
+        let candidate =
+            framework().select_victims(&CycleState::default(), &pod("new", 10, 500), &node);
+        assert_eq!(victim_names(candidate), None);
+    }
+
+    #[test]
+    fn nothing_to_evict_when_the_pod_fits() {
+        let node = node(&[pod("low", 1, 100)]);
+        let candidate =
+            framework().select_victims(&CycleState::default(), &pod("new", 10, 500), &node);
+        assert_eq!(victim_names(candidate), None);
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::{Duration, Instant};
+
+use resources::objects::pod_group::PodGroup;
+
+use crate::{framework::CycleState, queue::QueuedPod};
+
+/// A pod assumed on a node, waiting to be bound
+pub struct WaitingPod {
+    pub queued: QueuedPod,
+    pub node_name: String,
+    pub state: CycleState,
+}
+
+/// Members of a pod group which fit, waiting for the rest of the group to be placed
+pub struct Gang {
+    pub min_member: u32,
+    pub waiting: Vec<WaitingPod>,
+    /// Waiting members are released if the group isn't complete by then
+    deadline: Instant,
+}
+
+impl Gang {
+    pub fn new(pod_group: &PodGroup) -> Self {
+        Gang {
+            min_member: pod_group.spec.min_member,
+            waiting: Vec::new(),
+            deadline: Instant::now()
+                + Duration::from_secs(pod_group.spec.schedule_timeout_seconds()),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
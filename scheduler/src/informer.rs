@@ -1,16 +1,17 @@
-use anyhow::{anyhow, Error};
+use std::sync::Arc;
+
+use anyhow::Error;
 use resources::{
-    informer::{EventHandler, Informer, ListerWatcher, ResyncHandler, Store, WsStream},
-    models,
+    client::Api,
+    informer::{node_name_index, EventHandler, Informer, ResyncHandler, Store, NODE_NAME_INDEX},
     objects::{node::Node, pod::Pod},
 };
 use tokio::{
     sync::mpsc::{self, Receiver, Sender},
     task::JoinHandle,
 };
-use tokio_tungstenite::connect_async;
 
-use crate::{NodeUpdate, PodUpdate, CONFIG};
+use crate::{NodeUpdate, PodUpdate};
 
 pub type RunInformerResult<T, E> = (
     Sender<E>,
@@ -27,30 +28,10 @@ pub enum ResyncNotification {
 }
 
 pub fn run_pod_informer(
+    pod_api: &Api<Pod>,
     resync_tx: Sender<ResyncNotification>,
 ) -> RunInformerResult<Pod, PodUpdate> {
-    // create list watcher closures
-    // TODO: maybe some crate or macros can simplify the tedious boxed closure creation in heap
-    let lw = ListerWatcher::<Pod> {
-        lister: Box::new(|_| {
-            Box::pin(async {
-                let res = reqwest::get(CONFIG.api_server_endpoint.join("api/v1/pods")?)
-                    .await?
-                    .json::<models::Response<Vec<Pod>>>()
-                    .await?;
-                let res = res.data.ok_or_else(|| anyhow!("Lister failed"))?;
-                Ok::<Vec<Pod>, Error>(res)
-            })
-        }),
-        watcher: Box::new(|_| {
-            Box::pin(async {
-                let mut url = CONFIG.api_server_endpoint.join("api/v1/watch/pods")?;
-                url.set_scheme("ws").ok();
-                let (stream, _) = connect_async(url).await?;
-                Ok::<WsStream, Error>(stream)
-            })
-        }),
-    };
+    let lw = pod_api.lister_watcher();
 
     // create event handler closures
     let (tx, rx) = mpsc::channel::<PodUpdate>(16);
@@ -92,7 +73,8 @@ pub fn run_pod_informer(
         })
     }));
     // start the informer
-    let informer = Informer::new(lw, eh, rh);
+    let informer =
+        Informer::new(lw, eh, rh).with_indexer(NODE_NAME_INDEX, Arc::new(node_name_index));
     let store = informer.get_store();
     let informer_handler = tokio::spawn(async move { informer.run().await });
 
@@ -100,30 +82,10 @@ pub fn run_pod_informer(
 }
 
 pub fn run_node_informer(
+    node_api: &Api<Node>,
     resync_tx: Sender<ResyncNotification>,
 ) -> RunInformerResult<Node, NodeUpdate> {
-    // create list watcher closures
-    // TODO: maybe some crate or macros can simplify the tedious boxed closure creation in heap
-    let lw = ListerWatcher::<Node> {
-        lister: Box::new(|_| {
-            Box::pin(async {
-                let res = reqwest::get(CONFIG.api_server_endpoint.join("api/v1/nodes")?)
-                    .await?
-                    .json::<models::Response<Vec<Node>>>()
-                    .await?;
-                let res = res.data.ok_or_else(|| anyhow!("Lister failed"))?;
-                Ok::<Vec<Node>, Error>(res)
-            })
-        }),
-        watcher: Box::new(|_| {
-            Box::pin(async {
-                let mut url = CONFIG.api_server_endpoint.join("api/v1/watch/nodes")?;
-                url.set_scheme("ws").ok();
-                let (stream, _) = connect_async(url).await?;
-                Ok::<WsStream, Error>(stream)
-            })
-        }),
-    };
+    let lw = node_api.lister_watcher();
 
     // create event handler closures
     let (tx, rx) = mpsc::channel::<NodeUpdate>(16);
@@ -1,22 +1,48 @@
 #[macro_use]
 extern crate lazy_static;
 
-use std::env;
+use std::{env, path::PathBuf};
 
 use anyhow::Result;
+use clap::{Parser, Subcommand};
 use reqwest::Url;
 use resources::{
+    client::{Api, Client},
+    config::{load_or_default, scheduler::SchedulerProfile},
     models::NodeConfig,
-    objects::{node::Node, pod::Pod},
+    objects::{node::Node, pod::Pod, pod_group::PodGroup},
 };
 use tokio::sync::mpsc;
 
-use crate::{cache::Cache, informer::*, scheduler::Scheduler};
+use crate::{cache::Cache, framework::Framework, informer::*, scheduler::Scheduler};
 
-mod algorithm;
 mod cache;
+mod framework;
+mod gang;
 mod informer;
+mod plugins;
+mod queue;
 mod scheduler;
+mod simulator;
+
+const PROFILE_PATH: &str = "/etc/rminik8s/scheduler.yaml";
+
+#[derive(Parser)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    /// Scheduler profile, run several schedulers with different profiles
+    /// to schedule pods by their `schedulerName`
+    #[clap(short, long, default_value = PROFILE_PATH)]
+    profile: PathBuf,
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Simulate scheduling pending pods on a snapshot of the cluster, without binding them.
+    Simulate(simulator::Arg),
+}
 
 #[derive(Debug)]
 pub enum PodUpdate {
@@ -52,13 +78,34 @@ lazy_static! {
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
 
+    let cli = Cli::parse();
+    if let Some(Commands::Simulate(arg)) = cli.command {
+        return arg.handle().await;
+    }
+
+    let profile = load_or_default::<SchedulerProfile>(&cli.profile)?;
+    tracing::info!("Scheduler profile: {:?}", profile);
+    let client = Client::new((&*CONFIG).into())?;
+    let framework = Framework::new(&profile, &client)?;
+
     let (resync_tx, resync_rx) = mpsc::channel::<ResyncNotification>(16);
 
-    let (pod_tx, pod_rx, pod_store, pod_informer_handler) = run_pod_informer(resync_tx.clone());
-    let (_, node_rx, node_store, node_informer_handler) = run_node_informer(resync_tx);
+    let pod_api = Api::<Pod>::new(client.to_owned(), "pods");
+    let pod_group_api = Api::<PodGroup>::new(client.to_owned(), "podgroups");
+    let node_api = Api::<Node>::new(client, "nodes");
+    let (_, pod_rx, pod_store, pod_informer_handler) =
+        run_pod_informer(&pod_api, resync_tx.clone());
+    let (_, node_rx, node_store, node_informer_handler) = run_node_informer(&node_api, resync_tx);
 
     let cache = Cache::new(pod_store.clone(), node_store.clone());
-    let mut sched = Scheduler::new(algorithm::simple::simple, cache, resync_rx, pod_tx);
+    let mut sched = Scheduler::new(
+        profile.scheduler_name,
+        framework,
+        cache,
+        pod_api.to_owned(),
+        pod_group_api,
+        resync_rx,
+    );
     let scheduler_handle = tokio::spawn(async move { sched.run(pod_rx, node_rx).await });
 
     scheduler_handle.await?;
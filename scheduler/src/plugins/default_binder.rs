@@ -0,0 +1,44 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use resources::{
+    client::Api,
+    objects::{binding::Binding, object_reference::ObjectReference, pod::Pod},
+};
+
+use crate::framework::{BindPlugin, CycleState, Plugin};
+
+/// Bind the pod by posting a Binding to API server
+pub struct DefaultBinder {
+    pod_api: Api<Pod>,
+}
+
+impl DefaultBinder {
+    pub const NAME: &'static str = "DefaultBinder";
+
+    pub fn new(pod_api: Api<Pod>) -> Self {
+        Self {
+            pod_api,
+        }
+    }
+}
+
+impl Plugin for DefaultBinder {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+#[async_trait]
+impl BindPlugin for DefaultBinder {
+    async fn bind(&self, _: &CycleState, pod: &Pod, node_name: &str) -> Result<bool> {
+        let binding = Binding {
+            metadata: pod.metadata.clone(),
+            target: ObjectReference {
+                kind: "node".to_string(),
+                name: node_name.to_owned(),
+            },
+        };
+        self.pod_api.bind(&binding).await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,29 @@
+use resources::objects::pod::Pod;
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin},
+};
+
+/// Schedule every pod to the node named "localhost", for testing
+pub struct Dummy;
+
+impl Dummy {
+    pub const NAME: &'static str = "Dummy";
+}
+
+impl Plugin for Dummy {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for Dummy {
+    fn filter(&self, _: &CycleState, _: &Pod, node: &NodeState) -> Result<(), String> {
+        if node.name == "localhost" {
+            Ok(())
+        } else {
+            Err("node(s) were not localhost".to_string())
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+
+use resources::objects::{
+    affinity::{PodAffinity, PodAffinityTerm},
+    pod::Pod,
+};
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin, PreFilterPlugin, ScorePlugin, MAX_NODE_SCORE},
+};
+
+const STATE_KEY: &str = "PreFilterInterPodAffinity";
+
+/// Topology key and value of a topology domain, e.g. ("zone", "east")
+type TopologyPair = (String, String);
+
+/// Existing pods relevant to the pod being scheduled,
+/// counted by the topology domain they are running in.
+#[derive(Debug, Default)]
+struct PreFilterState {
+    /// For each required affinity term of the pod,
+    /// number of matching pods in each value of the topology key
+    affinity_counts: Vec<HashMap<String, usize>>,
+    /// For each required anti-affinity term of the pod,
+    /// number of matching pods in each value of the topology key
+    anti_affinity_counts: Vec<HashMap<String, usize>>,
+    /// Topology domains of existing pods whose required anti-affinity matches the pod
+    existing_anti_affinity: HashSet<TopologyPair>,
+    /// Sum of weights of matching preferred terms,
+    /// in both directions, in each topology domain
+    scores: HashMap<TopologyPair, i64>,
+}
+
+fn pod_affinity(pod: &Pod) -> Option<&PodAffinity> {
+    pod.spec.affinity.as_ref()?.pod_affinity.as_ref()
+}
+
+fn pod_anti_affinity(pod: &Pod) -> Option<&PodAffinity> {
+    pod.spec.affinity.as_ref()?.pod_anti_affinity.as_ref()
+}
+
+fn required_terms(affinity: Option<&PodAffinity>) -> &[PodAffinityTerm] {
+    match affinity {
+        Some(affinity) => &affinity.required_during_scheduling_ignored_during_execution,
+        None => &[],
+    }
+}
+
+/// Preferred terms with their weights, negative for anti-affinity
+fn preferred_terms(pod: &Pod) -> impl Iterator<Item = (&PodAffinityTerm, i64)> {
+    let affinity = pod_affinity(pod).into_iter().flat_map(|affinity| {
+        affinity
+            .preferred_during_scheduling_ignored_during_execution
+            .iter()
+            .map(|term| (&term.pod_affinity_term, term.weight))
+    });
+    let anti_affinity = pod_anti_affinity(pod).into_iter().flat_map(|affinity| {
+        affinity
+            .preferred_during_scheduling_ignored_during_execution
+            .iter()
+            .map(|term| (&term.pod_affinity_term, -term.weight))
+    });
+    affinity.chain(anti_affinity)
+}
+
+fn term_matches(term: &PodAffinityTerm, pod: &Pod) -> bool {
+    term.label_selector.matches(&pod.metadata.labels)
+}
+
+/// Filter and score nodes by pod affinity and anti-affinity
+/// against pods already running in the same topology domain.
+///
+/// Requires the PreFilter extension point to be enabled.
+pub struct InterPodAffinity;
+
+impl InterPodAffinity {
+    pub const NAME: &'static str = "InterPodAffinity";
+}
+
+impl Plugin for InterPodAffinity {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl PreFilterPlugin for InterPodAffinity {
+    fn pre_filter(
+        &self,
+        state: &mut CycleState,
+        pod: &Pod,
+        nodes: &[&NodeState],
+    ) -> Result<(), String> {
+        let affinity_terms = required_terms(pod_affinity(pod));
+        let anti_affinity_terms = required_terms(pod_anti_affinity(pod));
+        let mut result = PreFilterState {
+            affinity_counts: vec![HashMap::new(); affinity_terms.len()],
+            anti_affinity_counts: vec![HashMap::new(); anti_affinity_terms.len()],
+            ..Default::default()
+        };
+
+        for node in nodes {
+            let topology_pair = |term: &PodAffinityTerm| {
+                node.topology_value(&term.topology_key)
+                    .map(|value| (term.topology_key.to_owned(), value.to_owned()))
+            };
+            for existing in node.pods.values() {
+                for (term, counts) in affinity_terms
+                    .iter()
+                    .zip(result.affinity_counts.iter_mut())
+                    .chain(
+                        anti_affinity_terms
+                            .iter()
+                            .zip(result.anti_affinity_counts.iter_mut()),
+                    )
+                {
+                    if let Some((_, value)) =
+                        topology_pair(term).filter(|_| term_matches(term, existing))
+                    {
+                        *counts.entry(value).or_default() += 1;
+                    }
+                }
+                for term in required_terms(pod_anti_affinity(existing)) {
+                    if let Some(pair) = topology_pair(term).filter(|_| term_matches(term, pod)) {
+                        result.existing_anti_affinity.insert(pair);
+                    }
+                }
+                // Preferred terms of the pod matching existing pods, and vice versa
+                let preferred = preferred_terms(pod)
+                    .filter(|(term, _)| term_matches(term, existing))
+                    .chain(preferred_terms(existing).filter(|(term, _)| term_matches(term, pod)));
+                for (term, weight) in preferred {
+                    if let Some(pair) = topology_pair(term) {
+                        *result.scores.entry(pair).or_default() += weight;
+                    }
+                }
+            }
+        }
+
+        state.write(STATE_KEY, result);
+        Ok(())
+    }
+}
+
+impl FilterPlugin for InterPodAffinity {
+    fn filter(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        let state = match state.read::<PreFilterState>(STATE_KEY) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+
+        let in_domain =
+            |(key, value): &TopologyPair| node.topology_value(key) == Some(value.as_str());
+        if state.existing_anti_affinity.iter().any(in_domain) {
+            return Err("node(s) didn't satisfy existing pods anti-affinity rules".to_string());
+        }
+
+        let count = |term: &PodAffinityTerm, counts: &HashMap<String, usize>| {
+            node.topology_value(&term.topology_key)
+                .and_then(|value| counts.get(value))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        let anti_affinity_terms = required_terms(pod_anti_affinity(pod));
+        let violated = anti_affinity_terms
+            .iter()
+            .zip(state.anti_affinity_counts.iter())
+            .any(|(term, counts)| count(term, counts) > 0);
+        if violated {
+            return Err("node(s) didn't match pod anti-affinity rules".to_string());
+        }
+
+        let affinity_terms = required_terms(pod_affinity(pod));
+        let satisfied = affinity_terms
+            .iter()
+            .zip(state.affinity_counts.iter())
+            .all(|(term, counts)| count(term, counts) > 0);
+        // The first pod of a group that has affinity to itself can go anywhere
+        // as long as the node has the topology keys
+        let first_of_group = state.affinity_counts.iter().all(HashMap::is_empty)
+            && affinity_terms.iter().all(|term| {
+                term_matches(term, pod) && node.topology_value(&term.topology_key).is_some()
+            });
+        if !satisfied && !first_of_group {
+            return Err("node(s) didn't match pod affinity rules".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl ScorePlugin for InterPodAffinity {
+    fn score(&self, state: &CycleState, _: &Pod, node: &NodeState) -> i64 {
+        state.read::<PreFilterState>(STATE_KEY).map_or(0, |state| {
+            state
+                .scores
+                .iter()
+                .filter(|((key, value), _)| node.topology_value(key) == Some(value.as_str()))
+                .map(|(_, score)| score)
+                .sum()
+        })
+    }
+
+    /// Scores can be negative because of anti-affinity, scale them by min and max
+    fn normalize_score(&self, _: &CycleState, _: &Pod, scores: &mut [i64]) {
+        let max = scores.iter().copied().max().unwrap_or_default();
+        let min = scores.iter().copied().min().unwrap_or_default();
+        for score in scores.iter_mut() {
+            *score = if max == min {
+                0
+            } else {
+                (*score - min) * MAX_NODE_SCORE / (max - min)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::objects::{pod::PodSpec, Labels, Metadata};
+    use serde_json::json;
+
+    use super::*;
+
+    fn labels(labels: &[(&str, &str)]) -> Labels {
+        Labels(
+            labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    fn pod(name: &str, app: &str, affinity: serde_json::Value) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: name.to_owned(),
+                labels: labels(&[("app", app)]),
+                ..Default::default()
+            },
+            spec: PodSpec {
+                affinity: Some(serde_json::from_value(affinity).unwrap()),
+                ..Default::default()
+            },
+            status: None,
+        }
+    }
+
+    /// A node in `zone` running `pods`, not in any zone if it's empty
+    fn node(name: &str, zone: &str, pods: &[Pod]) -> NodeState {
+        let mut node = NodeState {
+            name: name.to_owned(),
+            ..Default::default()
+        };
+        if !zone.is_empty() {
+            node.labels = labels(&[("zone", zone)]);
+        }
+        for pod in pods {
+            node.add_pod(pod);
+        }
+        node
+    }
+
+    fn term(app: &str) -> serde_json::Value {
+        json!({"labelSelector": {"matchLabels": {"app": app}}, "topologyKey": "zone"})
+    }
+
+    fn required(kind: &str, app: &str) -> serde_json::Value {
+        let mut affinity = serde_json::Map::new();
+        affinity.insert(
+            kind.to_owned(),
+            json!({"requiredDuringSchedulingIgnoredDuringExecution": [term(app)]}),
+        );
+        affinity.into()
+    }
+
+    /// Names of the nodes passing the filter
+    fn feasible(pod: &Pod, nodes: &[NodeState]) -> Vec<String> {
+        let mut state = CycleState::default();
+        let nodes = nodes.iter().collect::<Vec<_>>();
+        InterPodAffinity
+            .pre_filter(&mut state, pod, &nodes)
+            .unwrap();
+        nodes
+            .into_iter()
+            .filter(|node| InterPodAffinity.filter(&state, pod, node).is_ok())
+            .map(|node| node.name.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn required_affinity_needs_a_matching_pod_in_the_domain() {
+        let web = pod("web", "web", required("podAffinity", "db"));
+        let nodes = [
+            node("a-1", "a", &[pod("db", "db", json!({}))]),
+            node("a-2", "a", &[]),
+            node("b-1", "b", &[]),
+        ];
+        assert_eq!(feasible(&web, &nodes), ["a-1", "a-2"]);
+    }
+
+    #[test]
+    fn required_anti_affinity_excludes_domains_with_a_matching_pod() {
+        let web = pod("web-2", "web", required("podAntiAffinity", "web"));
+        let nodes = [
+            node("a-1", "a", &[pod("web-1", "web", json!({}))]),
+            node("a-2", "a", &[]),
+            node("b-1", "b", &[]),
+        ];
+        assert_eq!(feasible(&web, &nodes), ["b-1"]);
+    }
+
+    #[test]
+    fn anti_affinity_of_existing_pods_is_respected() {
+        let existing = pod("db", "db", required("podAntiAffinity", "web"));
+        let nodes = [node("a-1", "a", &[existing]), node("b-1", "b", &[])];
+        assert_eq!(feasible(&pod("web", "web", json!({})), &nodes), ["b-1"]);
+    }
+
+    #[test]
+    fn first_pod_of_a_group_with_affinity_to_itself_goes_anywhere_in_a_domain() {
+        let web = pod("web", "web", required("podAffinity", "web"));
+        let nodes = [node("a-1", "a", &[]), node("none", "", &[])];
+        assert_eq!(feasible(&web, &nodes), ["a-1"]);
+    }
+
+    #[test]
+    fn preferred_terms_score_domains_by_weight() {
+        let web = pod(
+            "web",
+            "web",
+            json!({
+                "podAffinity": {"preferredDuringSchedulingIgnoredDuringExecution": [
+                    {"weight": 10, "podAffinityTerm": term("db")},
+                ]},
+                "podAntiAffinity": {"preferredDuringSchedulingIgnoredDuringExecution": [
+                    {"weight": 5, "podAffinityTerm": term("web")},
+                ]},
+            }),
+        );
+        let nodes = [
+            node("a-1", "a", &[pod("db", "db", json!({}))]),
+            node("b-1", "b", &[pod("web-1", "web", json!({}))]),
+            node("c-1", "c", &[]),
+        ];
+        let mut state = CycleState::default();
+        InterPodAffinity
+            .pre_filter(&mut state, &web, &nodes.iter().collect::<Vec<_>>())
+            .unwrap();
+        let mut scores = nodes
+            .iter()
+            .map(|node| InterPodAffinity.score(&state, &web, node))
+            .collect::<Vec<_>>();
+        assert_eq!(scores, [10, -5, 0]);
+
+        InterPodAffinity.normalize_score(&state, &web, &mut scores);
+        assert_eq!(scores, [MAX_NODE_SCORE, 0, 5 * MAX_NODE_SCORE / 15]);
+    }
+}
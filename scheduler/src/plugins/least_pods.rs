@@ -0,0 +1,37 @@
+use resources::objects::pod::Pod;
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, Plugin, ScorePlugin, MAX_NODE_SCORE},
+};
+
+/// Prefer nodes running fewer pods
+pub struct LeastPods;
+
+impl LeastPods {
+    pub const NAME: &'static str = "LeastPods";
+}
+
+impl Plugin for LeastPods {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl ScorePlugin for LeastPods {
+    /// Raw score is the number of pods, reversed in normalization
+    fn score(&self, _: &CycleState, _: &Pod, node: &NodeState) -> i64 {
+        node.pod_count() as i64
+    }
+
+    fn normalize_score(&self, _: &CycleState, _: &Pod, scores: &mut [i64]) {
+        let max = scores.iter().copied().max().unwrap_or_default();
+        for score in scores.iter_mut() {
+            *score = if max == 0 {
+                MAX_NODE_SCORE
+            } else {
+                (max - *score) * MAX_NODE_SCORE / max
+            };
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use resources::client::{Api, Client};
+
+use crate::framework::{BindPlugin, FilterPlugin, PreFilterPlugin, ReservePlugin, ScorePlugin};
+
+pub mod default_binder;
+pub mod dummy;
+pub mod inter_pod_affinity;
+pub mod least_pods;
+pub mod node_affinity;
+pub mod node_pressure;
+pub mod node_ready;
+pub mod node_resources;
+pub mod node_selector;
+pub mod node_unschedulable;
+pub mod pod_topology_spread;
+pub mod taint_toleration;
+
+use default_binder::DefaultBinder;
+use dummy::Dummy;
+use inter_pod_affinity::InterPodAffinity;
+use least_pods::LeastPods;
+use node_affinity::NodeAffinity;
+use node_pressure::NodePressure;
+use node_ready::NodeReady;
+use node_resources::{
+    NodeResourcesBalancedAllocation, NodeResourcesFit, NodeResourcesLeastAllocated,
+};
+use node_selector::NodeSelector;
+use node_unschedulable::NodeUnschedulable;
+use pod_topology_spread::PodTopologySpread;
+use taint_toleration::TaintToleration;
+
+/// Create the pre-filter plugin registered as `name`
+pub fn new_pre_filter_plugin(name: &str) -> Option<Box<dyn PreFilterPlugin>> {
+    match name {
+        NodeResourcesFit::NAME => Some(Box::new(NodeResourcesFit)),
+        InterPodAffinity::NAME => Some(Box::new(InterPodAffinity)),
+        PodTopologySpread::NAME => Some(Box::new(PodTopologySpread)),
+        _ => None,
+    }
+}
+
+/// Create the filter plugin registered as `name`
+pub fn new_filter_plugin(name: &str) -> Option<Box<dyn FilterPlugin>> {
+    match name {
+        Dummy::NAME => Some(Box::new(Dummy)),
+        NodeReady::NAME => Some(Box::new(NodeReady)),
+        NodeUnschedulable::NAME => Some(Box::new(NodeUnschedulable)),
+        NodePressure::NAME => Some(Box::new(NodePressure)),
+        NodeSelector::NAME => Some(Box::new(NodeSelector)),
+        NodeAffinity::NAME => Some(Box::new(NodeAffinity)),
+        InterPodAffinity::NAME => Some(Box::new(InterPodAffinity)),
+        NodeResourcesFit::NAME => Some(Box::new(NodeResourcesFit)),
+        TaintToleration::NAME => Some(Box::new(TaintToleration)),
+        PodTopologySpread::NAME => Some(Box::new(PodTopologySpread)),
+        _ => None,
+    }
+}
+
+/// Create the score plugin registered as `name`
+pub fn new_score_plugin(name: &str) -> Option<Box<dyn ScorePlugin>> {
+    match name {
+        LeastPods::NAME => Some(Box::new(LeastPods)),
+        NodeAffinity::NAME => Some(Box::new(NodeAffinity)),
+        InterPodAffinity::NAME => Some(Box::new(InterPodAffinity)),
+        NodeResourcesLeastAllocated::NAME => Some(Box::new(NodeResourcesLeastAllocated)),
+        NodeResourcesBalancedAllocation::NAME => Some(Box::new(NodeResourcesBalancedAllocation)),
+        TaintToleration::NAME => Some(Box::new(TaintToleration)),
+        PodTopologySpread::NAME => Some(Box::new(PodTopologySpread)),
+        _ => None,
+    }
+}
+
+/// Create the reserve plugin registered as `name`
+pub fn new_reserve_plugin(_name: &str) -> Option<Box<dyn ReservePlugin>> {
+    // No reserve plugin is built in yet
+    None
+}
+
+/// Create the bind plugin registered as `name`, which talks to API server through `client`
+pub fn new_bind_plugin(name: &str, client: &Client) -> Option<Box<dyn BindPlugin>> {
+    match name {
+        DefaultBinder::NAME => Some(Box::new(DefaultBinder::new(Api::new(
+            client.to_owned(),
+            "pods",
+        )))),
+        _ => None,
+    }
+}
@@ -0,0 +1,171 @@
+use resources::objects::{affinity, pod::Pod};
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin, ScorePlugin, MAX_NODE_SCORE},
+};
+
+/// Filter nodes by the required node affinity of the pod,
+/// prefer nodes matching more of its preferred terms
+pub struct NodeAffinity;
+
+impl NodeAffinity {
+    pub const NAME: &'static str = "NodeAffinity";
+}
+
+fn node_affinity(pod: &Pod) -> Option<&affinity::NodeAffinity> {
+    pod.spec.affinity.as_ref()?.node_affinity.as_ref()
+}
+
+impl Plugin for NodeAffinity {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for NodeAffinity {
+    fn filter(&self, _: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        let required = node_affinity(pod).and_then(|affinity| {
+            affinity
+                .required_during_scheduling_ignored_during_execution
+                .as_ref()
+        });
+        match required {
+            Some(selector) if !selector.matches(&node.labels) => {
+                Err("node(s) didn't match node affinity".to_string())
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ScorePlugin for NodeAffinity {
+    /// Raw score is the sum of weights of matching preferred terms
+    fn score(&self, _: &CycleState, pod: &Pod, node: &NodeState) -> i64 {
+        node_affinity(pod).map_or(0, |affinity| {
+            affinity
+                .preferred_during_scheduling_ignored_during_execution
+                .iter()
+                .filter(|term| term.preference.matches(&node.labels))
+                .map(|term| term.weight)
+                .sum()
+        })
+    }
+
+    fn normalize_score(&self, _: &CycleState, _: &Pod, scores: &mut [i64]) {
+        let max = scores.iter().copied().max().unwrap_or_default();
+        if max > 0 {
+            for score in scores.iter_mut() {
+                *score = *score * MAX_NODE_SCORE / max;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::objects::{affinity::Affinity, pod::PodSpec, Labels, Metadata};
+    use serde_json::json;
+
+    use super::*;
+
+    fn pod(node_affinity: serde_json::Value) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: "pod".to_string(),
+                ..Default::default()
+            },
+            spec: PodSpec {
+                affinity: Some(Affinity {
+                    node_affinity: Some(serde_json::from_value(node_affinity).unwrap()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            status: None,
+        }
+    }
+
+    fn node(labels: &[(&str, &str)]) -> NodeState {
+        NodeState {
+            name: "node".to_string(),
+            labels: Labels(
+                labels
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn filter_requires_any_term_to_match() {
+        let pod = pod(json!({
+            "requiredDuringSchedulingIgnoredDuringExecution": {
+                "nodeSelectorTerms": [
+                    {"matchExpressions": [{"key": "zone", "operator": "In", "values": ["a"]}]},
+                    {"matchExpressions": [
+                        {"key": "zone", "operator": "In", "values": ["b"]},
+                        {"key": "ssd", "operator": "Exists"},
+                    ]},
+                ],
+            },
+        }));
+        let state = CycleState::default();
+        let filter =
+            |labels: &[(&str, &str)]| NodeAffinity.filter(&state, &pod, &node(labels)).is_ok();
+        assert!(filter(&[("zone", "a")]));
+        assert!(filter(&[("zone", "b"), ("ssd", "true")]));
+        assert!(!filter(&[("zone", "b")]));
+        assert!(!filter(&[]));
+    }
+
+    #[test]
+    fn filter_passes_without_required_affinity() {
+        let pod = pod(json!({}));
+        assert!(NodeAffinity
+            .filter(&CycleState::default(), &pod, &node(&[]))
+            .is_ok());
+    }
+
+    #[test]
+    fn score_sums_weights_of_matching_preferred_terms() {
+        let pod = pod(json!({
+            "preferredDuringSchedulingIgnoredDuringExecution": [
+                {
+                    "weight": 20,
+                    "preference": {
+                        "matchExpressions": [{"key": "zone", "operator": "In", "values": ["a"]}],
+                    },
+                },
+                {
+                    "weight": 60,
+                    "preference": {"matchExpressions": [{"key": "ssd", "operator": "Exists"}]},
+                },
+            ],
+        }));
+        let state = CycleState::default();
+        let mut scores = [
+            node(&[("zone", "a"), ("ssd", "true")]),
+            node(&[("ssd", "true")]),
+            node(&[("zone", "a")]),
+            node(&[]),
+        ]
+        .iter()
+        .map(|node| NodeAffinity.score(&state, &pod, node))
+        .collect::<Vec<_>>();
+        assert_eq!(scores, [80, 60, 20, 0]);
+
+        NodeAffinity.normalize_score(&state, &pod, &mut scores);
+        assert_eq!(
+            scores,
+            [
+                MAX_NODE_SCORE,
+                MAX_NODE_SCORE * 60 / 80,
+                MAX_NODE_SCORE * 20 / 80,
+                0
+            ]
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use resources::objects::pod::Pod;
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin},
+};
+
+/// Filter out nodes short of memory, disk or process IDs,
+/// where new pods would soon be evicted
+pub struct NodePressure;
+
+impl NodePressure {
+    pub const NAME: &'static str = "NodePressure";
+}
+
+impl Plugin for NodePressure {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for NodePressure {
+    fn filter(&self, _: &CycleState, _: &Pod, node: &NodeState) -> Result<(), String> {
+        match node.pressures.first() {
+            Some(pressure) => Err(format!("node(s) had {}", pressure)),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use resources::objects::pod::Pod;
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin},
+};
+
+/// Filter out nodes that are not ready
+pub struct NodeReady;
+
+impl NodeReady {
+    pub const NAME: &'static str = "NodeReady";
+}
+
+impl Plugin for NodeReady {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for NodeReady {
+    fn filter(&self, _: &CycleState, _: &Pod, node: &NodeState) -> Result<(), String> {
+        if node.is_ready {
+            Ok(())
+        } else {
+            Err("node(s) were not ready".to_string())
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use resources::objects::{metrics::Resource, pod::Pod};
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin, PreFilterPlugin, ScorePlugin, MAX_NODE_SCORE},
+};
+
+const STATE_KEY: &str = "PreFilterNodeResourcesFit";
+
+/// Total CPU (in milli cores) and memory (in bytes) requested by a pod
+#[derive(Debug, Clone, Copy)]
+struct PodRequests {
+    cpu: i64,
+    memory: i64,
+}
+
+impl PodRequests {
+    /// Read requests computed in PreFilter, compute them if PreFilter isn't enabled
+    fn of(state: &CycleState, pod: &Pod) -> PodRequests {
+        state
+            .read::<PodRequests>(STATE_KEY)
+            .copied()
+            .unwrap_or_else(|| PodRequests::compute(pod))
+    }
+
+    fn compute(pod: &Pod) -> PodRequests {
+        PodRequests {
+            cpu: pod.requests(&Resource::CPU),
+            memory: pod.requests(&Resource::Memory),
+        }
+    }
+}
+
+/// Fraction of CPU and memory of the node requested after placing the pod on it
+fn requested_fractions(requests: PodRequests, node: &NodeState) -> (f64, f64) {
+    (
+        fraction(node.requested_cpu + requests.cpu, node.allocatable_cpu),
+        fraction(
+            node.requested_memory + requests.memory,
+            node.allocatable_memory,
+        ),
+    )
+}
+
+fn fraction(requested: i64, allocatable: i64) -> f64 {
+    if allocatable == 0 {
+        return 1.0;
+    }
+    (requested as f64 / allocatable as f64).min(1.0)
+}
+
+/// Filter out nodes that don't have enough allocatable CPU or memory for the pod
+pub struct NodeResourcesFit;
+
+impl NodeResourcesFit {
+    pub const NAME: &'static str = "NodeResourcesFit";
+}
+
+impl Plugin for NodeResourcesFit {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl PreFilterPlugin for NodeResourcesFit {
+    fn pre_filter(
+        &self,
+        state: &mut CycleState,
+        pod: &Pod,
+        _: &[&NodeState],
+    ) -> Result<(), String> {
+        state.write(STATE_KEY, PodRequests::compute(pod));
+        Ok(())
+    }
+}
+
+impl FilterPlugin for NodeResourcesFit {
+    fn filter(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        let requests = PodRequests::of(state, pod);
+        if node.requested_cpu + requests.cpu > node.allocatable_cpu {
+            return Err("Insufficient cpu".to_string());
+        }
+        if node.requested_memory + requests.memory > node.allocatable_memory {
+            return Err("Insufficient memory".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Prefer nodes with more free CPU and memory
+pub struct NodeResourcesLeastAllocated;
+
+impl NodeResourcesLeastAllocated {
+    pub const NAME: &'static str = "NodeResourcesLeastAllocated";
+}
+
+impl Plugin for NodeResourcesLeastAllocated {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl ScorePlugin for NodeResourcesLeastAllocated {
+    fn score(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> i64 {
+        let (cpu, memory) = requested_fractions(PodRequests::of(state, pod), node);
+        ((2.0 - cpu - memory) / 2.0 * MAX_NODE_SCORE as f64) as i64
+    }
+}
+
+/// Prefer nodes whose CPU and memory usage are balanced
+pub struct NodeResourcesBalancedAllocation;
+
+impl NodeResourcesBalancedAllocation {
+    pub const NAME: &'static str = "NodeResourcesBalancedAllocation";
+}
+
+impl Plugin for NodeResourcesBalancedAllocation {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl ScorePlugin for NodeResourcesBalancedAllocation {
+    fn score(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> i64 {
+        let (cpu, memory) = requested_fractions(PodRequests::of(state, pod), node);
+        ((1.0 - (cpu - memory).abs()) * MAX_NODE_SCORE as f64) as i64
+    }
+}
@@ -0,0 +1,29 @@
+use resources::objects::pod::Pod;
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin},
+};
+
+/// Filter out nodes whose labels don't match `node_selector` of the pod
+pub struct NodeSelector;
+
+impl NodeSelector {
+    pub const NAME: &'static str = "NodeSelector";
+}
+
+impl Plugin for NodeSelector {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for NodeSelector {
+    fn filter(&self, _: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        if node.labels.matches(&pod.spec.node_selector) {
+            Ok(())
+        } else {
+            Err("node(s) didn't match node selector".to_string())
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use resources::objects::{
+    node::TAINT_NODE_UNSCHEDULABLE,
+    pod::Pod,
+    taint::{tolerated, Taint, TaintEffect},
+};
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin},
+};
+
+/// Filter out cordoned nodes,
+/// unless the pod tolerates the unschedulable taint
+pub struct NodeUnschedulable;
+
+impl NodeUnschedulable {
+    pub const NAME: &'static str = "NodeUnschedulable";
+}
+
+impl Plugin for NodeUnschedulable {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for NodeUnschedulable {
+    fn filter(&self, _: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        if !node.unschedulable {
+            return Ok(());
+        }
+        let taint = Taint {
+            key: TAINT_NODE_UNSCHEDULABLE.to_string(),
+            value: None,
+            effect: TaintEffect::NoSchedule,
+            time_added: None,
+        };
+        if tolerated(&pod.spec.tolerations, &taint) {
+            Ok(())
+        } else {
+            Err("node(s) were unschedulable".to_string())
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use resources::objects::{
+    affinity::{TopologySpreadConstraint, UnsatisfiableConstraintAction},
+    pod::Pod,
+};
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin, PreFilterPlugin, ScorePlugin, MAX_NODE_SCORE},
+};
+
+const STATE_KEY: &str = "PreFilterPodTopologySpread";
+
+/// For each topology spread constraint of the pod, in the same order,
+/// number of matching pods in each value of the topology key
+#[derive(Debug, Default)]
+struct PreFilterState(Vec<HashMap<String, i64>>);
+
+/// Only nodes the pod may run on count as topology domains
+fn eligible(pod: &Pod, node: &NodeState) -> bool {
+    let required_affinity = pod
+        .spec
+        .affinity
+        .as_ref()
+        .and_then(|affinity| affinity.node_affinity.as_ref())
+        .and_then(|affinity| {
+            affinity
+                .required_during_scheduling_ignored_during_execution
+                .as_ref()
+        });
+    node.labels.matches(&pod.spec.node_selector)
+        && required_affinity.map_or(true, |selector| selector.matches(&node.labels))
+}
+
+/// Constraints of the pod with the given action, along with their counts
+fn constraints<'a>(
+    pod: &'a Pod,
+    state: &'a PreFilterState,
+    action: UnsatisfiableConstraintAction,
+) -> impl Iterator<Item = (&'a TopologySpreadConstraint, &'a HashMap<String, i64>)> {
+    pod.spec
+        .topology_spread_constraints
+        .iter()
+        .zip(state.0.iter())
+        .filter(move |(constraint, _)| constraint.when_unsatisfiable == action)
+}
+
+/// Spread pods matching the same label selector evenly across topology domains,
+/// e.g. replicas of a ReplicaSet across nodes or zones.
+///
+/// Requires the PreFilter extension point to be enabled.
+pub struct PodTopologySpread;
+
+impl PodTopologySpread {
+    pub const NAME: &'static str = "PodTopologySpread";
+}
+
+impl Plugin for PodTopologySpread {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl PreFilterPlugin for PodTopologySpread {
+    fn pre_filter(
+        &self,
+        state: &mut CycleState,
+        pod: &Pod,
+        nodes: &[&NodeState],
+    ) -> Result<(), String> {
+        let constraints = &pod.spec.topology_spread_constraints;
+        let mut counts = vec![HashMap::new(); constraints.len()];
+        for node in nodes.iter().filter(|node| eligible(pod, node)) {
+            for (constraint, counts) in constraints.iter().zip(counts.iter_mut()) {
+                if let Some(value) = node.topology_value(&constraint.topology_key) {
+                    let matching = node
+                        .pods
+                        .values()
+                        .filter(|existing| {
+                            constraint.label_selector.matches(&existing.metadata.labels)
+                        })
+                        .count();
+                    *counts.entry(value.to_owned()).or_default() += matching as i64;
+                }
+            }
+        }
+        state.write(STATE_KEY, PreFilterState(counts));
+        Ok(())
+    }
+}
+
+impl FilterPlugin for PodTopologySpread {
+    /// Skew is the number of matching pods in the domain of the node,
+    /// including the pod itself if it matches,
+    /// minus the minimum number of matching pods in any domain.
+    fn filter(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        let state = match state.read::<PreFilterState>(STATE_KEY) {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        for (constraint, counts) in
+            constraints(pod, state, UnsatisfiableConstraintAction::DoNotSchedule)
+        {
+            let value = node
+                .topology_value(&constraint.topology_key)
+                .ok_or_else(|| {
+                    "node(s) didn't match pod topology spread constraints (missing required label)"
+                        .to_string()
+                })?;
+            let min_count = counts.values().copied().min().unwrap_or_default();
+            let count = counts.get(value).copied().unwrap_or_default();
+            let self_match = constraint.label_selector.matches(&pod.metadata.labels) as i64;
+            if count + self_match - min_count > constraint.max_skew {
+                return Err("node(s) didn't match pod topology spread constraints".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ScorePlugin for PodTopologySpread {
+    /// Raw score is the number of matching pods in the domains of the node, lower is better,
+    /// -1 if the node lacks any of the topology keys.
+    fn score(&self, state: &CycleState, pod: &Pod, node: &NodeState) -> i64 {
+        let state = match state.read::<PreFilterState>(STATE_KEY) {
+            Some(state) => state,
+            None => return 0,
+        };
+        let mut score = 0;
+        for (constraint, counts) in
+            constraints(pod, state, UnsatisfiableConstraintAction::ScheduleAnyway)
+        {
+            match node.topology_value(&constraint.topology_key) {
+                Some(value) => score += counts.get(value).copied().unwrap_or_default(),
+                None => return -1,
+            }
+        }
+        score
+    }
+
+    /// Nodes with fewer matching pods get higher scores,
+    /// nodes lacking topology keys get the lowest.
+    fn normalize_score(&self, _: &CycleState, _: &Pod, scores: &mut [i64]) {
+        let valid = scores.iter().copied().filter(|score| *score >= 0);
+        let max = valid.clone().max().unwrap_or_default();
+        let min = valid.min().unwrap_or_default();
+        for score in scores.iter_mut() {
+            *score = if *score < 0 {
+                0
+            } else if max == min {
+                MAX_NODE_SCORE
+            } else {
+                (max - *score) * MAX_NODE_SCORE / (max - min)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::objects::{pod::PodSpec, Labels, Metadata};
+    use serde_json::json;
+
+    use super::*;
+
+    fn labels(labels: &[(&str, &str)]) -> Labels {
+        Labels(
+            labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    fn pod(name: &str, constraints: Vec<TopologySpreadConstraint>) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: name.to_owned(),
+                labels: labels(&[("app", "web")]),
+                ..Default::default()
+            },
+            spec: PodSpec {
+                topology_spread_constraints: constraints,
+                ..Default::default()
+            },
+            status: None,
+        }
+    }
+
+    /// Spread pods labeled app=web across zones
+    fn constraint(
+        max_skew: i64,
+        action: UnsatisfiableConstraintAction,
+    ) -> TopologySpreadConstraint {
+        TopologySpreadConstraint {
+            max_skew,
+            topology_key: "zone".to_string(),
+            when_unsatisfiable: action,
+            label_selector: serde_json::from_value(json!({"matchLabels": {"app": "web"}})).unwrap(),
+        }
+    }
+
+    /// A node labeled `node_labels` running `replicas` pods labeled app=web
+    fn node(name: &str, node_labels: &[(&str, &str)], replicas: usize) -> NodeState {
+        let mut node = NodeState {
+            name: name.to_owned(),
+            labels: labels(node_labels),
+            ..Default::default()
+        };
+        for i in 0..replicas {
+            node.add_pod(&pod(&format!("{}-web-{}", name, i), vec![]));
+        }
+        node
+    }
+
+    fn pre_filter(pod: &Pod, nodes: &[NodeState]) -> CycleState {
+        let mut state = CycleState::default();
+        PodTopologySpread
+            .pre_filter(&mut state, pod, &nodes.iter().collect::<Vec<_>>())
+            .unwrap();
+        state
+    }
+
+    /// Names of the nodes passing the filter
+    fn feasible(pod: &Pod, nodes: &[NodeState]) -> Vec<String> {
+        let state = pre_filter(pod, nodes);
+        nodes
+            .iter()
+            .filter(|node| PodTopologySpread.filter(&state, pod, node).is_ok())
+            .map(|node| node.name.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn filter_keeps_skew_within_max_skew() {
+        let nodes = [
+            node("a-1", &[("zone", "a")], 2),
+            node("b-1", &[("zone", "b")], 1),
+            node("c-1", &[("zone", "c")], 0),
+            node("none", &[], 0),
+        ];
+        let new_pod = |max_skew| {
+            pod(
+                "web",
+                vec![constraint(
+                    max_skew,
+                    UnsatisfiableConstraintAction::DoNotSchedule,
+                )],
+            )
+        };
+        assert_eq!(feasible(&new_pod(1), &nodes), ["c-1"]);
+        assert_eq!(feasible(&new_pod(2), &nodes), ["b-1", "c-1"]);
+    }
+
+    #[test]
+    fn only_nodes_the_pod_may_run_on_count_as_domains() {
+        let nodes = [
+            node("a-1", &[("zone", "a"), ("disk", "ssd")], 1),
+            node("b-1", &[("zone", "b"), ("disk", "ssd")], 1),
+            node("c-1", &[("zone", "c")], 0),
+        ];
+        let mut web = pod(
+            "web",
+            vec![constraint(1, UnsatisfiableConstraintAction::DoNotSchedule)],
+        );
+        web.spec.node_selector = labels(&[("disk", "ssd")]);
+        let feasible = feasible(&web, &nodes);
+        assert!(feasible.contains(&"a-1".to_string()));
+        assert!(feasible.contains(&"b-1".to_string()));
+    }
+
+    #[test]
+    fn score_prefers_domains_with_fewer_matching_pods() {
+        let nodes = [
+            node("a-1", &[("zone", "a")], 2),
+            node("b-1", &[("zone", "b")], 1),
+            node("c-1", &[("zone", "c")], 0),
+            node("none", &[], 0),
+        ];
+        let web = pod(
+            "web",
+            vec![constraint(1, UnsatisfiableConstraintAction::ScheduleAnyway)],
+        );
+        // Only DoNotSchedule constraints are enforced by the filter
+        assert_eq!(feasible(&web, &nodes), ["a-1", "b-1", "c-1", "none"]);
+
+        let state = pre_filter(&web, &nodes);
+        let mut scores = nodes
+            .iter()
+            .map(|node| PodTopologySpread.score(&state, &web, node))
+            .collect::<Vec<_>>();
+        assert_eq!(scores, [2, 1, 0, -1]);
+
+        PodTopologySpread.normalize_score(&state, &web, &mut scores);
+        assert_eq!(scores, [0, MAX_NODE_SCORE / 2, MAX_NODE_SCORE, 0]);
+    }
+}
@@ -0,0 +1,166 @@
+use resources::objects::{
+    pod::Pod,
+    taint::{tolerated, TaintEffect},
+};
+
+use crate::{
+    cache::NodeState,
+    framework::{CycleState, FilterPlugin, Plugin, ScorePlugin, MAX_NODE_SCORE},
+};
+
+/// Filter out nodes with NoSchedule or NoExecute taints the pod doesn't tolerate,
+/// prefer nodes with fewer untolerated PreferNoSchedule taints
+pub struct TaintToleration;
+
+impl TaintToleration {
+    pub const NAME: &'static str = "TaintToleration";
+}
+
+impl Plugin for TaintToleration {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+impl FilterPlugin for TaintToleration {
+    fn filter(&self, _: &CycleState, pod: &Pod, node: &NodeState) -> Result<(), String> {
+        let untolerated = node.taints.iter().find(|taint| {
+            taint.effect != TaintEffect::PreferNoSchedule
+                && !tolerated(&pod.spec.tolerations, taint)
+        });
+        match untolerated {
+            Some(taint) => Err(format!(
+                "node(s) had untolerated taint {{{}: {}}}",
+                taint.key,
+                taint.value.as_deref().unwrap_or_default()
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ScorePlugin for TaintToleration {
+    /// Raw score is the number of untolerated PreferNoSchedule taints,
+    /// reversed in normalization
+    fn score(&self, _: &CycleState, pod: &Pod, node: &NodeState) -> i64 {
+        node.taints
+            .iter()
+            .filter(|taint| {
+                taint.effect == TaintEffect::PreferNoSchedule
+                    && !tolerated(&pod.spec.tolerations, taint)
+            })
+            .count() as i64
+    }
+
+    fn normalize_score(&self, _: &CycleState, _: &Pod, scores: &mut [i64]) {
+        let max = scores.iter().copied().max().unwrap_or_default();
+        for score in scores.iter_mut() {
+            *score = if max == 0 {
+                MAX_NODE_SCORE
+            } else {
+                (max - *score) * MAX_NODE_SCORE / max
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::objects::{
+        pod::PodSpec,
+        taint::{Taint, Toleration, TolerationOperator},
+        Metadata,
+    };
+
+    use super::*;
+
+    fn pod(tolerations: Vec<Toleration>) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: "pod".to_string(),
+                ..Default::default()
+            },
+            spec: PodSpec {
+                tolerations,
+                ..Default::default()
+            },
+            status: None,
+        }
+    }
+
+    /// Tolerate taints with `key` of any value and effect
+    fn tolerate(key: &str) -> Toleration {
+        Toleration {
+            key: Some(key.to_owned()),
+            operator: TolerationOperator::Exists,
+            ..Default::default()
+        }
+    }
+
+    /// A node with taints of the form "key=value:Effect"
+    fn node(taints: &[&str]) -> NodeState {
+        NodeState {
+            name: "node".to_string(),
+            taints: taints
+                .iter()
+                .map(|taint| taint.parse::<Taint>().unwrap())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn filter_rejects_untolerated_hard_taints() {
+        let state = CycleState::default();
+        let filter =
+            |pod: &Pod, taints: &[&str]| TaintToleration.filter(&state, pod, &node(taints));
+        let intolerant = pod(vec![]);
+        assert!(filter(&intolerant, &[]).is_ok());
+        assert!(filter(&intolerant, &["gpu=true:PreferNoSchedule"]).is_ok());
+        assert_eq!(
+            filter(&intolerant, &["gpu=true:NoSchedule"]),
+            Err("node(s) had untolerated taint {gpu: true}".to_string())
+        );
+        assert!(filter(&intolerant, &["maintenance:NoExecute"]).is_err());
+
+        let tolerant = pod(vec![tolerate("gpu"), tolerate("maintenance")]);
+        assert!(filter(&tolerant, &["gpu=true:NoSchedule", "maintenance:NoExecute"]).is_ok());
+        assert!(filter(
+            &tolerant,
+            &["gpu=true:NoSchedule", "dedicated=db:NoSchedule"]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn score_prefers_fewer_untolerated_soft_taints() {
+        let pod = pod(vec![tolerate("gpu")]);
+        let state = CycleState::default();
+        let nodes = [
+            node(&["gpu=true:PreferNoSchedule", "spot=true:PreferNoSchedule"]),
+            node(&["spot=true:PreferNoSchedule", "old=true:PreferNoSchedule"]),
+            node(&["old=true:PreferNoSchedule", "dedicated=db:NoSchedule"]),
+            node(&["gpu=true:PreferNoSchedule"]),
+        ];
+        let mut scores = nodes
+            .iter()
+            .map(|node| TaintToleration.score(&state, &pod, node))
+            .collect::<Vec<_>>();
+        assert_eq!(scores, [1, 2, 1, 0]);
+
+        TaintToleration.normalize_score(&state, &pod, &mut scores);
+        assert_eq!(
+            scores,
+            [MAX_NODE_SCORE / 2, 0, MAX_NODE_SCORE / 2, MAX_NODE_SCORE]
+        );
+    }
+
+    #[test]
+    fn all_nodes_score_the_max_without_soft_taints() {
+        let pod = pod(vec![]);
+        let state = CycleState::default();
+        let mut scores = vec![0, 0];
+        TaintToleration.normalize_score(&state, &pod, &mut scores);
+        assert_eq!(scores, [MAX_NODE_SCORE, MAX_NODE_SCORE]);
+    }
+}
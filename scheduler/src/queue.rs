@@ -0,0 +1,347 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+
+use resources::objects::{pod::Pod, Object};
+
+/// Backoff after the first failed attempt, doubled for each further failure
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+/// Unschedulable pods are retried after staying in the queue for this long,
+/// in case no cluster event ever makes them schedulable
+const UNSCHEDULABLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Higher priority first, then first in first out
+type QueueKey = (Reverse<i32>, u64);
+
+/// A pod waiting to be scheduled
+pub struct QueuedPod {
+    pub pod: Pod,
+    /// Number of failed scheduling attempts
+    pub attempts: u32,
+    /// When the last attempt failed, or when the pod is added
+    timestamp: Instant,
+}
+
+impl QueuedPod {
+    fn new(pod: Pod) -> Self {
+        QueuedPod {
+            pod,
+            attempts: 0,
+            timestamp: Instant::now(),
+        }
+    }
+
+    fn backoff_expiry(&self) -> Instant {
+        if self.attempts == 0 {
+            return self.timestamp;
+        }
+        let backoff = INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(self.attempts - 1))
+            .min(MAX_BACKOFF);
+        self.timestamp + backoff
+    }
+
+    fn failed(&mut self) {
+        self.attempts += 1;
+        self.timestamp = Instant::now();
+    }
+}
+
+/// Pods waiting to be scheduled.
+///
+/// New pods go to the active queue, ordered by priority.
+/// Pods that don't fit on any node wait in the unschedulable queue
+/// until a cluster event may make room for them,
+/// then they wait in the backoff queue until their backoff expires,
+/// so that failing pods don't starve the others.
+#[derive(Default)]
+pub struct SchedulingQueue {
+    active: BTreeMap<QueueKey, QueuedPod>,
+    /// Key of each pod in the active queue, by name
+    active_keys: HashMap<String, QueueKey>,
+    /// Keep the arrival order of pods with the same priority
+    next_seq: u64,
+    backoff: HashMap<String, QueuedPod>,
+    unschedulable: HashMap<String, QueuedPod>,
+}
+
+impl SchedulingQueue {
+    /// Add a new pod to the active queue, or update it if it's queued already
+    pub fn add(&mut self, pod: Pod) {
+        let name = pod.name().to_owned();
+        if let Some(queued) = self.backoff.get_mut(&name) {
+            queued.pod = pod;
+        } else if let Some(mut queued) = self.unschedulable.remove(&name) {
+            // e.g. tolerations added, worth another try
+            let spec_changed = queued.pod.spec != pod.spec;
+            queued.pod = pod;
+            if spec_changed {
+                self.move_to_active_or_backoff(queued);
+            } else {
+                self.unschedulable.insert(name, queued);
+            }
+        } else {
+            let queued = match self
+                .active_keys
+                .get(&name)
+                .and_then(|key| self.active.get(key))
+            {
+                Some(old) => QueuedPod {
+                    pod,
+                    attempts: old.attempts,
+                    timestamp: old.timestamp,
+                },
+                None => QueuedPod::new(pod),
+            };
+            self.push_active(queued);
+        }
+    }
+
+    /// Put back a pod that doesn't fit on any node
+    pub fn add_unschedulable(&mut self, mut queued: QueuedPod) {
+        queued.failed();
+        self.unschedulable
+            .insert(queued.pod.name().to_owned(), queued);
+    }
+
+    /// Put back a pod that failed for other reasons, e.g. binding,
+    /// to be retried once its backoff expires
+    pub fn add_backoff(&mut self, mut queued: QueuedPod) {
+        queued.failed();
+        self.backoff.insert(queued.pod.name().to_owned(), queued);
+    }
+
+    /// Remove the pod from the queue if it's queued
+    pub fn delete(&mut self, name: &str) {
+        if let Some(key) = self.active_keys.remove(name) {
+            self.active.remove(&key);
+        }
+        self.backoff.remove(name);
+        self.unschedulable.remove(name);
+    }
+
+    /// Take the pod with the highest priority from the active queue
+    pub fn pop(&mut self) -> Option<QueuedPod> {
+        let key = *self.active.keys().next()?;
+        let queued = self.active.remove(&key)?;
+        self.active_keys.remove(queued.pod.name());
+        Some(queued)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Retry all unschedulable pods on a cluster event which may make room for them,
+    /// e.g. a node is added or a pod is deleted.
+    pub fn move_all_to_active_or_backoff(&mut self) {
+        let pods = self
+            .unschedulable
+            .drain()
+            .map(|(_, queued)| queued)
+            .collect::<Vec<_>>();
+        for queued in pods {
+            self.move_to_active_or_backoff(queued);
+        }
+    }
+
+    /// Move pods whose backoff expired to the active queue,
+    /// and retry pods which have been unschedulable for too long.
+    pub fn flush(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .backoff
+            .iter()
+            .filter(|(_, queued)| queued.backoff_expiry() <= now)
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<_>>();
+        for name in expired {
+            if let Some(queued) = self.backoff.remove(&name) {
+                self.push_active(queued);
+            }
+        }
+
+        let timed_out = self
+            .unschedulable
+            .iter()
+            .filter(|(_, queued)| now.duration_since(queued.timestamp) >= UNSCHEDULABLE_TIMEOUT)
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<_>>();
+        for name in timed_out {
+            if let Some(queued) = self.unschedulable.remove(&name) {
+                self.move_to_active_or_backoff(queued);
+            }
+        }
+    }
+
+    fn move_to_active_or_backoff(&mut self, queued: QueuedPod) {
+        if queued.backoff_expiry() <= Instant::now() {
+            self.push_active(queued);
+        } else {
+            self.backoff.insert(queued.pod.name().to_owned(), queued);
+        }
+    }
+
+    /// Insert the pod into the active queue,
+    /// keeping its position if it's there already with the same priority.
+    fn push_active(&mut self, queued: QueuedPod) {
+        let name = queued.pod.name().to_owned();
+        let priority = Reverse(queued.pod.priority());
+        let key = match self.active_keys.get(&name).copied() {
+            Some(key) if key.0 == priority => key,
+            old_key => {
+                if let Some(old_key) = old_key {
+                    self.active.remove(&old_key);
+                }
+                self.next_seq += 1;
+                (priority, self.next_seq)
+            },
+        };
+        self.active_keys.insert(name, key);
+        self.active.insert(key, queued);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use resources::objects::{pod::PodSpec, Metadata};
+
+    use super::*;
+
+    fn pod(name: &str, priority: i32) -> Pod {
+        Pod {
+            metadata: Metadata {
+                name: name.to_owned(),
+                ..Default::default()
+            },
+            spec: PodSpec {
+                priority: Some(priority),
+                ..Default::default()
+            },
+            status: None,
+        }
+    }
+
+    fn pop_names(queue: &mut SchedulingQueue) -> Vec<String> {
+        std::iter::from_fn(|| queue.pop())
+            .map(|queued| queued.pod.name().to_owned())
+            .collect()
+    }
+
+    /// Pretend the last attempt failed `elapsed` ago
+    fn age(queued: &mut QueuedPod, elapsed: Duration) {
+        queued.timestamp = Instant::now() - elapsed;
+    }
+
+    #[test]
+    fn pops_higher_priority_first_then_in_arrival_order() {
+        let mut queue = SchedulingQueue::default();
+        queue.add(pod("low", 0));
+        queue.add(pod("high-1", 10));
+        queue.add(pod("mid", 5));
+        queue.add(pod("high-2", 10));
+        assert_eq!(pop_names(&mut queue), ["high-1", "high-2", "mid", "low"]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn adding_a_queued_pod_updates_it_in_place() {
+        let mut queue = SchedulingQueue::default();
+        queue.add(pod("a", 0));
+        queue.add(pod("b", 0));
+        queue.add(pod("a", 0));
+        assert_eq!(pop_names(&mut queue), ["a", "b"]);
+
+        // A new priority moves it to its new position
+        queue.add(pod("a", 0));
+        queue.add(pod("b", 0));
+        queue.add(pod("b", 1));
+        assert_eq!(pop_names(&mut queue), ["b", "a"]);
+    }
+
+    #[test]
+    fn backoff_doubles_from_one_second_up_to_ten() {
+        let mut queued = QueuedPod::new(pod("a", 0));
+        assert_eq!(queued.backoff_expiry(), queued.timestamp);
+        let mut backoffs = Vec::new();
+        for _ in 0..6 {
+            queued.failed();
+            backoffs.push((queued.backoff_expiry() - queued.timestamp).as_secs());
+        }
+        assert_eq!(backoffs, [1, 2, 4, 8, 10, 10]);
+    }
+
+    #[test]
+    fn failed_pods_wait_for_their_backoff() {
+        let mut queue = SchedulingQueue::default();
+        queue.add(pod("a", 0));
+        let queued = queue.pop().unwrap();
+        queue.add_backoff(queued);
+        queue.flush();
+        assert!(queue.is_empty());
+
+        age(queue.backoff.get_mut("a").unwrap(), INITIAL_BACKOFF);
+        queue.flush();
+        assert_eq!(pop_names(&mut queue), ["a"]);
+    }
+
+    #[test]
+    fn unschedulable_pods_are_retried_after_timeout() {
+        let mut queue = SchedulingQueue::default();
+        queue.add(pod("a", 0));
+        let queued = queue.pop().unwrap();
+        queue.add_unschedulable(queued);
+        age(
+            queue.unschedulable.get_mut("a").unwrap(),
+            Duration::from_secs(30),
+        );
+        queue.flush();
+        assert!(queue.is_empty());
+
+        age(
+            queue.unschedulable.get_mut("a").unwrap(),
+            UNSCHEDULABLE_TIMEOUT,
+        );
+        queue.flush();
+        assert_eq!(pop_names(&mut queue), ["a"]);
+    }
+
+    #[test]
+    fn unschedulable_pods_are_retried_when_their_spec_changes() {
+        let mut queue = SchedulingQueue::default();
+        queue.add(pod("a", 0));
+        let queued = queue.pop().unwrap();
+        queue.add_unschedulable(queued);
+        age(queue.unschedulable.get_mut("a").unwrap(), INITIAL_BACKOFF);
+
+        queue.add(pod("a", 0));
+        assert!(queue.is_empty());
+        let mut changed = pod("a", 0);
+        changed.spec.scheduler_name = Some("other".to_string());
+        queue.add(changed);
+        assert_eq!(pop_names(&mut queue), ["a"]);
+    }
+
+    #[test]
+    fn deleted_pods_leave_every_queue() {
+        let mut queue = SchedulingQueue::default();
+        queue.add(pod("backoff", 0));
+        queue.add(pod("unschedulable", 0));
+        let queued = queue.pop().unwrap();
+        queue.add_backoff(queued);
+        let queued = queue.pop().unwrap();
+        queue.add_unschedulable(queued);
+        queue.add(pod("active", 0));
+
+        for name in ["active", "backoff", "unschedulable"] {
+            queue.delete(name);
+        }
+        queue.move_all_to_active_or_backoff();
+        assert!(queue.is_empty());
+        assert!(queue.backoff.is_empty());
+        assert!(queue.unschedulable.is_empty());
+    }
+}
@@ -1,42 +1,82 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
 use anyhow::Result;
 use resources::{
-    models,
-    objects::{binding::Binding, object_reference::ObjectReference, pod::Pod, KubeObject, Object},
+    client::Api,
+    objects::{
+        condition::Condition,
+        pod::{Pod, PodConditionType},
+        pod_group::{pod_group_name, PodGroup, PodGroupConditionType, PodGroupPhase},
+        Object,
+    },
 };
 use tokio::{
     select,
-    sync::mpsc::{Receiver, Sender},
+    sync::mpsc::{self, Receiver, Sender},
+    time,
+};
+
+use crate::{
+    cache::Cache,
+    framework::{CycleState, FitError, Framework, ScheduleError},
+    gang::{Gang, WaitingPod},
+    informer::ResyncNotification,
+    queue::{QueuedPod, SchedulingQueue},
+    NodeUpdate, PodUpdate,
 };
 
-use crate::{cache::Cache, informer::ResyncNotification, NodeUpdate, PodUpdate, CONFIG};
+/// Interval to move pods whose backoff expired back to the active queue
+const QUEUE_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
 
-pub struct Scheduler<T>
-where
-    T: Fn(&Pod, &Cache) -> Option<ObjectReference>,
-{
+/// Outcome of binding in the background
+enum BindingResult {
+    Pod(WaitingPod, Result<()>),
+    /// Members of a pod group bound together
+    Gang {
+        group_name: String,
+        min_member: u32,
+        /// Members bound before
+        bound: u32,
+        results: Vec<(WaitingPod, Result<()>)>,
+    },
+}
+
+pub struct Scheduler {
+    /// Only pods with this `schedulerName` are scheduled
+    scheduler_name: String,
     cache: Cache,
-    algorithm: T,
-    client: reqwest::Client,
+    framework: Arc<Framework>,
+    queue: SchedulingQueue,
+    /// Members of pod groups waiting for the rest of their group, by group name
+    gangs: HashMap<String, Gang>,
+    pod_api: Api<Pod>,
+    pod_group_api: Api<PodGroup>,
     resync_rx: Receiver<ResyncNotification>,
-    pod_queue_tx: Sender<PodUpdate>,
+    binding_tx: Sender<BindingResult>,
+    binding_rx: Receiver<BindingResult>,
 }
 
-impl<T> Scheduler<T>
-where
-    T: Fn(&Pod, &Cache) -> Option<ObjectReference>,
-{
+impl Scheduler {
     pub fn new(
-        algorithm: T,
+        scheduler_name: String,
+        framework: Framework,
         cache: Cache,
+        pod_api: Api<Pod>,
+        pod_group_api: Api<PodGroup>,
         resync_rx: Receiver<ResyncNotification>,
-        pod_queue_tx: Sender<PodUpdate>,
-    ) -> Scheduler<T> {
+    ) -> Scheduler {
+        let (binding_tx, binding_rx) = mpsc::channel(16);
         Scheduler {
+            scheduler_name,
             cache,
-            algorithm,
-            client: reqwest::Client::new(),
+            framework: Arc::new(framework),
+            queue: SchedulingQueue::default(),
+            gangs: HashMap::new(),
+            pod_api,
+            pod_group_api,
             resync_rx,
-            pod_queue_tx,
+            binding_tx,
+            binding_rx,
         }
     }
 
@@ -48,21 +88,27 @@ where
         tracing::info!("Scheduler started");
         self.cache.refresh().await;
 
+        let mut flush_interval = time::interval(QUEUE_FLUSH_INTERVAL);
         loop {
+            // Catch up with cluster changes before scheduling the next pod
             select! {
+                biased;
                 Some(update) = pod_queue.recv() => {
                     self.handle_pod_change(update).await;
                 },
                 Some(update) = node_queue.recv() => {
                     self.handle_node_change(update).await;
                 },
+                Some(result) = self.binding_rx.recv() => {
+                    self.handle_binding_result(result).await;
+                },
                 Some(notification) = self.resync_rx.recv() => {
                     match notification {
                         ResyncNotification::EnqueuePods => {
                             let store = self.cache.pod_cache.read().await;
                             for pod in store.values() {
-                                if self.pod_queue_tx.send(PodUpdate::Add(pod.to_owned())).await.is_err() {
-                                    tracing::error!("Failed to send pod {} to work queue", pod.name());
+                                if self.needs_scheduling(pod) {
+                                    self.queue.add(pod.to_owned());
                                 }
                             }
                         },
@@ -71,74 +117,508 @@ where
                         }
                     }
                 },
+                _ = flush_interval.tick() => {
+                    self.queue.flush();
+                    self.cache.cleanup_assumed_pods();
+                    self.expire_gangs().await;
+                },
+                _ = std::future::ready(()), if !self.queue.is_empty() => {
+                    if let Some(queued) = self.queue.pop() {
+                        self.schedule_one(queued).await;
+                    }
+                },
             }
         }
     }
 
-    async fn bind(&self, pod: &Pod, node: ObjectReference) -> Result<()> {
-        let binding = KubeObject::Binding(Binding {
-            metadata: pod.metadata.clone(),
-            target: node,
-        });
+    /// Record why the pod can't be scheduled in its PodScheduled condition,
+    /// along with the node nominated for it if preemption succeeded.
+    async fn mark_unschedulable(
+        &self,
+        pod: &Pod,
+        fit_error: &FitError,
+        nominated_node_name: Option<String>,
+    ) -> Result<()> {
+        // The queued copy may be stale, don't revert changes made since it was queued
+        let mut pod = match self.pod_api.get(pod.name()).await {
+            Ok(pod) => pod,
+            Err(e) if e.is_not_found() => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if pod.spec.node_name.is_some() {
+            return Ok(());
+        }
+        let status = pod.status.get_or_insert_with(Default::default);
+        let condition = Condition::new(PodConditionType::PodScheduled, false)
+            .with_reason("Unschedulable", fit_error);
+        let mut changed = status.conditions.set(condition);
+        if nominated_node_name.is_some() && status.nominated_node_name != nominated_node_name {
+            status.nominated_node_name = nominated_node_name;
+            changed = true;
+        }
+        if !changed {
+            return Ok(());
+        }
 
-        let url = CONFIG.api_server_endpoint.join("api/v1/bindings")?;
-        let res = self.client.post(url).json(&binding).send().await?;
+        self.pod_api.replace(&pod).await?;
+        Ok(())
+    }
 
-        if let Err(err) = res.error_for_status_ref() {
-            let err_res = res.json::<models::ErrResponse>().await?;
-            tracing::error!(
-                "status {}, msg: {}, cause: {}",
-                err.status().unwrap_or_default(),
-                err_res.msg,
-                err_res.cause.unwrap_or_else(|| "None".to_string())
+    /// Evict pods of lower priority to make room for the pod,
+    /// return the node nominated for it.
+    ///
+    /// Victims are deleted through the API server,
+    /// so that kubelet stops them within their termination grace period.
+    async fn preempt(&self, state: &CycleState, pod: &Pod, fit_error: &FitError) -> Option<String> {
+        if fit_error.unresolvable {
+            return None;
+        }
+        let candidate = self.framework.preempt(state, pod, &self.cache)?;
+        for victim in candidate.victims.iter() {
+            if let Err(e) = self.delete_pod(victim).await {
+                tracing::error!("Failed to preempt pod {}: {:#}", victim.name(), e);
+                return None;
+            }
+            tracing::info!(
+                "Preempted pod {} on node {} for pod {}",
+                victim.name(),
+                candidate.node_name,
+                pod.name()
             );
         }
+        Some(candidate.node_name)
+    }
 
-        Ok(())
+    async fn delete_pod(&self, pod: &Pod) -> Result<()> {
+        match self.pod_api.delete(pod.name()).await {
+            // Already gone, which is what preemption wants
+            Err(e) if e.is_not_found() => Ok(()),
+            result => Ok(result?),
+        }
     }
 
     async fn handle_pod_change(&mut self, update: PodUpdate) {
         match update {
-            PodUpdate::Add(pod) => self.handle_pod_add(pod).await,
-            PodUpdate::Update(..) => {},
-            PodUpdate::Delete(pod) => self.cache.handle_pod_delete(pod).await,
+            PodUpdate::Add(pod) => {
+                self.cache.update_nominated_pod(&pod);
+                if pod_group_name(&pod).is_some() {
+                    // Its group may have enough members now
+                    self.queue.move_all_to_active_or_backoff();
+                }
+                if let Some(node_name) = &pod.spec.node_name {
+                    self.cache.handle_pod_add(pod.to_owned(), node_name).await;
+                } else if self.needs_scheduling(&pod) {
+                    self.queue.add(pod);
+                }
+            },
+            PodUpdate::Update(_, new_pod) => {
+                self.cache.update_nominated_pod(&new_pod);
+                if let Some(node_name) = &new_pod.spec.node_name {
+                    self.queue.delete(new_pod.name());
+                    self.cache
+                        .handle_pod_add(new_pod.to_owned(), node_name)
+                        .await;
+                } else if self.needs_scheduling(&new_pod) {
+                    self.queue.add(new_pod);
+                }
+            },
+            PodUpdate::Delete(pod) => {
+                self.queue.delete(pod.name());
+                self.remove_waiting_pod(&pod);
+                self.cache.remove_nominated_pod(&pod);
+                if pod.spec.node_name.is_some() {
+                    // Resources are released, e.g. preemption victims are gone
+                    self.queue.move_all_to_active_or_backoff();
+                }
+                self.cache.handle_pod_delete(pod).await;
+            },
         };
     }
 
+    /// Whether the pod is unscheduled and meant for this scheduler
+    fn needs_scheduling(&self, pod: &Pod) -> bool {
+        pod.spec.node_name.is_none()
+            && pod.spec.scheduler_name() == self.scheduler_name
+            && !self.cache.is_assumed(pod)
+    }
+
     async fn handle_node_change(&mut self, update: NodeUpdate) {
         match update {
-            NodeUpdate::Add(node) => self.cache.handle_node_add(node).await,
-            NodeUpdate::Update(_, new_node) => self.cache.handle_node_update(new_node).await,
+            NodeUpdate::Add(node) => {
+                self.cache.handle_node_add(node).await;
+                self.queue.move_all_to_active_or_backoff();
+            },
+            NodeUpdate::Update(_, new_node) => {
+                self.cache.handle_node_update(new_node).await;
+                self.queue.move_all_to_active_or_backoff();
+            },
             NodeUpdate::Delete(node) => self.cache.handle_node_delete(node).await,
         };
     }
 
-    async fn handle_pod_add(&mut self, pod: Pod) {
+    /// Try to schedule a pod from the queue,
+    /// put it back to be retried later if it fails.
+    async fn schedule_one(&mut self, queued: QueuedPod) {
+        let pod = &queued.pod;
         let pod_name = pod.name().to_owned();
-        tracing::info!("Scheduling pod: {}", pod_name);
-        if pod.spec.node_name.is_some() {
-            tracing::debug!("Pod {} already scheduled", pod_name);
-            return;
-        }
-        // Schedule pod
-        let node = (self.algorithm)(&pod, &self.cache);
-        match node {
-            Some(node) => {
-                let node_name = node.name.to_owned();
-                match self.bind(&pod, node).await {
-                    Ok(()) => {
-                        self.cache.handle_pod_add(pod, &node_name).await;
-                        tracing::info!("Pod {} scheduled to node {}", pod_name, node_name)
-                    },
-                    Err(e) => tracing::error!(
-                        "Failed to bind pod {} to node {}: {:#}",
-                        pod_name,
+        tracing::info!(
+            "Scheduling pod: {}, attempt {}",
+            pod_name,
+            queued.attempts + 1
+        );
+
+        let pod_group = match pod_group_name(pod) {
+            Some(group_name) => match self.check_pod_group(group_name).await {
+                Ok(pod_group) => Some(pod_group),
+                Err(reason) => {
+                    let fit_error = FitError::unresolvable(self.cache.node_states.len(), reason);
+                    self.handle_fit_error(queued, &CycleState::default(), fit_error)
+                        .await;
+                    return;
+                },
+            },
+            None => None,
+        };
+
+        let mut state = CycleState::default();
+        match self.framework.schedule(&mut state, pod, &self.cache).await {
+            Ok(node_name) => {
+                self.cache.assume_pod(pod, &node_name);
+                if let Err(e) = self.framework.run_reserve_plugins(&state, pod, &node_name) {
+                    tracing::error!(
+                        "Failed to reserve node {} for pod {}: {:#}",
                         node_name,
+                        pod_name,
                         e
-                    ),
+                    );
+                    self.cache.forget_pod(pod);
+                    self.queue.add_backoff(queued);
+                    return;
+                }
+                let waiting = WaitingPod {
+                    queued,
+                    node_name,
+                    state,
+                };
+                match pod_group {
+                    Some(pod_group) => self.wait_for_gang(&pod_group, waiting).await,
+                    None => self.bind(waiting),
+                }
+            },
+            Err(err) => {
+                if let Some(pod_group) = pod_group {
+                    // Other members can't be bound without this one
+                    self.reject_gang(
+                        pod_group.name(),
+                        "Unschedulable",
+                        format!("Pod {} can't be scheduled: {}", pod_name, err),
+                    )
+                    .await;
+                }
+                match err {
+                    ScheduleError::Unschedulable(fit_error) => {
+                        self.handle_fit_error(queued, &state, fit_error).await;
+                    },
+                    ScheduleError::Extender(e) => {
+                        tracing::error!("Failed to schedule pod {}: {:#}", pod_name, e);
+                        self.queue.add_backoff(queued);
+                    },
+                }
+            },
+        }
+    }
+
+    /// Try to preempt other pods for the pod, then put it back as unschedulable
+    async fn handle_fit_error(
+        &mut self,
+        queued: QueuedPod,
+        state: &CycleState,
+        fit_error: FitError,
+    ) {
+        let pod = &queued.pod;
+        tracing::warn!("Failed to schedule pod {}: {}", pod.name(), fit_error);
+        let nominated_node_name = self.preempt(state, pod, &fit_error).await;
+        if let Some(node_name) = &nominated_node_name {
+            tracing::info!("Node {} nominated for pod {}", node_name, pod.name());
+        }
+        if let Err(e) = self
+            .mark_unschedulable(pod, &fit_error, nominated_node_name)
+            .await
+        {
+            tracing::error!("Failed to update status of pod {}: {:#}", pod.name(), e);
+        }
+        self.queue.add_unschedulable(queued);
+    }
+
+    /// Bind the pod to the node it's assumed on in the background,
+    /// so that the next pods can be scheduled meanwhile.
+    fn bind(&self, waiting: WaitingPod) {
+        let framework = self.framework.clone();
+        let binding_tx = self.binding_tx.clone();
+        tokio::spawn(async move {
+            let result = framework
+                .run_bind_plugins(&waiting.state, &waiting.queued.pod, &waiting.node_name)
+                .await;
+            binding_tx
+                .send(BindingResult::Pod(waiting, result))
+                .await
+                .ok();
+        });
+    }
+
+    /// Bind all members of the pod group in the background
+    fn bind_gang(&self, group_name: String, min_member: u32, bound: u32, members: Vec<WaitingPod>) {
+        let framework = self.framework.clone();
+        let binding_tx = self.binding_tx.clone();
+        tokio::spawn(async move {
+            let mut results = Vec::new();
+            for waiting in members {
+                let result = framework
+                    .run_bind_plugins(&waiting.state, &waiting.queued.pod, &waiting.node_name)
+                    .await;
+                results.push((waiting, result));
+            }
+            binding_tx
+                .send(BindingResult::Gang {
+                    group_name,
+                    min_member,
+                    bound,
+                    results,
+                })
+                .await
+                .ok();
+        });
+    }
+
+    async fn handle_binding_result(&mut self, result: BindingResult) {
+        match result {
+            BindingResult::Pod(waiting, result) => {
+                self.finish_binding(waiting, result);
+            },
+            BindingResult::Gang {
+                group_name,
+                min_member,
+                bound,
+                results,
+            } => {
+                let mut scheduled = bound;
+                for (waiting, result) in results {
+                    if self.finish_binding(waiting, result) {
+                        scheduled += 1;
+                    }
+                }
+                tracing::info!("Pod group {}: {} members scheduled", group_name, scheduled);
+                let (phase, condition) = if scheduled >= min_member {
+                    (
+                        PodGroupPhase::Scheduled,
+                        Condition::new(PodGroupConditionType::Scheduled, true)
+                            .with_reason("Scheduled", format!("{} members scheduled", scheduled)),
+                    )
+                } else {
+                    (
+                        PodGroupPhase::Pending,
+                        Condition::new(PodGroupConditionType::Scheduled, false).with_reason(
+                            "BindFailed",
+                            format!("Only {}/{} members bound", scheduled, min_member),
+                        ),
+                    )
+                };
+                self.update_pod_group_status(&group_name, phase, scheduled, condition)
+                    .await;
+            },
+        }
+    }
+
+    /// Keep the pod assumed until its binding is seen if it's bound,
+    /// otherwise forget it and put it back to be retried later.
+    fn finish_binding(&mut self, waiting: WaitingPod, result: Result<()>) -> bool {
+        let WaitingPod {
+            queued,
+            node_name,
+            state,
+        } = waiting;
+        let pod = &queued.pod;
+        match result {
+            Ok(()) => {
+                tracing::info!("Pod {} scheduled to node {}", pod.name(), node_name);
+                self.cache.remove_nominated_pod(pod);
+                self.cache.finish_binding(pod);
+                true
+            },
+            Err(e) => {
+                tracing::error!(
+                    "Failed to bind pod {} to node {}: {:#}",
+                    pod.name(),
+                    node_name,
+                    e
+                );
+                self.framework
+                    .run_unreserve_plugins(&state, pod, &node_name);
+                // Not assumed any more if it's deleted while binding
+                if self.cache.is_assumed(pod) {
+                    self.cache.forget_pod(pod);
+                    self.queue.add_backoff(queued);
                 }
+                false
             },
-            None => tracing::warn!("No schedulable node found for pod {}", pod_name),
+        }
+    }
+
+    /// Get the pod group and check it has enough members to be scheduled at all
+    async fn check_pod_group(&self, name: &str) -> Result<PodGroup, String> {
+        let pod_group = self
+            .get_pod_group(name)
+            .await
+            .map_err(|e| format!("pod group {} is unavailable: {:#}", name, e))?;
+        let members = self.count_members(name, Pod::is_active).await;
+        if members < pod_group.spec.min_member {
+            return Err(format!(
+                "pod group {} has {} pod(s), less than minMember {}",
+                name, members, pod_group.spec.min_member
+            ));
+        }
+        Ok(pod_group)
+    }
+
+    async fn get_pod_group(&self, name: &str) -> Result<PodGroup> {
+        Ok(self.pod_group_api.get(name).await?)
+    }
+
+    /// Number of members of the pod group satisfying `predicate` in the pod cache
+    async fn count_members(&self, group_name: &str, predicate: impl Fn(&Pod) -> bool) -> u32 {
+        let store = self.cache.pod_cache.read().await;
+        store
+            .values()
+            .filter(|pod| pod_group_name(pod).map_or(false, |name| name == group_name))
+            .filter(|&pod| predicate(pod))
+            .count() as u32
+    }
+
+    /// Keep the assumed pod waiting until `min_member` members of its group fit,
+    /// then bind all of them.
+    async fn wait_for_gang(&mut self, pod_group: &PodGroup, waiting: WaitingPod) {
+        let group_name = pod_group.name().to_owned();
+        let bound = self
+            .count_members(&group_name, |pod| {
+                pod.spec.node_name.is_some() && pod.is_active()
+            })
+            .await;
+        let gang = self
+            .gangs
+            .entry(group_name.to_owned())
+            .or_insert_with(|| Gang::new(pod_group));
+        // Owners may shrink the group while members are waiting, e.g. as jobs complete
+        gang.min_member = pod_group.spec.min_member;
+        gang.waiting.push(waiting);
+
+        let placed = bound + gang.waiting.len() as u32;
+        if placed < gang.min_member {
+            tracing::info!(
+                "Pod group {}: {}/{} members placed, waiting for the rest",
+                group_name,
+                placed,
+                gang.min_member
+            );
+            let condition = Condition::new(PodGroupConditionType::Scheduled, false).with_reason(
+                "Waiting",
+                format!("{}/{} members placed", placed, gang.min_member),
+            );
+            self.update_pod_group_status(&group_name, PodGroupPhase::Scheduling, bound, condition)
+                .await;
+            return;
+        }
+
+        let gang = self.gangs.remove(&group_name).expect("Gang should exist");
+        self.bind_gang(group_name, gang.min_member, bound, gang.waiting);
+    }
+
+    /// Release all waiting members of the group to be retried later,
+    /// e.g. when it times out or one of the members doesn't fit.
+    async fn reject_gang(&mut self, group_name: &str, reason: &str, message: String) {
+        let gang = match self.gangs.remove(group_name) {
+            Some(gang) => gang,
+            None => return,
+        };
+        tracing::warn!("Pod group {} rejected: {}", group_name, message);
+        for waiting in gang.waiting {
+            self.framework.run_unreserve_plugins(
+                &waiting.state,
+                &waiting.queued.pod,
+                &waiting.node_name,
+            );
+            self.cache.forget_pod(&waiting.queued.pod);
+            self.queue.add_backoff(waiting.queued);
+        }
+        let bound = self
+            .count_members(group_name, |pod| {
+                pod.spec.node_name.is_some() && pod.is_active()
+            })
+            .await;
+        let condition =
+            Condition::new(PodGroupConditionType::Scheduled, false).with_reason(reason, message);
+        self.update_pod_group_status(group_name, PodGroupPhase::Pending, bound, condition)
+            .await;
+    }
+
+    /// Release gangs which have waited for too long
+    async fn expire_gangs(&mut self) {
+        let expired = self
+            .gangs
+            .iter()
+            .filter(|(_, gang)| gang.is_expired())
+            .map(|(name, _)| name.to_owned())
+            .collect::<Vec<_>>();
+        for group_name in expired {
+            self.reject_gang(
+                &group_name,
+                "Timeout",
+                "Timed out waiting for minMember pods to be placed".to_string(),
+            )
+            .await;
+        }
+    }
+
+    /// Forget a waiting pod which is deleted
+    fn remove_waiting_pod(&mut self, pod: &Pod) {
+        for gang in self.gangs.values_mut() {
+            if let Some(index) = gang
+                .waiting
+                .iter()
+                .position(|waiting| waiting.queued.pod.name() == pod.name())
+            {
+                let waiting = gang.waiting.remove(index);
+                self.framework
+                    .run_unreserve_plugins(&waiting.state, pod, &waiting.node_name);
+                self.cache.forget_pod(pod);
+            }
+        }
+        self.gangs.retain(|_, gang| !gang.waiting.is_empty());
+    }
+
+    async fn update_pod_group_status(
+        &self,
+        name: &str,
+        phase: PodGroupPhase,
+        scheduled: u32,
+        condition: Condition<PodGroupConditionType>,
+    ) {
+        let result = async {
+            let mut pod_group = self.get_pod_group(name).await?;
+            let status = pod_group.status.get_or_insert_with(Default::default);
+            let mut changed = status.conditions.set(condition);
+            if status.phase != phase || status.scheduled != scheduled {
+                status.phase = phase;
+                status.scheduled = scheduled;
+                changed = true;
+            }
+            if !changed {
+                return Ok(());
+            }
+            self.pod_group_api.replace(&pod_group).await?;
+            Ok::<_, anyhow::Error>(())
+        };
+        if let Err(e) = result.await {
+            tracing::error!("Failed to update status of pod group {}: {:#}", name, e);
         }
     }
 }
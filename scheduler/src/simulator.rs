@@ -0,0 +1,259 @@
+//! Replay a snapshot of the cluster through the scheduling algorithm,
+//! to see where pending pods would go before changing the scheduler profile.
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use resources::{
+    client::Client,
+    config::{load_or_default, scheduler::SchedulerProfile},
+    informer::{node_name_index, IndexedStore, NODE_NAME_INDEX},
+    models,
+    objects::{node::Node, pod::Pod, Object},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    cache::{Cache, NodeState},
+    framework::{CycleState, Framework},
+    queue::SchedulingQueue,
+    CONFIG, PROFILE_PATH,
+};
+
+#[derive(Args)]
+pub struct Arg {
+    /// Snapshot file of nodes and pods, taken from the API server if not specified.
+    ///
+    /// Nodes in a snapshot file are taken as ready,
+    /// as their heartbeats are outdated when it's replayed.
+    #[clap(short, long)]
+    snapshot: Option<PathBuf>,
+    /// Save the snapshot taken from the API server to the file
+    #[clap(long, conflicts_with = "snapshot")]
+    save: Option<PathBuf>,
+    /// Scheduler profile to simulate
+    #[clap(short, long, default_value = PROFILE_PATH)]
+    profile: PathBuf,
+    /// Pod files to schedule after pending pods of the scheduler in the snapshot
+    pods: Vec<PathBuf>,
+}
+
+/// Nodes and pods of the cluster at some point
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(default)]
+    nodes: Vec<Node>,
+    #[serde(default)]
+    pods: Vec<Pod>,
+}
+
+impl Snapshot {
+    async fn fetch() -> Result<Snapshot> {
+        Ok(Snapshot {
+            nodes: list("api/v1/nodes").await?,
+            pods: list("api/v1/pods").await?,
+        })
+    }
+}
+
+impl Arg {
+    /// Schedule pending pods in the order of the scheduling queue,
+    /// each placement is assumed in the cache for the next pods.
+    /// Nothing is reserved or bound, preemption and pod groups are not simulated.
+    pub async fn handle(&self) -> Result<()> {
+        let profile = load_or_default::<SchedulerProfile>(&self.profile)?;
+        // Nothing is bound, the client is only needed to build bind plugins
+        let framework = Framework::new(&profile, &Client::new((&*CONFIG).into())?)?;
+        let snapshot = self.load_snapshot().await?;
+
+        let mut pending = snapshot
+            .pods
+            .iter()
+            .filter(|pod| {
+                pod.spec.node_name.is_none()
+                    && pod.spec.scheduler_name() == profile.scheduler_name
+                    && !is_terminated(pod)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in self.pods.iter() {
+            pending.push(read_yaml::<Pod>(path)?);
+        }
+        // The same file may be given several times to simulate replicas
+        let mut taken = snapshot
+            .pods
+            .iter()
+            .filter(|pod| pod.spec.node_name.is_some())
+            .map(|pod| pod.name().to_owned())
+            .collect::<HashSet<_>>();
+        for pod in pending.iter_mut() {
+            assign_unique_name(pod, &mut taken);
+        }
+
+        let mut cache = new_cache(snapshot);
+        cache.refresh().await;
+        if self.snapshot.is_some() {
+            for node_state in cache.node_states.values_mut() {
+                node_state.is_ready = true;
+            }
+        }
+
+        let mut queue = SchedulingQueue::default();
+        for pod in pending {
+            queue.add(pod);
+        }
+        let mut placements = Vec::new();
+        let mut unschedulable = Vec::new();
+        while let Some(queued) = queue.pop() {
+            let mut pod = queued.pod;
+            let mut state = CycleState::default();
+            match framework.schedule(&mut state, &pod, &cache).await {
+                Ok(node_name) => {
+                    placements.push((pod.name().to_owned(), node_name.to_owned()));
+                    pod.spec.node_name = Some(node_name.to_owned());
+                    cache.handle_pod_add(pod, &node_name).await;
+                },
+                Err(e) => {
+                    placements.push((pod.name().to_owned(), "<none>".to_string()));
+                    unschedulable.push((pod.name().to_owned(), e.to_string()));
+                },
+            }
+        }
+
+        println!("{:<30} {:<20}", "POD", "NODE");
+        for (pod_name, node_name) in placements {
+            println!("{:<30} {:<20}", pod_name, node_name);
+        }
+
+        println!();
+        println!(
+            "{:<20} {:<6} {:<24} {:<24}",
+            "NODE", "PODS", "CPU", "MEMORY"
+        );
+        let mut node_states = cache.node_states.values().collect::<Vec<_>>();
+        node_states.sort_by(|a, b| a.name.cmp(&b.name));
+        for node_state in node_states {
+            print_utilization(node_state);
+        }
+
+        if !unschedulable.is_empty() {
+            println!();
+            println!("Unschedulable pods:");
+            for (pod_name, reason) in unschedulable {
+                println!("  {}: {}", pod_name, reason);
+            }
+        }
+        Ok(())
+    }
+
+    async fn load_snapshot(&self) -> Result<Snapshot> {
+        if let Some(path) = &self.snapshot {
+            return read_yaml(path);
+        }
+        let snapshot = Snapshot::fetch()
+            .await
+            .context("Failed to take snapshot from API server")?;
+        if let Some(path) = &self.save {
+            fs::write(path, serde_yaml::to_string(&snapshot)?)
+                .with_context(|| format!("Failed to save snapshot to {}", path.display()))?;
+        }
+        Ok(snapshot)
+    }
+}
+
+/// Build a cache of the nodes and pods bound to them, as the informers do
+fn new_cache(snapshot: Snapshot) -> Cache {
+    let mut pod_store = IndexedStore::default();
+    pod_store.add_indexer(NODE_NAME_INDEX, Arc::new(node_name_index));
+    for pod in snapshot.pods {
+        if pod.spec.node_name.is_some() && !is_terminated(&pod) {
+            pod_store.insert(pod.uri(), pod);
+        }
+    }
+    let mut node_store = IndexedStore::default();
+    for node in snapshot.nodes {
+        node_store.insert(node.uri(), node);
+    }
+    Cache::new(
+        Arc::new(RwLock::new(pod_store)),
+        Arc::new(RwLock::new(node_store)),
+    )
+}
+
+/// Name the pod uniquely among `taken`, since the queue and the cache key pods by name.
+/// Unnamed pods are named after `generateName`, duplicates get a numeric suffix.
+fn assign_unique_name(pod: &mut Pod, taken: &mut HashSet<String>) {
+    let name = &pod.metadata.name;
+    if !name.is_empty() && taken.insert(name.to_owned()) {
+        return;
+    }
+    let prefix = if name.is_empty() {
+        pod.metadata
+            .generate_name
+            .to_owned()
+            .unwrap_or_else(|| "pod-".to_string())
+    } else {
+        format!("{}-", name)
+    };
+    let name = (1..)
+        .map(|i| format!("{}{}", prefix, i))
+        .find(|name| !taken.contains(name))
+        .expect("some suffix is unused");
+    taken.insert(name.to_owned());
+    pod.metadata.name = name;
+}
+
+/// Pods written by hand may have no status yet
+fn is_terminated(pod: &Pod) -> bool {
+    pod.status.is_some() && !pod.is_active()
+}
+
+fn print_utilization(node_state: &NodeState) {
+    let cpu = format!(
+        "{}m/{}m ({}%)",
+        node_state.requested_cpu,
+        node_state.allocatable_cpu,
+        percentage(node_state.requested_cpu, node_state.allocatable_cpu)
+    );
+    let memory = format!(
+        "{}Mi/{}Mi ({}%)",
+        node_state.requested_memory >> 20,
+        node_state.allocatable_memory >> 20,
+        percentage(node_state.requested_memory, node_state.allocatable_memory)
+    );
+    println!(
+        "{:<20} {:<6} {:<24} {:<24}",
+        node_state.name,
+        node_state.pod_count(),
+        cpu,
+        memory
+    );
+}
+
+fn percentage(requested: i64, allocatable: i64) -> i64 {
+    if allocatable <= 0 {
+        return 0;
+    }
+    requested * 100 / allocatable
+}
+
+fn read_yaml<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_yaml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+async fn list<T: Serialize + DeserializeOwned>(path: &str) -> Result<Vec<T>> {
+    let res = reqwest::get(CONFIG.api_server_endpoint.join(path)?)
+        .await?
+        .json::<models::Response<Vec<T>>>()
+        .await?;
+    res.data.ok_or_else(|| anyhow!("Failed to list {}", path))
+}
@@ -15,6 +15,8 @@ curl -F "endpoints-controller-arm=@endpoints-controller-arm" http://minik8s.xyz:
 curl -F "ingress-controller-arm=@ingress-controller-arm" http://minik8s.xyz:8008/api/upload
 curl -F "podautoscaler-arm=@podautoscaler-arm" http://minik8s.xyz:8008/api/upload
 curl -F "replicaset-controller-arm=@replicaset-controller-arm" http://minik8s.xyz:8008/api/upload
+curl -F "nodelifecycle-controller-arm=@nodelifecycle-controller-arm" http://minik8s.xyz:8008/api/upload
+curl -F "descheduler-arm=@descheduler-arm" http://minik8s.xyz:8008/api/upload
 curl -F "rkube-proxy-arm=@rkube-proxy-arm" http://minik8s.xyz:8008/api/upload
 curl -F "rkubectl-arm=@rkubectl-arm" http://minik8s.xyz:8008/api/upload
 curl -F "rkubelet-arm=@rkubelet-arm" http://minik8s.xyz:8008/api/upload
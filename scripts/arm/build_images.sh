@@ -4,6 +4,8 @@ docker build -t minik8s.xyz/endpoints-controller-arm:latest ./docker/endpoints-c
 docker build -t minik8s.xyz/ingress-controller-arm:latest ./docker/ingress-controller
 docker build -t minik8s.xyz/podautoscaler-arm:latest ./docker/podautoscaler
 docker build -t minik8s.xyz/replicaset-controller-arm:latest ./docker/replicaset-controller
+docker build -t minik8s.xyz/nodelifecycle-controller-arm:latest ./docker/nodelifecycle-controller
+docker build -t minik8s.xyz/descheduler-arm:latest ./docker/descheduler
 docker build -t minik8s.xyz/scheduler-arm:latest ./docker/scheduler
 docker build -t minik8s.xyz/gpujob-controller-arm:latest ./docker/gpujob-controller
 docker build -t minik8s.xyz/serverless-router-arm:latest ./docker/serverless-router
@@ -14,6 +16,8 @@ docker push minik8s.xyz/endpoints-controller-arm:latest
 docker push minik8s.xyz/ingress-controller-arm:latest
 docker push minik8s.xyz/podautoscaler-arm:latest
 docker push minik8s.xyz/replicaset-controller-arm:latest
+docker push minik8s.xyz/nodelifecycle-controller-arm:latest
+docker push minik8s.xyz/descheduler-arm:latest
 docker push minik8s.xyz/scheduler-arm:latest
 docker push minik8s.xyz/gpujob-controller-arm:latest
 docker push minik8s.xyz/serverless-router-arm:latest
@@ -4,6 +4,8 @@ docker pull minik8s.xyz/endpoints-controller-arm:latest
 docker pull minik8s.xyz/ingress-controller-arm:latest
 docker pull minik8s.xyz/podautoscaler-arm:latest
 docker pull minik8s.xyz/replicaset-controller-arm:latest
+docker pull minik8s.xyz/nodelifecycle-controller-arm:latest
+docker pull minik8s.xyz/descheduler-arm:latest
 docker pull minik8s.xyz/scheduler-arm:latest
 docker pull minik8s.xyz/gpujob-controller-arm:latest
 docker pull minik8s.xyz/serverless-router-arm:latest
@@ -11,11 +11,13 @@ use hyper::{
 use prometheus::{opts, register_counter_vec, CounterVec};
 use reqwest::Url;
 use resources::{
+    client::{Api, Client},
+    informer::{EventHandler, Informer, ResyncHandler},
     models::NodeConfig,
     objects::{function::Function, service::Service},
 };
 
-use crate::{route::router, utils::create_informer};
+use crate::route::router;
 
 lazy_static! {
     static ref CONFIG: NodeConfig = {
@@ -39,7 +41,6 @@ lazy_static! {
 }
 
 mod route;
-mod utils;
 mod workflow;
 
 #[tokio::main]
@@ -47,12 +48,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     tracing_subscriber::fmt::init();
     tracing::info!("Serverless router started");
 
-    // let (tx, mut rx) = mpsc::channel::<Notification>(16);
-    let func_informer = create_informer::<Function>("functions");
+    let client = Client::new((&*CONFIG).into())?;
+    let func_informer = Informer::new(
+        Api::<Function>::new(client.clone(), "functions").lister_watcher(),
+        EventHandler::noop(),
+        ResyncHandler::noop(),
+    );
     let func_store = func_informer.get_store();
     let func_informer_handler = tokio::spawn(async move { func_informer.run().await });
 
-    let svc_informer = create_informer::<Service>("services");
+    let svc_informer = Informer::new(
+        Api::<Service>::new(client, "services").lister_watcher(),
+        EventHandler::noop(),
+        ResyncHandler::noop(),
+    );
     let svc_store = svc_informer.get_store();
     let svc_informer_handler = tokio::spawn(async move { svc_informer.run().await });
 